@@ -0,0 +1,125 @@
+use leptos::prelude::*;
+use megafactory_sql_types::configurator::{CostEstimate, CostOptions, PricingModel};
+
+fn usd(amount: f64) -> String {
+    format!("${amount:.0}")
+}
+
+/// Live monthly cost estimate of the deployment being edited, with the
+/// pricing model and stored data size as inputs.
+#[component]
+pub fn CostPanel(
+    estimate: Signal<CostEstimate>,
+    options: ReadSignal<CostOptions>,
+    set_options: WriteSignal<CostOptions>,
+    /// Whether KEDA is enabled, so the CPU workers' range applies.
+    keda_enabled: Signal<bool>,
+) -> impl IntoView {
+    view! {
+        <div class="cost-panel">
+            <h3>"Estimated Cost"</h3>
+            <div class="form-row">
+                <div class="form-field">
+                    <label>"Pricing"</label>
+                    <select on:change=move |ev| {
+                        let value = event_target_value(&ev);
+                        if let Some(pricing) = PricingModel::all().iter().find(|p| p.label() == value) {
+                            set_options.update(|o| o.pricing = *pricing);
+                        }
+                    }>
+                        {PricingModel::all().iter().map(|pricing| {
+                            let pricing = *pricing;
+                            view! {
+                                <option
+                                    value=pricing.label()
+                                    selected=move || options.with(|o| o.pricing == pricing)
+                                >
+                                    {pricing.label()}
+                                </option>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </select>
+                </div>
+                <div class="form-field">
+                    <label>"Stored Data (GB)"</label>
+                    <input
+                        type="number"
+                        min="0"
+                        prop:value=move || options.with(|o| o.storage_gb.to_string())
+                        on:input=move |ev| {
+                            if let Ok(gb) = event_target_value(&ev).parse() {
+                                set_options.update(|o| o.storage_gb = gb);
+                            }
+                        }
+                    />
+                </div>
+            </div>
+
+            {move || {
+                let estimate = estimate.get();
+                let keda = keda_enabled.get();
+                let unpriced: Vec<String> = estimate
+                    .unpriced_pools()
+                    .map(|p| format!("{} ({})", p.pool, p.instance_type))
+                    .collect();
+                view! {
+                    <table class="cost-table">
+                        <thead>
+                            <tr>
+                                <th>"Pool"</th>
+                                <th>"Instance"</th>
+                                <th>"$/hr"</th>
+                                <th>"Replicas"</th>
+                                <th>"$/month"</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {estimate.pools.into_iter().map(|pool| {
+                                let price = pool
+                                    .price_per_hour_usd
+                                    .map(|p| format!("${p:.3}"))
+                                    .unwrap_or_else(|| "—".to_string());
+                                let replicas = if pool.min_replicas == pool.max_replicas {
+                                    pool.replicas.to_string()
+                                } else {
+                                    format!("{} ({}–{})", pool.replicas, pool.min_replicas, pool.max_replicas)
+                                };
+                                view! {
+                                    <tr>
+                                        <td>{pool.pool}</td>
+                                        <td>{pool.instance_type}</td>
+                                        <td>{price}</td>
+                                        <td>{replicas}</td>
+                                        <td>{usd(pool.monthly_usd)}</td>
+                                    </tr>
+                                }
+                            }).collect::<Vec<_>>()}
+                            <tr>
+                                <td>"Storage"</td>
+                                <td colspan="3"></td>
+                                <td>{usd(estimate.storage_monthly_usd)}</td>
+                            </tr>
+                        </tbody>
+                    </table>
+                    <div class="cost-total">
+                        <span class="cost-total-value">{format!("{} / month", usd(estimate.monthly_usd))}</span>
+                        {keda.then(|| view! {
+                            <span class="cost-range">
+                                {format!(
+                                    "KEDA range: {} – {}",
+                                    usd(estimate.min_monthly_usd),
+                                    usd(estimate.max_monthly_usd),
+                                )}
+                            </span>
+                        })}
+                    </div>
+                    {(!unpriced.is_empty()).then(|| view! {
+                        <p class="cost-note">
+                            {format!("Not priced in the catalog: {}", unpriced.join(", "))}
+                        </p>
+                    })}
+                }
+            }}
+        </div>
+    }
+}
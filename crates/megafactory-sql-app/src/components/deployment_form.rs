@@ -0,0 +1,375 @@
+use leptos::prelude::*;
+use megafactory_sql_types::configurator::{
+    find_instance, AcceleratorType, DeploymentConfig, InstanceFamily, IssueSeverity,
+    PoolAccelerator, StorageBackend, ValidationIssue, WorkerPoolConfig,
+};
+
+/// A worker pool of [`DeploymentConfig`]; the CPU pool is always present.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PoolKind {
+    Cpu,
+    Gpu,
+    Fpga,
+    Npu,
+}
+
+impl PoolKind {
+    const ALL: [PoolKind; 4] = [Self::Cpu, Self::Gpu, Self::Fpga, Self::Npu];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Cpu => "CPU Workers",
+            Self::Gpu => "GPU Workers",
+            Self::Fpga => "FPGA Workers",
+            Self::Npu => "NPU Workers",
+        }
+    }
+
+    /// Field path of the pool in [`ValidationIssue::field`].
+    fn field(self) -> &'static str {
+        match self {
+            Self::Cpu => "cpu_worker_pool",
+            Self::Gpu => "gpu_worker_pool",
+            Self::Fpga => "fpga_worker_pool",
+            Self::Npu => "npu_worker_pool",
+        }
+    }
+
+    fn accelerator_type(self) -> AcceleratorType {
+        match self {
+            Self::Cpu => AcceleratorType::None,
+            Self::Gpu => AcceleratorType::Gpu,
+            Self::Fpga => AcceleratorType::Fpga,
+            Self::Npu => AcceleratorType::Npu,
+        }
+    }
+
+    fn pool(self, config: &DeploymentConfig) -> Option<&WorkerPoolConfig> {
+        match self {
+            Self::Cpu => Some(&config.cpu_worker_pool),
+            Self::Gpu => config.gpu_worker_pool.as_ref(),
+            Self::Fpga => config.fpga_worker_pool.as_ref(),
+            Self::Npu => config.npu_worker_pool.as_ref(),
+        }
+    }
+
+    fn pool_mut(self, config: &mut DeploymentConfig) -> Option<&mut WorkerPoolConfig> {
+        match self {
+            Self::Cpu => Some(&mut config.cpu_worker_pool),
+            Self::Gpu => config.gpu_worker_pool.as_mut(),
+            Self::Fpga => config.fpga_worker_pool.as_mut(),
+            Self::Npu => config.npu_worker_pool.as_mut(),
+        }
+    }
+
+    /// Add or remove an optional pool; the CPU pool can't be removed.
+    fn set(self, config: &mut DeploymentConfig, pool: Option<WorkerPoolConfig>) {
+        match self {
+            Self::Cpu => {}
+            Self::Gpu => config.gpu_worker_pool = pool,
+            Self::Fpga => config.fpga_worker_pool = pool,
+            Self::Npu => config.npu_worker_pool = pool,
+        }
+    }
+}
+
+/// Editor for a deployment config: cluster name, storage, KEDA and the
+/// worker pools, with instance types from the provider's catalog. Each
+/// validation issue is shown next to the pool (or settings) it is about.
+#[component]
+pub fn DeploymentForm(
+    config: ReadSignal<DeploymentConfig>,
+    set_config: WriteSignal<DeploymentConfig>,
+    families: Signal<Vec<InstanceFamily>>,
+    issues: Signal<Vec<ValidationIssue>>,
+) -> impl IntoView {
+    let general_issues = Signal::derive(move || {
+        issues.with(|issues| {
+            issues
+                .iter()
+                .filter(|i| !PoolKind::ALL.iter().any(|k| i.field.starts_with(k.field())))
+                .cloned()
+                .collect()
+        })
+    });
+
+    view! {
+        <div class="deployment-form">
+            <h3>"Deployment"</h3>
+            <div class="form-row">
+                <div class="form-field">
+                    <label>"Cluster Name"</label>
+                    <input
+                        type="text"
+                        prop:value=move || config.with(|c| c.cluster_name.clone())
+                        on:input=move |ev| {
+                            set_config.update(|c| c.cluster_name = event_target_value(&ev))
+                        }
+                    />
+                </div>
+                <div class="form-field">
+                    <label>"Storage Backend"</label>
+                    <select on:change=move |ev| {
+                        let value = event_target_value(&ev);
+                        if let Some(backend) = StorageBackend::all().iter().find(|b| b.label() == value) {
+                            set_config.update(|c| c.storage_backend = *backend);
+                        }
+                    }>
+                        {StorageBackend::all().iter().map(|backend| {
+                            let backend = *backend;
+                            view! {
+                                <option
+                                    value=backend.label()
+                                    selected=move || config.with(|c| c.storage_backend == backend)
+                                >
+                                    {backend.label()}
+                                </option>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </select>
+                </div>
+                <div class="form-field">
+                    <label>"KEDA Autoscaling"</label>
+                    <input
+                        type="checkbox"
+                        prop:checked=move || config.with(|c| c.keda_enabled)
+                        on:change=move |ev| {
+                            set_config.update(|c| c.keda_enabled = event_target_checked(&ev))
+                        }
+                    />
+                </div>
+            </div>
+            <IssueList issues=general_issues />
+
+            {PoolKind::ALL.into_iter().map(|kind| view! {
+                <PoolEditor
+                    kind=kind
+                    config=config
+                    set_config=set_config
+                    families=families
+                    issues=Signal::derive(move || issues.with(|issues| {
+                        issues.iter().filter(|i| i.field.starts_with(kind.field())).cloned().collect()
+                    }))
+                />
+            }).collect::<Vec<_>>()}
+        </div>
+    }
+}
+
+/// One worker pool: enable toggle (accelerator pools), instance type,
+/// replicas and accelerator settings.
+#[component]
+fn PoolEditor(
+    kind: PoolKind,
+    config: ReadSignal<DeploymentConfig>,
+    set_config: WriteSignal<DeploymentConfig>,
+    families: Signal<Vec<InstanceFamily>>,
+    /// Validation issues of this pool.
+    issues: Signal<Vec<ValidationIssue>>,
+) -> impl IntoView {
+    let enabled = move || config.with(|c| kind.pool(c).is_some());
+    let update_pool = move |f: &dyn Fn(&mut WorkerPoolConfig)| {
+        set_config.update(|c| {
+            if let Some(pool) = kind.pool_mut(c) {
+                f(pool);
+            }
+        });
+    };
+
+    // Instance types this pool can run on: (name, label)
+    let options = move || {
+        families.with(|families| {
+            families
+                .iter()
+                .filter(|f| f.accelerator_type == kind.accelerator_type())
+                .flat_map(|f| &f.instance_types)
+                .map(|t| {
+                    let price = t
+                        .price_per_hour_usd
+                        .map(|p| format!(" — ${p:.2}/hr"))
+                        .unwrap_or_default();
+                    (t.name.clone(), format!("{}{price}", t.name))
+                })
+                .collect::<Vec<_>>()
+        })
+    };
+
+    let on_toggle = move |ev: leptos::ev::Event| {
+        let pool = event_target_checked(&ev)
+            .then(|| {
+                families.with(|families| {
+                    families
+                        .iter()
+                        .filter(|f| f.accelerator_type == kind.accelerator_type())
+                        .flat_map(|f| &f.instance_types)
+                        .next()
+                        .map(|instance| WorkerPoolConfig::for_instance(instance, 1))
+                })
+            })
+            .flatten();
+        set_config.update(|c| kind.set(c, pool));
+    };
+
+    let on_instance = move |ev: leptos::ev::Event| {
+        let name = event_target_value(&ev);
+        // A new instance type brings its own accelerators; MIG starts off
+        let accelerator = families.with(|families| {
+            find_instance(families, &name).and_then(|(_, t)| PoolAccelerator::for_instance(t))
+        });
+        update_pool(&|pool| {
+            pool.instance_type = name.clone();
+            pool.accelerator = accelerator.clone();
+        });
+    };
+
+    let pool_value = move |f: fn(&WorkerPoolConfig) -> i32| {
+        move || config.with(|c| kind.pool(c).map(f).unwrap_or_default())
+    };
+    // MIG profiles of the selected GPU instance type
+    let mig_profiles = move || {
+        let name = config.with(|c| kind.pool(c).map(|p| p.instance_type.clone()))?;
+        families.with(|families| {
+            let (_, instance) = find_instance(families, &name)?;
+            let detail = instance.accelerator.as_ref()?;
+            detail.supports_mig.then(|| detail.mig_profiles.clone())
+        })
+    };
+
+    view! {
+        <div class="pool-editor">
+            <div class="pool-editor-header">
+                <h4>{kind.label()}</h4>
+                {(kind != PoolKind::Cpu).then(|| view! {
+                    <label class="pool-toggle">
+                        <input
+                            type="checkbox"
+                            prop:checked=enabled
+                            // Nothing to enable when the catalog has no instance type for it
+                            disabled=move || !enabled() && options().is_empty()
+                            on:change=on_toggle
+                        />
+                        "Enabled"
+                    </label>
+                })}
+            </div>
+            <Show when=enabled>
+                <div class="form-row">
+                    <div class="form-field">
+                        <label>"Instance Type"</label>
+                        <select on:change=on_instance>
+                            {move || {
+                                let current = config
+                                    .with(|c| kind.pool(c).map(|p| p.instance_type.clone()))
+                                    .unwrap_or_default();
+                                let mut options = options();
+                                // Keep a type missing from the catalog selectable
+                                if !options.iter().any(|(name, _)| *name == current) {
+                                    options.insert(0, (current.clone(), format!("{current} (not in catalog)")));
+                                }
+                                options.into_iter().map(|(name, label)| {
+                                    let selected = name == current;
+                                    view! { <option value=name selected=selected>{label}</option> }
+                                }).collect::<Vec<_>>()
+                            }}
+                        </select>
+                    </div>
+                    <NumberField
+                        label="Replicas"
+                        value=Signal::derive(pool_value(|p| p.replicas))
+                        on_change=Callback::new(move |n: i32| update_pool(&|p| p.replicas = n))
+                    />
+                    <NumberField
+                        label="Min"
+                        value=Signal::derive(pool_value(|p| p.min_replicas))
+                        on_change=Callback::new(move |n: i32| update_pool(&|p| p.min_replicas = n))
+                    />
+                    <NumberField
+                        label="Max"
+                        value=Signal::derive(pool_value(|p| p.max_replicas))
+                        on_change=Callback::new(move |n: i32| update_pool(&|p| p.max_replicas = n))
+                    />
+                    {(kind != PoolKind::Cpu).then(|| view! {
+                        <NumberField
+                            label="Accelerators / Pod"
+                            value=Signal::derive(move || config.with(|c| {
+                                kind.pool(c)
+                                    .and_then(|p| p.accelerator.as_ref())
+                                    .map(|a| a.count_per_pod as i32)
+                                    .unwrap_or_default()
+                            }))
+                            on_change=Callback::new(move |n: i32| update_pool(&|p| {
+                                if let Some(accel) = p.accelerator.as_mut() {
+                                    accel.count_per_pod = n.max(0) as u32;
+                                }
+                            }))
+                        />
+                    })}
+                    {move || mig_profiles().map(|profiles| view! {
+                        <div class="form-field">
+                            <label>"MIG Profile"</label>
+                            <select on:change=move |ev| {
+                                let value = event_target_value(&ev);
+                                update_pool(&|p| {
+                                    if let Some(accel) = p.accelerator.as_mut() {
+                                        accel.mig_profile = (!value.is_empty()).then(|| value.clone());
+                                    }
+                                });
+                            }>
+                                <option value="">"None (whole GPU)"</option>
+                                {profiles.into_iter().map(|profile| {
+                                    let selected = {
+                                        let profile = profile.clone();
+                                        move || config.with(|c| {
+                                            kind.pool(c)
+                                                .and_then(|p| p.accelerator.as_ref())
+                                                .and_then(|a| a.mig_profile.as_deref())
+                                                == Some(profile.as_str())
+                                        })
+                                    };
+                                    view! { <option value=profile.clone() selected=selected>{profile.clone()}</option> }
+                                }).collect::<Vec<_>>()}
+                            </select>
+                        </div>
+                    })}
+                </div>
+                <IssueList issues=issues />
+            </Show>
+        </div>
+    }
+}
+
+/// Validation issues, errors first.
+#[component]
+fn IssueList(issues: Signal<Vec<ValidationIssue>>) -> impl IntoView {
+    view! {
+        <ul class="validation-issues">
+            {move || issues.get().into_iter().map(|issue| {
+                let class = match issue.severity {
+                    IssueSeverity::Error => "validation-issue validation-issue--error",
+                    IssueSeverity::Warning => "validation-issue validation-issue--warning",
+                };
+                view! { <li class=class title=issue.field>{issue.message}</li> }
+            }).collect::<Vec<_>>()}
+        </ul>
+    }
+}
+
+/// Labelled integer input.
+#[component]
+fn NumberField(label: &'static str, value: Signal<i32>, on_change: Callback<i32>) -> impl IntoView {
+    view! {
+        <div class="form-field">
+            <label>{label}</label>
+            <input
+                type="number"
+                min="0"
+                prop:value=move || value.get().to_string()
+                on:input=move |ev| {
+                    if let Ok(n) = event_target_value(&ev).parse() {
+                        on_change.run(n);
+                    }
+                }
+            />
+        </div>
+    }
+}
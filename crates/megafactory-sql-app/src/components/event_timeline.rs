@@ -0,0 +1,133 @@
+use leptos::prelude::*;
+use megafactory_sql_types::ws::{EventSeverity, K8sEvent};
+
+use crate::state::k8s::use_k8s_state;
+
+/// Minimum severities offered in the filter.
+const SEVERITY_OPTIONS: [EventSeverity; 3] = [
+    EventSeverity::Info,
+    EventSeverity::Warning,
+    EventSeverity::Error,
+];
+
+/// Cluster events, newest first, filterable by object and minimum severity.
+#[component]
+pub fn EventTimeline() -> impl IntoView {
+    let (k8s_state, _) = use_k8s_state();
+    // Empty means every object
+    let (object_filter, set_object_filter) = signal(String::new());
+    let (min_severity, set_min_severity) = signal(EventSeverity::Info);
+
+    let objects = Memo::new(move |_| {
+        let mut names: Vec<String> = k8s_state.with(|s| {
+            s.recent_events
+                .iter()
+                .map(|e| e.pod_name.clone())
+                .filter(|name| !name.is_empty())
+                .collect()
+        });
+        names.sort();
+        names.dedup();
+        names
+    });
+
+    let filtered = move || {
+        let object = object_filter.get();
+        let min = min_severity.get();
+        k8s_state.with(|s| {
+            s.recent_events
+                .iter()
+                .filter(|e| e.severity >= min && (object.is_empty() || e.pod_name == object))
+                .cloned()
+                .collect::<Vec<_>>()
+        })
+    };
+
+    view! {
+        <div class="k8s-events">
+            <div class="k8s-events-header">
+                <h3>"Events"</h3>
+                <select
+                    class="log-select"
+                    on:change=move |ev| set_object_filter.set(event_target_value(&ev))
+                >
+                    <option value="" selected=move || object_filter.get().is_empty()>"All objects"</option>
+                    {move || objects.get().into_iter().map(|name| {
+                        let selected = {
+                            let name = name.clone();
+                            move || object_filter.get() == name
+                        };
+                        view! { <option value=name.clone() selected=selected>{name.clone()}</option> }
+                    }).collect::<Vec<_>>()}
+                </select>
+                <select
+                    class="log-select"
+                    on:change=move |ev| {
+                        let value = event_target_value(&ev);
+                        if let Some(severity) = SEVERITY_OPTIONS.into_iter().find(|s| s.label() == value) {
+                            set_min_severity.set(severity);
+                        }
+                    }
+                >
+                    {SEVERITY_OPTIONS.into_iter().map(|severity| {
+                        let label = if severity == EventSeverity::Info {
+                            "All severities".to_string()
+                        } else {
+                            format!("{} and above", severity.label())
+                        };
+                        view! {
+                            <option value=severity.label() selected=move || min_severity.get() == severity>{label}</option>
+                        }
+                    }).collect::<Vec<_>>()}
+                </select>
+            </div>
+            {move || {
+                let events = filtered();
+                if events.is_empty() {
+                    view! { <p class="k8s-events-empty">"No events"</p> }.into_any()
+                } else {
+                    view! {
+                        <ul class="k8s-event-list">
+                            {events.into_iter().map(|ev| view! { <TimelineEntry event=ev /> }).collect::<Vec<_>>()}
+                        </ul>
+                    }.into_any()
+                }
+            }}
+        </div>
+    }
+}
+
+#[component]
+fn TimelineEntry(event: K8sEvent) -> impl IntoView {
+    let class = format!(
+        "k8s-event k8s-event--{}",
+        event.severity.label().to_lowercase()
+    );
+    let time = event
+        .last_timestamp
+        .map(|t| t.format("%H:%M:%S").to_string())
+        .unwrap_or_default();
+    let reason = if event.reason.is_empty() {
+        event.event_type
+    } else {
+        event.reason
+    };
+    let object = if event.object_kind.is_empty() {
+        event.pod_name
+    } else {
+        format!("{}/{}", event.object_kind, event.pod_name)
+    };
+
+    view! {
+        <li class=class>
+            <span class="k8s-event-time">{time}</span>
+            <span class="k8s-event-severity">{event.severity.label()}</span>
+            <span class="k8s-event-pod">{object}</span>
+            <span class="k8s-event-type">{reason}</span>
+            <span class="k8s-event-message">{event.message}</span>
+            {(event.count > 1).then(|| view! {
+                <span class="k8s-event-count">{format!("×{}", event.count)}</span>
+            })}
+        </li>
+    }
+}
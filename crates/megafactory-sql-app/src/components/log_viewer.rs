@@ -0,0 +1,214 @@
+use leptos::prelude::*;
+use megafactory_sql_types::logs::{LogChunk, LogLevel, LogQuery};
+
+use crate::log_stream::{stream_logs, LogStreamHandle};
+
+/// Lines kept in the viewer; older lines are dropped as new ones arrive.
+const MAX_LINES: usize = 5_000;
+
+/// Tail sizes offered in the toolbar; 0 means the whole log.
+const TAIL_OPTIONS: [i64; 4] = [100, 500, 1_000, 0];
+
+/// "Since" options in the toolbar: (seconds, label); 0 means no limit.
+const SINCE_OPTIONS: [(i64, &str); 5] = [
+    (0, "All time"),
+    (300, "Last 5m"),
+    (3_600, "Last 1h"),
+    (21_600, "Last 6h"),
+    (86_400, "Last 24h"),
+];
+
+#[derive(Debug, Clone)]
+struct LogLine {
+    level: Option<LogLevel>,
+    text: String,
+}
+
+impl LogLine {
+    fn new(text: String) -> Self {
+        Self {
+            level: LogLevel::detect(&text),
+            text,
+        }
+    }
+}
+
+/// Streaming log viewer for one pod, with search, level highlighting and
+/// pause/resume. Changing the follow/tail/previous/since options reopens
+/// the stream.
+#[component]
+pub fn LogViewer(
+    pod: String,
+    /// Called when the viewer's close button is clicked.
+    on_close: Callback<()>,
+) -> impl IntoView {
+    let (follow, set_follow) = signal(true);
+    let (tail_lines, set_tail_lines) = signal(500i64);
+    let (previous, set_previous) = signal(false);
+    let (since_seconds, set_since_seconds) = signal(0i64);
+    let (search, set_search) = signal(String::new());
+    let (paused, set_paused) = signal(false);
+    let (streaming, set_streaming) = signal(false);
+    let (error, set_error) = signal(Option::<String>::None);
+    let (lines, set_lines) = signal(Vec::<LogLine>::new());
+    // Lines received while paused, shown on resume
+    let (pending, set_pending) = signal(Vec::<LogLine>::new());
+    let handle = StoredValue::new_local(Option::<LogStreamHandle>::None);
+
+    let push_lines = move |new: Vec<LogLine>| {
+        set_lines.update(|lines| {
+            lines.extend(new);
+            let excess = lines.len().saturating_sub(MAX_LINES);
+            lines.drain(..excess);
+        });
+    };
+
+    let pod_name = pod.clone();
+    Effect::new(move || {
+        let query = LogQuery {
+            follow: follow.get(),
+            tail_lines: Some(tail_lines.get()).filter(|n| *n > 0),
+            previous: previous.get(),
+            since_seconds: Some(since_seconds.get()).filter(|s| *s > 0),
+            ..LogQuery::default()
+        };
+        let stream = LogStreamHandle::new();
+        if let Some(old) = handle.get_value() {
+            old.abort();
+        }
+        handle.set_value(Some(stream.clone()));
+        set_lines.set(Vec::new());
+        set_pending.set(Vec::new());
+        set_error.set(None);
+        set_streaming.set(true);
+
+        let pod = pod_name.clone();
+        leptos::task::spawn_local(async move {
+            let outcome = stream_logs(&pod, &query, &stream, |chunk| match chunk {
+                LogChunk::Line { text } if paused.get_untracked() => {
+                    set_pending.update(|p| p.push(LogLine::new(text)));
+                }
+                LogChunk::Line { text } => push_lines(vec![LogLine::new(text)]),
+                LogChunk::Error { message } => set_error.set(Some(message)),
+            })
+            .await;
+            if let Err(e) = outcome {
+                set_error.set(Some(e));
+            }
+            set_streaming.set(false);
+        });
+    });
+
+    on_cleanup(move || {
+        if let Some(stream) = handle.get_value() {
+            stream.abort();
+        }
+    });
+
+    let toggle_pause = move |_| {
+        if paused.get_untracked() {
+            let held = pending.get_untracked();
+            set_pending.set(Vec::new());
+            push_lines(held);
+        }
+        set_paused.update(|p| *p = !*p);
+    };
+
+    let visible_lines = move || {
+        let needle = search.get().to_lowercase();
+        lines.with(|lines| {
+            lines
+                .iter()
+                .filter(|l| needle.is_empty() || l.text.to_lowercase().contains(&needle))
+                .cloned()
+                .collect::<Vec<_>>()
+        })
+    };
+
+    view! {
+        <div class="log-viewer">
+            <div class="log-viewer-header">
+                <h3>{format!("Logs: {pod}")}</h3>
+                <span class="log-viewer-state">
+                    {move || match (streaming.get(), paused.get()) {
+                        (true, true) => format!("Paused ({} new)", pending.with(Vec::len)),
+                        (true, false) if follow.get() => "Following".to_string(),
+                        (true, false) => "Loading...".to_string(),
+                        (false, _) => "Ended".to_string(),
+                    }}
+                </span>
+                <button class="btn btn-sm" on:click=move |_| on_close.run(())>"Close"</button>
+            </div>
+
+            <div class="log-viewer-toolbar">
+                <input
+                    type="search"
+                    class="log-search"
+                    placeholder="Search logs..."
+                    prop:value=move || search.get()
+                    on:input=move |ev| set_search.set(event_target_value(&ev))
+                />
+                <select
+                    class="log-select"
+                    on:change=move |ev| {
+                        if let Ok(n) = event_target_value(&ev).parse() {
+                            set_tail_lines.set(n);
+                        }
+                    }
+                >
+                    {TAIL_OPTIONS.iter().map(|&n| {
+                        let label = if n == 0 { "All lines".to_string() } else { format!("Last {n} lines") };
+                        view! {
+                            <option value=n.to_string() selected=move || tail_lines.get() == n>{label}</option>
+                        }
+                    }).collect::<Vec<_>>()}
+                </select>
+                <select
+                    class="log-select"
+                    on:change=move |ev| {
+                        if let Ok(s) = event_target_value(&ev).parse() {
+                            set_since_seconds.set(s);
+                        }
+                    }
+                >
+                    {SINCE_OPTIONS.iter().map(|&(secs, label)| {
+                        view! {
+                            <option value=secs.to_string() selected=move || since_seconds.get() == secs>{label}</option>
+                        }
+                    }).collect::<Vec<_>>()}
+                </select>
+                <label class="log-toggle">
+                    <input
+                        type="checkbox"
+                        prop:checked=move || follow.get()
+                        on:change=move |ev| set_follow.set(event_target_checked(&ev))
+                    />
+                    "Follow"
+                </label>
+                <label class="log-toggle" title="Logs of the container before its last restart">
+                    <input
+                        type="checkbox"
+                        prop:checked=move || previous.get()
+                        on:change=move |ev| set_previous.set(event_target_checked(&ev))
+                    />
+                    "Previous"
+                </label>
+                <button class="btn btn-sm" on:click=toggle_pause>
+                    {move || if paused.get() { "Resume" } else { "Pause" }}
+                </button>
+            </div>
+
+            {move || error.get().map(|e| view! { <div class="log-error">{e}</div> })}
+
+            <pre class="log-lines">
+                {move || visible_lines().into_iter().map(|line| {
+                    let class = match line.level {
+                        Some(level) => format!("log-line {}", level.css_class()),
+                        None => "log-line".to_string(),
+                    };
+                    view! { <div class=class>{line.text}</div> }
+                }).collect::<Vec<_>>()}
+            </pre>
+        </div>
+    }
+}
@@ -1,7 +1,11 @@
 pub mod auto_refresh;
 pub mod chart;
 pub mod codemirror;
+pub mod cost_panel;
+pub mod deployment_form;
+pub mod event_timeline;
 pub mod keyboard;
+pub mod log_viewer;
 pub mod modal;
 pub mod pod_card;
 pub mod query_history;
@@ -11,5 +15,7 @@ pub mod scaling_panel;
 pub mod schema_tree;
 pub mod sidebar;
 pub mod storage_panel;
+pub mod suggest_panel;
 pub mod tab_bar;
+pub mod terminal;
 pub mod toast;
@@ -1,9 +1,20 @@
 use leptos::prelude::*;
-use megafactory_sql_types::k8s::PodInfo;
+use megafactory_sql_types::k8s::{PodAction, PodInfo};
 
 /// Card displaying a single pod's status and resource usage.
 #[component]
-pub fn PodCard(pod: PodInfo) -> impl IntoView {
+pub fn PodCard(
+    pod: PodInfo,
+    /// Shows a "Logs" button that calls this with the pod name.
+    #[prop(optional)]
+    on_logs: Option<Callback<String>>,
+    /// Shows an "Exec" button that calls this with the pod name.
+    #[prop(optional)]
+    on_exec: Option<Callback<String>>,
+    /// Shows "Delete" (and "Drain" on workers) buttons that call this.
+    #[prop(optional)]
+    on_action: Option<Callback<PodAction>>,
+) -> impl IntoView {
     let status_class = match pod.status.as_str() {
         "Running" => "pod-status--running",
         "Pending" => "pod-status--pending",
@@ -26,6 +37,45 @@ pub fn PodCard(pod: PodInfo) -> impl IntoView {
             <div class="pod-card-footer">
                 <span class="pod-restarts">{format!("Restarts: {}", pod.restart_count)}</span>
                 <span class="pod-age">{pod.age_display()}</span>
+                {on_logs.map(|on_logs| {
+                    let name = pod.name.clone();
+                    view! {
+                        <button class="btn btn-sm" on:click=move |_| on_logs.run(name.clone())>
+                            "Logs"
+                        </button>
+                    }
+                })}
+                {on_exec.map(|on_exec| {
+                    let name = pod.name.clone();
+                    view! {
+                        <button class="btn btn-sm" on:click=move |_| on_exec.run(name.clone())>
+                            "Exec"
+                        </button>
+                    }
+                })}
+                {on_action.map(|on_action| {
+                    let name = pod.name.clone();
+                    let drain = (pod.role == "worker").then(|| {
+                        let name = name.clone();
+                        view! {
+                            <button
+                                class="btn btn-sm"
+                                on:click=move |_| on_action.run(PodAction::DrainWorker { pod: name.clone() })
+                            >
+                                "Drain"
+                            </button>
+                        }
+                    });
+                    view! {
+                        {drain}
+                        <button
+                            class="btn btn-sm btn-danger"
+                            on:click=move |_| on_action.run(PodAction::DeletePod { pod: name.clone() })
+                        >
+                            "Delete"
+                        </button>
+                    }
+                })}
             </div>
         </div>
     }
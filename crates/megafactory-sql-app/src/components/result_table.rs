@@ -4,78 +4,100 @@ use megafactory_sql_types::toast::ToastLevel;
 
 use crate::state::toast::{push_toast, use_toast_write};
 
+/// Maximum rows rendered in the table; exports include every retained row.
+const MAX_DISPLAY_ROWS: usize = 1000;
+
 /// Result table for query output with CSV/JSON export.
+///
+/// Rows are appended in place as a streamed result grows. Rows are keyed by
+/// position, so callers reset `result` to `None` before starting a new query.
 #[component]
 pub fn ResultTable(result: Signal<Option<QueryResult>>) -> impl IntoView {
     let toast = use_toast_write();
 
+    // Rebuild the table only when its shape changes, not on every appended batch.
+    let shape = Memo::new(move |_| {
+        result.with(|r| r.as_ref().map(|r| (r.columns.clone(), r.error.clone())))
+    });
+    let row_count = move || result.with(|r| r.as_ref().map_or(0, |r| r.row_count));
+    let execution_time_ms = move || result.with(|r| r.as_ref().map_or(0, |r| r.execution_time_ms));
+    let visible_rows =
+        move || result.with(|r| r.as_ref().map_or(0, |r| r.rows.len().min(MAX_DISPLAY_ROWS)));
+
     view! {
         <div class="result-table-container">
-            {move || match result.get() {
+            {move || match shape.get() {
                 None => view! {
                     <div class="result-table-empty">
                         <p>"Run a query to see results"</p>
                     </div>
                 }.into_any(),
-                Some(ref r) if r.error.is_some() => view! {
+                Some((_, Some(error))) => view! {
                     <div class="result-table-error">
-                        <p class="error-message">{r.error.clone().unwrap_or_default()}</p>
+                        <p class="error-message">{error}</p>
                     </div>
                 }.into_any(),
-                Some(ref r) => {
-                    let r_csv = r.clone();
-                    let r_json = r.clone();
-                    view! {
-                        <div class="result-table-wrapper">
-                            <div class="result-table-header">
-                                <span class="result-count">
-                                    {format!("{} rows", r.row_count)}
-                                </span>
-                                <span class="result-time">
-                                    {format!("{}ms", r.execution_time_ms)}
-                                </span>
-                                <div class="result-actions">
-                                    <button
-                                        class="btn btn-sm"
-                                        title="Export CSV"
-                                        on:click=move |_| {
-                                            export_csv(&r_csv);
+                Some((columns, None)) => view! {
+                    <div class="result-table-wrapper">
+                        <div class="result-table-header">
+                            <span class="result-count">
+                                {move || format!("{} rows", row_count())}
+                            </span>
+                            <span class="result-time">
+                                {move || format!("{}ms", execution_time_ms())}
+                            </span>
+                            <div class="result-actions">
+                                <button
+                                    class="btn btn-sm"
+                                    title="Export CSV"
+                                    on:click=move |_| {
+                                        if let Some(r) = result.get_untracked() {
+                                            export_csv(&r);
                                             push_toast(toast, ToastLevel::Success, "Exported as CSV");
                                         }
-                                    >
-                                        "CSV"
-                                    </button>
-                                    <button
-                                        class="btn btn-sm"
-                                        title="Export JSON"
-                                        on:click=move |_| {
-                                            export_json(&r_json);
+                                    }
+                                >
+                                    "CSV"
+                                </button>
+                                <button
+                                    class="btn btn-sm"
+                                    title="Export JSON"
+                                    on:click=move |_| {
+                                        if let Some(r) = result.get_untracked() {
+                                            export_json(&r);
                                             push_toast(toast, ToastLevel::Success, "Exported as JSON");
                                         }
-                                    >
-                                        "JSON"
-                                    </button>
-                                </div>
+                                    }
+                                >
+                                    "JSON"
+                                </button>
                             </div>
-                            <div class="result-table-scroll">
-                                <table class="result-table">
-                                    <thead>
-                                        <tr>
-                                            {r.columns.iter().map(|col| {
-                                                let name = col.name.clone();
-                                                let dtype = col.data_type.clone();
-                                                let dtype2 = dtype.clone();
-                                                view! {
-                                                    <th title=dtype>
-                                                        <span class="col-name">{name}</span>
-                                                        <span class="col-type">{dtype2}</span>
-                                                    </th>
-                                                }
-                                            }).collect::<Vec<_>>()}
-                                        </tr>
-                                    </thead>
-                                    <tbody>
-                                        {r.rows.iter().take(1000).enumerate().map(|(i, row)| {
+                        </div>
+                        <div class="result-table-scroll">
+                            <table class="result-table">
+                                <thead>
+                                    <tr>
+                                        {columns.into_iter().map(|col| {
+                                            let dtype = col.data_type.clone();
+                                            view! {
+                                                <th title=dtype>
+                                                    <span class="col-name">{col.name}</span>
+                                                    <span class="col-type">{col.data_type}</span>
+                                                </th>
+                                            }
+                                        }).collect::<Vec<_>>()}
+                                    </tr>
+                                </thead>
+                                <tbody>
+                                    <For
+                                        each=move || 0..visible_rows()
+                                        key=|i| *i
+                                        children=move |i| {
+                                            let row = result.with_untracked(|r| {
+                                                r.as_ref()
+                                                    .and_then(|r| r.rows.get(i).cloned())
+                                                    .unwrap_or_default()
+                                            });
                                             view! {
                                                 <tr class=if i % 2 == 0 { "row-even" } else { "row-odd" }>
                                                     {row.iter().map(|val| {
@@ -87,13 +109,13 @@ pub fn ResultTable(result: Signal<Option<QueryResult>>) -> impl IntoView {
                                                     }).collect::<Vec<_>>()}
                                                 </tr>
                                             }
-                                        }).collect::<Vec<_>>()}
-                                    </tbody>
-                                </table>
-                            </div>
+                                        }
+                                    />
+                                </tbody>
+                            </table>
                         </div>
-                    }.into_any()
-                },
+                    </div>
+                }.into_any(),
             }}
         </div>
     }
@@ -1,4 +1,7 @@
+use std::collections::BTreeMap;
+
 use leptos::prelude::*;
+use megafactory_sql_types::k8s::{AutoscalerStatus, AutoscalerUpdate};
 
 /// Panel for controlling cluster scaling.
 #[component]
@@ -7,6 +10,11 @@ pub fn ScalingPanel(
     min_replicas: Signal<i32>,
     max_replicas: Signal<i32>,
     #[prop(into)] on_scale: Callback<i32>,
+    /// The KEDA ScaledObject, when the workers are autoscaled.
+    autoscaler: Option<AutoscalerStatus>,
+    /// Called with edited autoscaler bounds and thresholds.
+    #[prop(optional)]
+    on_autoscaler_update: Option<Callback<AutoscalerUpdate>>,
 ) -> impl IntoView {
     let (desired, set_desired) = signal(0i32);
 
@@ -47,6 +55,158 @@ pub fn ScalingPanel(
                 <span>" | Min: " {move || min_replicas.get()}</span>
                 <span>" | Max: " {move || max_replicas.get()}</span>
             </div>
+            {autoscaler.map(|status| view! {
+                <AutoscalerSection status=status on_update=on_autoscaler_update />
+            })}
+        </div>
+    }
+}
+
+/// Live ScaledObject: editable bounds and thresholds, the value KEDA last
+/// saw for each trigger, and recent scaling.
+#[component]
+fn AutoscalerSection(
+    status: AutoscalerStatus,
+    on_update: Option<Callback<AutoscalerUpdate>>,
+) -> impl IntoView {
+    let (min, set_min) = signal(status.min_replicas);
+    let (max, set_max) = signal(status.max_replicas);
+    let (thresholds, set_thresholds) = signal(
+        status
+            .triggers
+            .iter()
+            .map(|t| (t.trigger.name.clone(), t.trigger.threshold))
+            .collect::<BTreeMap<_, _>>(),
+    );
+
+    let on_save = move |_| {
+        if let Some(on_update) = on_update {
+            on_update.run(AutoscalerUpdate {
+                min_replicas: min.get_untracked(),
+                max_replicas: max.get_untracked(),
+                thresholds: thresholds.get_untracked(),
+            });
+        }
+    };
+
+    let state = if status.active { "Active" } else { "Idle" };
+    let triggers = status
+        .triggers
+        .into_iter()
+        .map(|t| {
+            let name = t.trigger.name.clone();
+            let current = t
+                .current_value
+                .map(|v| format!("{v:.1}"))
+                .unwrap_or_else(|| "-".into());
+            view! {
+                <tr>
+                    <td>{t.trigger.name.clone()}</td>
+                    <td><code>{t.trigger.query}</code></td>
+                    <td>{current}</td>
+                    <td>
+                        <input
+                            type="number"
+                            min="0"
+                            step="any"
+                            class="autoscaler-threshold"
+                            prop:value=t.trigger.threshold.to_string()
+                            disabled=on_update.is_none()
+                            on:input=move |ev| {
+                                if let Ok(v) = event_target_value(&ev).parse::<f64>() {
+                                    let name = name.clone();
+                                    set_thresholds.update(|t| { t.insert(name, v); });
+                                }
+                            }
+                        />
+                    </td>
+                </tr>
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let history = status
+        .history
+        .into_iter()
+        .take(10)
+        .map(|ev| {
+            let time = ev
+                .time
+                .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or_default();
+            let replicas = ev.replicas.map(|r| format!("-> {r}")).unwrap_or_default();
+            view! {
+                <li class="autoscaler-event">
+                    <span class="autoscaler-event-time">{time}</span>
+                    <span class="autoscaler-event-replicas">{replicas}</span>
+                    <span class="autoscaler-event-message">{ev.message}</span>
+                </li>
+            }
+        })
+        .collect::<Vec<_>>();
+    let no_history = history.is_empty();
+
+    view! {
+        <div class="autoscaler">
+            <h4>
+                {format!("KEDA Autoscaler: {}", status.name)}
+                <span class=format!("autoscaler-state autoscaler-state--{}", state.to_lowercase())>
+                    {state}
+                </span>
+            </h4>
+            <div class="scaling-info">
+                {format!("HPA replicas: {} (desired {})", status.current_replicas, status.desired_replicas)}
+            </div>
+            <div class="scaling-controls">
+                <label>
+                    "Min "
+                    <input
+                        type="number"
+                        min="0"
+                        prop:value=move || min.get().to_string()
+                        disabled=on_update.is_none()
+                        on:input=move |ev| {
+                            if let Ok(v) = event_target_value(&ev).parse::<i32>() {
+                                set_min.set(v);
+                            }
+                        }
+                    />
+                </label>
+                <label>
+                    "Max "
+                    <input
+                        type="number"
+                        min="1"
+                        prop:value=move || max.get().to_string()
+                        disabled=on_update.is_none()
+                        on:input=move |ev| {
+                            if let Ok(v) = event_target_value(&ev).parse::<i32>() {
+                                set_max.set(v);
+                            }
+                        }
+                    />
+                </label>
+                {on_update.is_some().then(|| view! {
+                    <button class="btn btn-primary" on:click=on_save>"Save Autoscaler"</button>
+                })}
+            </div>
+            <table class="storage-table autoscaler-triggers">
+                <thead>
+                    <tr>
+                        <th>"Trigger"</th>
+                        <th>"Query"</th>
+                        <th>"Current"</th>
+                        <th>"Target / Worker"</th>
+                    </tr>
+                </thead>
+                <tbody>{triggers}</tbody>
+            </table>
+            <h4>"Scaling History"</h4>
+            {if no_history {
+                view! { <p class="k8s-events-empty">"No scaling events"</p> }.into_any()
+            } else {
+                view! { <ul class="autoscaler-history">{history}</ul> }.into_any()
+            }}
         </div>
     }
 }
@@ -1,9 +1,20 @@
 use leptos::prelude::*;
 use megafactory_sql_types::k8s::VolumeInfo;
 
-/// Panel displaying storage volumes and their usage.
+use crate::state::settings::use_settings_state;
+
+/// Panel displaying storage volumes and their usage. Volumes above the
+/// warning threshold in settings are flagged, with an expand button when
+/// their storage class allows it.
 #[component]
-pub fn StoragePanel(volumes: Signal<Vec<VolumeInfo>>) -> impl IntoView {
+pub fn StoragePanel(
+    volumes: Signal<Vec<VolumeInfo>>,
+    /// Called with the PVC name and new size in GiB.
+    #[prop(optional)]
+    on_resize: Option<Callback<(String, u64)>>,
+) -> impl IntoView {
+    let (settings, _) = use_settings_state();
+
     view! {
         <div class="storage-panel">
             <h3>"Storage Volumes"</h3>
@@ -11,37 +22,59 @@ pub fn StoragePanel(volumes: Signal<Vec<VolumeInfo>>) -> impl IntoView {
                 <thead>
                     <tr>
                         <th>"PVC Name"</th>
+                        <th>"Class"</th>
                         <th>"Access"</th>
                         <th>"Capacity"</th>
                         <th>"Used"</th>
                         <th>"Pod"</th>
+                        <th></th>
                     </tr>
                 </thead>
                 <tbody>
-                    {move || volumes.get().iter().map(|vol| {
-                        let used_pct = if vol.capacity_bytes > 0 {
-                            (vol.used_bytes as f64 / vol.capacity_bytes as f64) * 100.0
-                        } else {
-                            0.0
-                        };
-                        view! {
-                            <tr>
-                                <td>{vol.name.clone()}</td>
-                                <td>{vol.access_mode.clone()}</td>
-                                <td>{format_bytes(vol.capacity_bytes)}</td>
-                                <td>
-                                    <div class="storage-usage">
-                                        <div
-                                            class="storage-usage-bar"
-                                            style=format!("width:{}%", used_pct)
-                                        ></div>
-                                        <span>{format!("{:.0}%", used_pct)}</span>
-                                    </div>
-                                </td>
-                                <td>{vol.bound_pod.clone()}</td>
-                            </tr>
-                        }
-                    }).collect::<Vec<_>>()}
+                    {move || {
+                        let warn_percent = settings.with(|s| s.volume_warn_percent) as f64;
+                        volumes.get().iter().map(|vol| {
+                            let used_pct = vol.usage_percent();
+                            let warn = used_pct >= warn_percent;
+                            let row_class = if warn { "storage-row--warn" } else { "" };
+                            let action = match (warn, vol.expandable, on_resize) {
+                                (true, true, Some(on_resize)) => {
+                                    let (name, size) = (vol.name.clone(), vol.suggested_size_gib());
+                                    view! {
+                                        <button
+                                            class="btn btn-sm"
+                                            on:click=move |_| on_resize.run((name.clone(), size))
+                                        >
+                                            {format!("Expand to {size} GiB")}
+                                        </button>
+                                    }.into_any()
+                                }
+                                (true, false, _) => view! {
+                                    <span class="storage-note">"Class does not allow expansion"</span>
+                                }.into_any(),
+                                _ => ().into_any(),
+                            };
+                            view! {
+                                <tr class=row_class>
+                                    <td>{vol.name.clone()}</td>
+                                    <td>{vol.storage_class.clone()}</td>
+                                    <td>{vol.access_mode.clone()}</td>
+                                    <td>{format_bytes(vol.capacity_bytes)}</td>
+                                    <td>
+                                        <div class="storage-usage">
+                                            <div
+                                                class="storage-usage-bar"
+                                                style=format!("width:{}%", used_pct)
+                                            ></div>
+                                            <span>{format!("{:.0}%", used_pct)}</span>
+                                        </div>
+                                    </td>
+                                    <td>{vol.bound_pod.clone()}</td>
+                                    <td>{action}</td>
+                                </tr>
+                            }
+                        }).collect::<Vec<_>>()
+                    }}
                 </tbody>
             </table>
         </div>
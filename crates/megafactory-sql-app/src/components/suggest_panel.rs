@@ -0,0 +1,210 @@
+use leptos::prelude::*;
+use megafactory_sql_types::advisor::AccelerableOp;
+use megafactory_sql_types::configurator::{
+    suggest_configs, CloudProvider, ConfigSuggestion, DeploymentConfig, InstanceFamily,
+    WorkloadProfile,
+};
+
+/// How many suggestions are listed, the applied one included.
+const SHOWN_SUGGESTIONS: usize = 5;
+
+fn pools_summary(config: &DeploymentConfig) -> String {
+    config
+        .pools()
+        .into_iter()
+        .map(|(label, pool)| format!("{label}: {} × {}", pool.replicas, pool.instance_type))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Workload inputs and a "Suggest" button that ranks deployments from the
+/// provider's catalog by throughput per dollar, applies the best one, and
+/// lists the alternatives.
+#[component]
+pub fn SuggestPanel(
+    provider: Signal<CloudProvider>,
+    families: Signal<Vec<InstanceFamily>>,
+    /// Called with the suggested config whose pools should be used.
+    on_apply: Callback<DeploymentConfig>,
+) -> impl IntoView {
+    let (workload, set_workload) = signal(WorkloadProfile {
+        provider: CloudProvider::Aws,
+        data_size_gb: 1000,
+        target_qps: 5.0,
+        op_mix: vec![
+            (AccelerableOp::HashAggregate, 0.3),
+            (AccelerableOp::Filter, 0.2),
+            (AccelerableOp::Decompression, 0.2),
+        ],
+        budget_monthly_usd: None,
+    });
+    let (suggestions, set_suggestions) = signal(Option::<Vec<ConfigSuggestion>>::None);
+    // The QPS input holds something other than a positive number
+    let (qps_invalid, set_qps_invalid) = signal(false);
+
+    // Suggestions are for one provider's catalog
+    Effect::new(move || {
+        provider.track();
+        set_suggestions.set(None);
+    });
+
+    let share = move |op: AccelerableOp| {
+        workload.with(|w| {
+            w.op_mix
+                .iter()
+                .find(|(o, _)| *o == op)
+                .map(|(_, share)| (share * 10_000.0).round() / 100.0)
+                .unwrap_or(0.0)
+        })
+    };
+    let set_share = move |op: AccelerableOp, percent: f64| {
+        set_workload.update(|w| {
+            w.op_mix.retain(|(o, _)| *o != op);
+            if percent > 0.0 {
+                w.op_mix.push((op, percent / 100.0));
+            }
+        });
+    };
+
+    let on_suggest = move |_| {
+        if qps_invalid.get_untracked() {
+            return;
+        }
+        let workload = WorkloadProfile {
+            provider: provider.get_untracked(),
+            ..workload.get_untracked()
+        };
+        let ranked = families.with_untracked(|f| suggest_configs(&workload, f));
+        if let Some(top) = ranked.first() {
+            on_apply.run(top.config.clone());
+        }
+        set_suggestions.set(Some(ranked));
+    };
+
+    view! {
+        <div class="suggest-panel">
+            <h3>"Suggest Instances"</h3>
+            <div class="form-row">
+                <div class="form-field">
+                    <label>"Data Size (GB)"</label>
+                    <input
+                        type="number"
+                        min="1"
+                        prop:value=move || workload.with(|w| w.data_size_gb.to_string())
+                        on:input=move |ev| {
+                            if let Ok(gb) = event_target_value(&ev).parse() {
+                                set_workload.update(|w| w.data_size_gb = gb);
+                            }
+                        }
+                    />
+                </div>
+                <div class="form-field">
+                    <label>"Target QPS"</label>
+                    <input
+                        type="number"
+                        min="0.1"
+                        step="0.1"
+                        prop:value=move || workload.with(|w| w.target_qps.to_string())
+                        on:input=move |ev| {
+                            match event_target_value(&ev).parse::<f64>() {
+                                Ok(qps) if qps.is_finite() && qps > 0.0 => {
+                                    set_workload.update(|w| w.target_qps = qps);
+                                    set_qps_invalid.set(false);
+                                }
+                                _ => set_qps_invalid.set(true),
+                            }
+                        }
+                    />
+                </div>
+                <div class="form-field">
+                    <label>"Budget ($/month)"</label>
+                    <input
+                        type="number"
+                        min="0"
+                        placeholder="No limit"
+                        prop:value=move || {
+                            workload.with(|w| w.budget_monthly_usd.map(|b| b.to_string()).unwrap_or_default())
+                        }
+                        on:input=move |ev| {
+                            let budget = event_target_value(&ev).parse().ok();
+                            set_workload.update(|w| w.budget_monthly_usd = budget);
+                        }
+                    />
+                </div>
+            </div>
+            <div class="form-row">
+                {AccelerableOp::all().iter().map(|op| {
+                    let op = *op;
+                    view! {
+                        <div class="form-field">
+                            <label>{format!("{} (% of query time)", op.label())}</label>
+                            <input
+                                type="number"
+                                min="0"
+                                max="100"
+                                prop:value=move || share(op).to_string()
+                                on:input=move |ev| {
+                                    if let Ok(percent) = event_target_value(&ev).parse() {
+                                        set_share(op, percent);
+                                    }
+                                }
+                            />
+                        </div>
+                    }
+                }).collect::<Vec<_>>()}
+            </div>
+            {move || qps_invalid.get().then(|| view! {
+                <p class="form-error">"Target QPS must be a number above 0."</p>
+            })}
+            <button class="btn" disabled=qps_invalid on:click=on_suggest>"Suggest"</button>
+
+            {move || suggestions.get().map(|suggestions| {
+                if suggestions.is_empty() {
+                    return view! {
+                        <p class="suggest-note">
+                            "No priced instance types meet the target within the budget."
+                        </p>
+                    }
+                    .into_any();
+                }
+                view! {
+                    <table class="suggest-table">
+                        <thead>
+                            <tr>
+                                <th>"Pools"</th>
+                                <th>"QPS"</th>
+                                <th>"Speedup"</th>
+                                <th>"$/month"</th>
+                                <th>"QPS per $1k/month"</th>
+                                <th></th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {suggestions.into_iter().take(SHOWN_SUGGESTIONS).map(|suggestion| {
+                                let config = suggestion.config.clone();
+                                view! {
+                                    <tr>
+                                        <td>{pools_summary(&suggestion.config)}</td>
+                                        <td>{format!("{:.1}", suggestion.throughput_qps)}</td>
+                                        <td>{format!("{:.1}×", suggestion.speedup)}</td>
+                                        <td>{format!("${:.0}", suggestion.monthly_usd)}</td>
+                                        <td>{format!("{:.2}", suggestion.qps_per_dollar() * 1000.0)}</td>
+                                        <td>
+                                            <button
+                                                class="btn btn-sm"
+                                                on:click=move |_| on_apply.run(config.clone())
+                                            >
+                                                "Use"
+                                            </button>
+                                        </td>
+                                    </tr>
+                                }
+                            }).collect::<Vec<_>>()}
+                        </tbody>
+                    </table>
+                }
+                .into_any()
+            })}
+        </div>
+    }
+}
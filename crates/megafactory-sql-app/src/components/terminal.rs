@@ -0,0 +1,189 @@
+use leptos::prelude::*;
+use megafactory_sql_types::exec::{
+    strip_ansi, ExecClientMessage, ExecPolicy, ExecServerMessage, ALLOW_ANY_COMMAND,
+};
+
+use crate::exec_client::ExecSession;
+
+/// Output kept in the terminal, in bytes; the oldest output is dropped first.
+const MAX_OUTPUT: usize = 256 * 1024;
+const COLS: u16 = 120;
+const ROWS: u16 = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SessionState {
+    Idle,
+    Connecting,
+    Running,
+    Exited,
+}
+
+/// Line-mode terminal running an allowed command in a pod over the exec
+/// WebSocket. Input is sent a line at a time when Enter is pressed.
+#[component]
+pub fn PodTerminal(
+    pod: String,
+    policy: ExecPolicy,
+    /// Called when the terminal's close button is clicked.
+    on_close: Callback<()>,
+) -> impl IntoView {
+    let default_command = policy
+        .allowed_commands
+        .iter()
+        .find(|c| c.as_str() != ALLOW_ANY_COMMAND)
+        .cloned()
+        .unwrap_or_else(|| "sh".to_string());
+    let allowed = policy.allowed_commands.join(", ");
+
+    let (command, set_command) = signal(default_command);
+    let (tty, set_tty) = signal(false);
+    let (input, set_input) = signal(String::new());
+    let (output, set_output) = signal(String::new());
+    let (state, set_state) = signal(SessionState::Idle);
+    let session = StoredValue::new_local(Option::<ExecSession>::None);
+
+    let append = move |text: &str| {
+        set_output.update(|out| {
+            out.push_str(text);
+            if out.len() > MAX_OUTPUT {
+                let mut cut = out.len() - MAX_OUTPUT;
+                while !out.is_char_boundary(cut) {
+                    cut += 1;
+                }
+                out.drain(..cut);
+            }
+        });
+    };
+
+    let on_message = move |msg: ExecServerMessage| match msg {
+        ExecServerMessage::Started => set_state.set(SessionState::Running),
+        ExecServerMessage::Stdout { data } | ExecServerMessage::Stderr { data } => {
+            append(&strip_ansi(&data));
+        }
+        ExecServerMessage::Exit { code, message } => {
+            let code = code
+                .map(|c| format!(" (exit code {c})"))
+                .unwrap_or_default();
+            append(&format!("\n[{message}{code}]\n"));
+            set_state.set(SessionState::Exited);
+        }
+        ExecServerMessage::Error { message } => {
+            append(&format!("\n[Error: {message}]\n"));
+            set_state.set(SessionState::Exited);
+        }
+    };
+
+    let pod_name = StoredValue::new(pod.clone());
+    let start = move |_| {
+        let words: Vec<String> = command
+            .get_untracked()
+            .split_whitespace()
+            .map(String::from)
+            .collect();
+        if words.is_empty() {
+            return;
+        }
+        append(&format!("$ {}\n", words.join(" ")));
+        let start = ExecClientMessage::Start {
+            command: words,
+            tty: tty.get_untracked(),
+            cols: COLS,
+            rows: ROWS,
+        };
+        match ExecSession::open(&pod_name.get_value(), start, on_message) {
+            Ok(s) => {
+                session.set_value(Some(s));
+                set_state.set(SessionState::Connecting);
+            }
+            Err(e) => append(&format!("[Error: {e}]\n")),
+        }
+    };
+
+    let send_stdin = move |data: String| {
+        if let Some(s) = session.get_value() {
+            s.send(&ExecClientMessage::Stdin { data });
+        }
+    };
+
+    let on_input_key = move |ev: leptos::ev::KeyboardEvent| {
+        if ev.key() == "Enter" {
+            ev.prevent_default();
+            let line = input.get_untracked();
+            set_input.set(String::new());
+            // Without a TTY nothing echoes the input back
+            if !tty.get_untracked() {
+                append(&format!("{line}\n"));
+            }
+            send_stdin(format!("{line}\n"));
+        }
+    };
+
+    let stop = move || {
+        if let Some(s) = session.get_value() {
+            s.close();
+        }
+        session.set_value(None);
+    };
+    on_cleanup(stop);
+
+    let running = move || state.get() == SessionState::Running;
+    let busy = move || {
+        matches!(
+            state.get(),
+            SessionState::Connecting | SessionState::Running
+        )
+    };
+
+    view! {
+        <div class="pod-terminal">
+            <div class="log-viewer-header">
+                <h3>{format!("Exec: {pod}")}</h3>
+                <span class="log-viewer-state">{format!("Allowed: {allowed}")}</span>
+                <button class="btn btn-sm" on:click=move |_| { stop(); on_close.run(()); }>"Close"</button>
+            </div>
+
+            <div class="log-viewer-toolbar">
+                <input
+                    type="text"
+                    class="terminal-command"
+                    prop:value=move || command.get()
+                    on:input=move |ev| set_command.set(event_target_value(&ev))
+                    disabled=busy
+                />
+                <label class="log-toggle" title="Allocate a terminal (merges stderr into stdout)">
+                    <input
+                        type="checkbox"
+                        prop:checked=move || tty.get()
+                        on:change=move |ev| set_tty.set(event_target_checked(&ev))
+                        disabled=busy
+                    />
+                    "TTY"
+                </label>
+                <Show
+                    when=busy
+                    fallback=move || view! { <button class="btn btn-sm btn-primary" on:click=start>"Run"</button> }
+                >
+                    <button
+                        class="btn btn-sm"
+                        title="Send Ctrl-C"
+                        on:click=move |_| send_stdin("\u{3}".to_string())
+                    >
+                        "Ctrl-C"
+                    </button>
+                    <button class="btn btn-sm btn-danger" on:click=move |_| stop()>"Disconnect"</button>
+                </Show>
+            </div>
+
+            <pre class="log-lines terminal-output">{move || output.get()}</pre>
+            <input
+                type="text"
+                class="terminal-input"
+                placeholder=move || if running() { "Type input, Enter to send" } else { "Not running" }
+                prop:value=move || input.get()
+                on:input=move |ev| set_input.set(event_target_value(&ev))
+                on:keydown=on_input_key
+                disabled=move || !running()
+            />
+        </div>
+    }
+}
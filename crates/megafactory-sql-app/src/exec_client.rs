@@ -0,0 +1,121 @@
+//! Browser side of the pod exec endpoint: one WebSocket per terminal
+//! session, speaking `ExecClientMessage` / `ExecServerMessage` JSON.
+
+use megafactory_sql_types::exec::{ExecClientMessage, ExecServerMessage};
+
+/// Exec endpoint served by megafactory-sql-server.
+pub fn exec_path(pod: &str) -> String {
+    format!("/proxy/k8s/pods/{pod}/exec")
+}
+
+/// An open exec session. Cheap to clone; clones share the socket.
+#[derive(Clone, Default)]
+pub struct ExecSession {
+    #[cfg(target_arch = "wasm32")]
+    socket: Option<web_sys::WebSocket>,
+}
+
+impl ExecSession {
+    /// Connect and send `start` once the socket opens. `on_message` receives
+    /// every server message; a socket that closes before `Exit` is reported
+    /// as an `Exit` without a code.
+    /// Always fails on the server (SSR).
+    #[cfg(target_arch = "wasm32")]
+    pub fn open(
+        pod: &str,
+        start: ExecClientMessage,
+        on_message: impl Fn(ExecServerMessage) + 'static,
+    ) -> Result<Self, String> {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        use wasm_bindgen::prelude::*;
+        use wasm_bindgen::JsCast;
+        use web_sys::{MessageEvent, WebSocket};
+
+        let location = web_sys::window().ok_or("No window")?.location();
+        let protocol = location.protocol().unwrap_or_else(|_| "http:".into());
+        let host = location.host().unwrap_or_else(|_| "localhost:3000".into());
+        let ws_protocol = if protocol == "https:" { "wss:" } else { "ws:" };
+        let url = format!("{ws_protocol}//{host}{}", exec_path(pod));
+        let ws = WebSocket::new(&url).map_err(crate::query_stream::js_error)?;
+
+        let on_message: Rc<dyn Fn(ExecServerMessage)> = Rc::new(on_message);
+        let exited = Rc::new(Cell::new(false));
+
+        let socket = ws.clone();
+        let on_open = Closure::<dyn Fn()>::new(move || {
+            if let Ok(json) = serde_json::to_string(&start) {
+                let _ = socket.send_with_str(&json);
+            }
+        });
+        ws.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+        on_open.forget();
+
+        let (handler, seen_exit) = (on_message.clone(), exited.clone());
+        let on_ws_message = Closure::<dyn Fn(MessageEvent)>::new(move |ev: MessageEvent| {
+            let Some(text) = ev.data().as_string() else {
+                return;
+            };
+            match serde_json::from_str::<ExecServerMessage>(&text) {
+                Ok(msg) => {
+                    if matches!(msg, ExecServerMessage::Exit { .. }) {
+                        seen_exit.set(true);
+                    }
+                    handler(msg);
+                }
+                Err(e) => {
+                    web_sys::console::warn_1(&format!("Unrecognised exec message: {e}").into())
+                }
+            }
+        });
+        ws.set_onmessage(Some(on_ws_message.as_ref().unchecked_ref()));
+        on_ws_message.forget();
+
+        let on_close = Closure::<dyn Fn()>::new(move || {
+            if !exited.replace(true) {
+                on_message(ExecServerMessage::Exit {
+                    code: None,
+                    message: "Connection closed".to_string(),
+                });
+            }
+        });
+        ws.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+        on_close.forget();
+
+        Ok(Self { socket: Some(ws) })
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn open(
+        _pod: &str,
+        _start: ExecClientMessage,
+        _on_message: impl Fn(ExecServerMessage) + 'static,
+    ) -> Result<Self, String> {
+        Err("Exec is only available in the browser".to_string())
+    }
+
+    /// Send on the socket; dropped unless it is open.
+    pub fn send(&self, msg: &ExecClientMessage) {
+        #[cfg(target_arch = "wasm32")]
+        if let Some(ws) = self
+            .socket
+            .as_ref()
+            .filter(|ws| ws.ready_state() == web_sys::WebSocket::OPEN)
+        {
+            if let Ok(json) = serde_json::to_string(msg) {
+                let _ = ws.send_with_str(&json);
+            }
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        let _ = msg;
+    }
+
+    /// Close the socket; the server then stops the process.
+    pub fn close(&self) {
+        #[cfg(target_arch = "wasm32")]
+        if let Some(ws) = &self.socket {
+            let _ = ws.close();
+        }
+    }
+}
@@ -1,7 +1,13 @@
 pub mod components;
+pub mod exec_client;
+pub mod log_stream;
 pub mod pages;
+pub mod query_stream;
+#[cfg(feature = "ssr")]
+pub mod server;
 pub mod state;
 pub mod storage;
+pub mod ws_client;
 
 use leptos::prelude::*;
 use leptos_meta::*;
@@ -27,9 +33,11 @@ pub fn App() -> impl IntoView {
     state::query::provide_query_state();
     state::schema::provide_schema_state();
     state::k8s::provide_k8s_state();
+    state::monitoring::provide_monitoring_state();
     state::connection::provide_connection_state();
     state::settings::provide_settings_state();
     state::toast::provide_toast_state();
+    state::websocket::provide_websocket();
 
     view! {
         <Stylesheet id="app-styles" href="/style/main.css" />
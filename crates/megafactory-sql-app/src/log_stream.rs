@@ -0,0 +1,91 @@
+use megafactory_sql_types::logs::{LogChunk, LogQuery};
+
+/// Pod log endpoint served by megafactory-sql-server.
+pub fn logs_path(pod: &str, query: &LogQuery) -> String {
+    let params = query.to_query_string();
+    if params.is_empty() {
+        format!("/proxy/k8s/pods/{pod}/logs")
+    } else {
+        format!("/proxy/k8s/pods/{pod}/logs?{params}")
+    }
+}
+
+/// Stops a running [`stream_logs`] call; clones abort the same request.
+#[derive(Clone, Default)]
+pub struct LogStreamHandle {
+    #[cfg(target_arch = "wasm32")]
+    controller: Option<web_sys::AbortController>,
+}
+
+impl LogStreamHandle {
+    pub fn new() -> Self {
+        Self {
+            #[cfg(target_arch = "wasm32")]
+            controller: web_sys::AbortController::new().ok(),
+        }
+    }
+
+    /// Close the log request. A following stream ends without error.
+    pub fn abort(&self) {
+        #[cfg(target_arch = "wasm32")]
+        if let Some(controller) = &self.controller {
+            controller.abort();
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn is_aborted(&self) -> bool {
+        self.controller
+            .as_ref()
+            .is_some_and(|c| c.signal().aborted())
+    }
+}
+
+/// GET a pod's logs and call `on_chunk` for every NDJSON chunk as soon as it
+/// arrives. With `query.follow` this runs until the pod's container exits or
+/// `handle` is aborted.
+/// Always fails on the server (SSR).
+#[cfg(target_arch = "wasm32")]
+pub async fn stream_logs(
+    pod: &str,
+    query: &LogQuery,
+    handle: &LogStreamHandle,
+    mut on_chunk: impl FnMut(LogChunk),
+) -> Result<(), String> {
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_futures::JsFuture;
+
+    use crate::query_stream::{js_error, read_ndjson};
+
+    let window = web_sys::window().ok_or("No window")?;
+    let init = web_sys::RequestInit::new();
+    init.set_method("GET");
+    if let Some(controller) = &handle.controller {
+        init.set_signal(Some(&controller.signal()));
+    }
+    let request =
+        web_sys::Request::new_with_str_and_init(&logs_path(pod, query), &init).map_err(js_error)?;
+
+    let result = async {
+        let response: web_sys::Response = JsFuture::from(window.fetch_with_request(&request))
+            .await
+            .map_err(js_error)?
+            .unchecked_into();
+        read_ndjson(response, &mut on_chunk).await
+    }
+    .await;
+    match result {
+        Err(_) if handle.is_aborted() => Ok(()),
+        other => other,
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn stream_logs(
+    _pod: &str,
+    _query: &LogQuery,
+    _handle: &LogStreamHandle,
+    _on_chunk: impl FnMut(LogChunk),
+) -> Result<(), String> {
+    Err("Log streaming is only available in the browser".to_string())
+}
@@ -1,107 +1,136 @@
 use leptos::prelude::*;
 use megafactory_sql_types::configurator::{
-    AcceleratorType, CloudProvider, ClusterHardwareStatus, DeploymentConfig, DetectedAccelerator,
-    InstanceFamily, NodeHardware, PoolAccelerator, StorageBackend, TolerationConfig,
-    WorkerPoolConfig,
+    AcceleratorType, CloudProvider, ClusterHardwareStatus, ConfigChange, CostOptions,
+    DeploymentConfig, InstanceCatalog, InstanceFamily, PricingModel, ValidationIssue,
 };
+use megafactory_sql_types::export::{zip_files, ExportFile, ExportFormat};
+use megafactory_sql_types::k8s::MegaDbSummary;
+use megafactory_sql_types::toast::ToastLevel;
 
-/// Server function: get the instance catalog for a cloud provider.
+use crate::components::cost_panel::CostPanel;
+use crate::components::deployment_form::DeploymentForm;
+use crate::components::modal::ConfirmModal;
+use crate::components::suggest_panel::SuggestPanel;
+use crate::state::toast::{push_toast, use_toast_write};
+
+/// Server function: get the instance catalog for a cloud provider, from the
+/// server's override directory if it has one.
 #[server(GetInstanceCatalog, "/api")]
 pub async fn get_instance_catalog(
     provider: CloudProvider,
-) -> Result<Vec<InstanceFamily>, ServerFnError> {
-    Ok(megafactory_sql_types::configurator::instance_catalog(
-        provider,
-    ))
+) -> Result<InstanceCatalog, ServerFnError> {
+    let ctx = crate::server::server_context()?;
+    Ok(ctx.catalog.load(provider).await?)
 }
 
-/// Server function: detect hardware in the current K8s cluster.
-/// Phase 1: returns mock data. Phase 3+: proxy to K8s API.
+/// Server function: detect hardware in the current K8s cluster. Failures
+/// are reported in the status so the panel can show them.
 #[server(DetectClusterHardware, "/api")]
 pub async fn detect_cluster_hardware() -> Result<ClusterHardwareStatus, ServerFnError> {
-    Ok(mock_cluster_hardware())
+    use crate::server::K8sError;
+
+    let ctx = crate::server::server_context()?;
+    Ok(match ctx.k8s.cluster_hardware().await {
+        Ok(mut status) => {
+            if let Some(provider) = status.detected_provider {
+                match ctx.catalog.load(provider).await {
+                    Ok(catalog) => status.price_nodes(&catalog),
+                    Err(e) => tracing::warn!("Cluster cost unavailable: {e}"),
+                }
+            }
+            status
+        }
+        Err(e) => ClusterHardwareStatus {
+            k8s_connected: !matches!(e, K8sError::Unavailable(_)),
+            error: Some(e.to_string()),
+            ..Default::default()
+        },
+    })
 }
 
 /// Server function: generate CRD YAML from a deployment config.
 #[server(GenerateCrdYaml, "/api")]
 pub async fn generate_crd_yaml(config: DeploymentConfig) -> Result<String, ServerFnError> {
-    Ok(config.to_crd_yaml())
+    check_config(&config).await?;
+    crate::server::manifest::to_yaml(&config).map_err(|e| ServerFnError::new(e.to_string()))
 }
 
-/// Mock K8s cluster hardware status for Phase 1.
+/// Reject configs with validation errors against the provider's catalog.
 #[cfg(feature = "ssr")]
-fn mock_cluster_hardware() -> ClusterHardwareStatus {
-    ClusterHardwareStatus {
-        detected_provider: Some(CloudProvider::Aws),
-        nodes: vec![
-            NodeHardware {
-                node_name: "ip-10-0-1-100.ec2.internal".to_string(),
-                provider_instance_type: Some("m6i.4xlarge".to_string()),
-                cpu_allocatable: "16".to_string(),
-                memory_allocatable_bytes: 64 * 1024 * 1024 * 1024,
-                accelerators: vec![],
-                labels: vec![
-                    (
-                        "node.kubernetes.io/instance-type".to_string(),
-                        "m6i.4xlarge".to_string(),
-                    ),
-                    ("megadb.io/node-pool".to_string(), "cpu-pool".to_string()),
-                ],
-            },
-            NodeHardware {
-                node_name: "ip-10-0-2-200.ec2.internal".to_string(),
-                provider_instance_type: Some("g6.12xlarge".to_string()),
-                cpu_allocatable: "48".to_string(),
-                memory_allocatable_bytes: 192 * 1024 * 1024 * 1024,
-                accelerators: vec![DetectedAccelerator {
-                    resource_name: "nvidia.com/gpu".to_string(),
-                    accelerator_type: AcceleratorType::Gpu,
-                    allocatable: 4,
-                    allocated: 2,
-                    device_name: Some("NVIDIA L4 24GB".to_string()),
-                }],
-                labels: vec![
-                    (
-                        "node.kubernetes.io/instance-type".to_string(),
-                        "g6.12xlarge".to_string(),
-                    ),
-                    ("megadb.io/node-pool".to_string(), "gpu-pool".to_string()),
-                ],
-            },
-            NodeHardware {
-                node_name: "ip-10-0-3-150.ec2.internal".to_string(),
-                provider_instance_type: Some("g6.12xlarge".to_string()),
-                cpu_allocatable: "48".to_string(),
-                memory_allocatable_bytes: 192 * 1024 * 1024 * 1024,
-                accelerators: vec![DetectedAccelerator {
-                    resource_name: "nvidia.com/gpu".to_string(),
-                    accelerator_type: AcceleratorType::Gpu,
-                    allocatable: 4,
-                    allocated: 0,
-                    device_name: Some("NVIDIA L4 24GB".to_string()),
-                }],
-                labels: vec![
-                    (
-                        "node.kubernetes.io/instance-type".to_string(),
-                        "g6.12xlarge".to_string(),
-                    ),
-                    ("megadb.io/node-pool".to_string(), "gpu-pool".to_string()),
-                ],
-            },
-        ],
-        total_gpus: 8,
-        total_fpgas: 0,
-        total_npus: 0,
-        total_tpus: 0,
-        gpu_available: 6,
-        fpga_available: 0,
-        npu_available: 0,
-        tpu_available: 0,
-        k8s_connected: true,
-        error: None,
+async fn check_config(config: &DeploymentConfig) -> Result<(), ServerFnError> {
+    let ctx = crate::server::server_context()?;
+    let catalog = ctx.catalog.load(config.provider).await?;
+    let errors: Vec<String> = config
+        .validate(&catalog.families, None)
+        .into_iter()
+        .filter(ValidationIssue::is_error)
+        .map(|i| i.message)
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ServerFnError::new(format!(
+            "Invalid deployment config: {}",
+            errors.join("; ")
+        )))
     }
 }
 
+/// Server function: create or update the MegaDB resource for a deployment
+/// config with server-side apply. A dry run is validated but not persisted.
+#[server(DeployMegaDb, "/api")]
+pub async fn deploy_megadb(
+    config: DeploymentConfig,
+    dry_run: bool,
+) -> Result<MegaDbSummary, ServerFnError> {
+    use crate::server::crd::MegaDB;
+    use crate::server::keda::ScaledObject;
+
+    check_config(&config).await?;
+    let ctx = crate::server::server_context()?;
+    let applied = ctx
+        .k8s
+        .apply_megadb(&MegaDB::from_config(&config), dry_run)
+        .await?;
+    if let Some(scaled_object) = ScaledObject::from_config(&config) {
+        ctx.k8s.apply_scaled_object(&scaled_object, dry_run).await?;
+    }
+    Ok(applied.summary())
+}
+
+/// Server function: render a deployment config as Helm values, a Kustomize
+/// overlay or the raw CRD.
+#[server(ExportConfig, "/api")]
+pub async fn export_config(
+    config: DeploymentConfig,
+    format: ExportFormat,
+) -> Result<Vec<ExportFile>, ServerFnError> {
+    check_config(&config).await?;
+    crate::server::export::render(&config, format).map_err(|e| ServerFnError::new(e.to_string()))
+}
+
+/// Server function: MegaDB resources in the namespace, for "load from
+/// cluster".
+#[server(ListMegaDbs, "/api")]
+pub async fn list_megadbs() -> Result<Vec<MegaDbSummary>, ServerFnError> {
+    let ctx = crate::server::server_context()?;
+    Ok(ctx.k8s.list_megadbs().await?)
+}
+
+/// Server function: the deployment config of a live MegaDB resource.
+#[server(LoadMegaDbConfig, "/api")]
+pub async fn load_megadb_config(name: String) -> Result<DeploymentConfig, ServerFnError> {
+    let ctx = crate::server::server_context()?;
+    Ok(ctx.k8s.megadb_config(&name).await?)
+}
+
+/// Server function: parse a MegaDB manifest (optionally with its
+/// ScaledObject) into a deployment config.
+#[server(ParseManifest, "/api")]
+pub async fn parse_manifest(yaml: String) -> Result<DeploymentConfig, ServerFnError> {
+    crate::server::manifest::from_yaml(&yaml).map_err(|e| ServerFnError::new(e.to_string()))
+}
+
 /// MegaDB Configurator page.
 #[component]
 pub fn ConfiguratorPage() -> impl IntoView {
@@ -109,9 +138,40 @@ pub fn ConfiguratorPage() -> impl IntoView {
     let (selected_filter, set_selected_filter) = signal(Option::<AcceleratorType>::None);
     let (show_yaml, set_show_yaml) = signal(false);
     let (generated_yaml, set_generated_yaml) = signal(String::new());
+    // The config behind the YAML on display, which Dry Run / Deploy apply
+    let (last_config, set_last_config) = signal(Option::<DeploymentConfig>::None);
+    let (show_deploy_confirm, set_show_deploy_confirm) = signal(false);
+    let toast = use_toast_write();
 
     // Load instance catalog reactively when provider changes
     let catalog = Resource::new(move || selected_provider.get(), get_instance_catalog);
+    let families = Signal::derive(move || {
+        catalog
+            .get()
+            .and_then(Result::ok)
+            .map(|c| c.families)
+            .unwrap_or_default()
+    });
+
+    // The deployment being edited; reset to the provider's defaults once its
+    // catalog has loaded
+    let (config, set_config) = signal(DeploymentConfig::default_for(CloudProvider::Aws, &[]));
+    // The imported (or last deployed) config that edits are compared with
+    let (baseline, set_baseline) = signal(Option::<DeploymentConfig>::None);
+    Effect::new(move || {
+        families.with(|families| {
+            let Some(provider) = families.first().map(|f| f.provider) else {
+                return;
+            };
+            let imported = baseline.with_untracked(Option::is_some);
+            let stale = config.with_untracked(|c| {
+                c.provider != provider || (c.cpu_worker_pool.instance_type.is_empty() && !imported)
+            });
+            if stale {
+                set_config.set(DeploymentConfig::default_for(provider, families));
+            }
+        });
+    });
 
     // Load cluster hardware status once
     let (hw_counter, set_hw_counter) = signal(0u32);
@@ -121,6 +181,44 @@ pub fn ConfiguratorPage() -> impl IntoView {
         set_hw_counter.update(|c| *c += 1);
     };
 
+    let (cost_options, set_cost_options) = signal(CostOptions {
+        pricing: PricingModel::OnDemand,
+        storage_gb: 1000,
+    });
+    let issues = Signal::derive(move || {
+        let cluster = cluster_hw.get().and_then(Result::ok);
+        config.with(|c| families.with(|f| c.validate(f, cluster.as_ref())))
+    });
+    let has_errors = move || issues.with(|issues| issues.iter().any(ValidationIssue::is_error));
+    let changes = Signal::derive(move || {
+        baseline.with(|base| {
+            base.as_ref()
+                .map(|base| config.with(|c| c.changes_from(base)))
+                .unwrap_or_default()
+        })
+    });
+
+    let on_import = Callback::new(move |imported: DeploymentConfig| {
+        set_selected_provider.set(imported.provider);
+        set_baseline.set(Some(imported.clone()));
+        set_config.set(imported);
+    });
+
+    // A suggestion replaces the worker pools, keeping the rest of the config
+    let on_suggest = Callback::new(move |suggested: DeploymentConfig| {
+        set_config.update(|c| {
+            c.cpu_worker_pool = suggested.cpu_worker_pool;
+            c.gpu_worker_pool = suggested.gpu_worker_pool;
+            c.fpga_worker_pool = suggested.fpga_worker_pool;
+            c.npu_worker_pool = suggested.npu_worker_pool;
+        });
+    });
+
+    let estimate = Signal::derive(move || {
+        let options = cost_options.get();
+        config.with(|c| families.with(|f| c.estimate_cost(f, &options)))
+    });
+
     // Generate CRD YAML action
     let generate_action = Action::new(move |config: &DeploymentConfig| {
         let config = config.clone();
@@ -143,49 +241,129 @@ pub fn ConfiguratorPage() -> impl IntoView {
         }
     });
 
+    let deploy_action = Action::new(move |(config, dry_run): &(DeploymentConfig, bool)| {
+        let (config, dry_run) = (config.clone(), *dry_run);
+        async move { (dry_run, deploy_megadb(config, dry_run).await) }
+    });
+
+    Effect::new(move || {
+        if let Some((dry_run, result)) = deploy_action.value().get() {
+            match result {
+                Ok(summary) if dry_run => push_toast(
+                    toast,
+                    ToastLevel::Success,
+                    format!("Dry run passed for MegaDB {}", summary.name),
+                ),
+                Ok(summary) => {
+                    // The cluster now matches what was applied
+                    set_baseline.set(last_config.get_untracked());
+                    push_toast(
+                        toast,
+                        ToastLevel::Success,
+                        format!(
+                            "MegaDB {} applied in {} ({} workers)",
+                            summary.name, summary.namespace, summary.replicas
+                        ),
+                    )
+                }
+                Err(e) => push_toast(toast, ToastLevel::Error, format!("Deploy failed: {e}")),
+            }
+        }
+    });
+
+    let dispatch_deploy = move |dry_run: bool| {
+        if let Some(config) = last_config.get_untracked() {
+            deploy_action.dispatch((config, dry_run));
+        }
+    };
+    let on_confirm_deploy = Callback::new(move |_: ()| {
+        set_show_deploy_confirm.set(false);
+        dispatch_deploy(false);
+    });
+    let on_cancel_deploy = Callback::new(move |_: ()| set_show_deploy_confirm.set(false));
+    let deploy_disabled =
+        move || last_config.with(Option::is_none) || deploy_action.pending().get();
+
     let on_generate = move |_| {
-        let provider = selected_provider.get();
-        let config = DeploymentConfig {
-            cluster_name: "megadb-cluster".to_string(),
-            provider,
-            cpu_worker_pool: WorkerPoolConfig {
-                instance_type: "m6i.4xlarge".to_string(),
-                replicas: 3,
-                min_replicas: 2,
-                max_replicas: 10,
-                accelerator: None,
-                node_selector: vec![],
-                tolerations: vec![],
-            },
-            gpu_worker_pool: Some(WorkerPoolConfig {
-                instance_type: "g6.12xlarge".to_string(),
-                replicas: 2,
-                min_replicas: 1,
-                max_replicas: 8,
-                accelerator: Some(PoolAccelerator {
-                    accelerator_type: AcceleratorType::Gpu,
-                    k8s_resource_name: "nvidia.com/gpu".to_string(),
-                    count_per_pod: 4,
-                    device_name: "NVIDIA L4 24GB".to_string(),
-                    mig_profile: None,
-                }),
-                node_selector: vec![("megadb.io/node-pool".to_string(), "gpu-pool".to_string())],
-                tolerations: vec![TolerationConfig {
-                    key: "nvidia.com/gpu".to_string(),
-                    operator: "Exists".to_string(),
-                    value: None,
-                    effect: "NoSchedule".to_string(),
-                }],
-            }),
-            fpga_worker_pool: None,
-            npu_worker_pool: None,
-            storage_backend: StorageBackend::default_for(provider),
-            keda_enabled: true,
-            estimated_monthly_cost_usd: 12500.0,
-        };
+        let mut config = config.get();
+        config.estimated_monthly_cost_usd = estimate.get_untracked().monthly_usd;
+        set_last_config.set(Some(config.clone()));
         generate_action.dispatch(config);
     };
 
+    // The page is split into boxed views: as one static view type it is
+    // deep enough to overflow the compiler's query depth limit.
+    let editor = view! {
+        <ManifestImportPanel on_import=on_import />
+        <SuggestPanel
+            provider=Signal::derive(move || selected_provider.get())
+            families=families
+            on_apply=on_suggest
+        />
+        <DeploymentForm
+            config=config
+            set_config=set_config
+            families=families
+            issues=issues
+        />
+        <CostPanel
+            estimate=estimate
+            options=cost_options
+            set_options=set_cost_options
+            keda_enabled=Signal::derive(move || config.with(|c| c.keda_enabled))
+        />
+
+        <Show when=move || baseline.with(Option::is_some)>
+            <ConfigDiffPanel
+                name=Signal::derive(move || {
+                    baseline.with(|b| b.as_ref().map(|b| b.cluster_name.clone()).unwrap_or_default())
+                })
+                changes=changes
+                on_revert=Callback::new(move |_: ()| {
+                    if let Some(base) = baseline.get_untracked() {
+                        set_config.set(base);
+                    }
+                })
+            />
+        </Show>
+    }
+    .into_any();
+    let actions = view! {
+        <div class="configurator-actions">
+            <button class="btn btn-primary" disabled=has_errors on:click=on_generate>
+                "Generate CRD YAML"
+            </button>
+            <button
+                class="btn"
+                disabled=deploy_disabled
+                on:click=move |_| dispatch_deploy(true)
+            >
+                "Dry Run"
+            </button>
+            <button
+                class="btn btn-danger"
+                disabled=deploy_disabled
+                on:click=move |_| set_show_deploy_confirm.set(true)
+            >
+                "Deploy"
+            </button>
+        </div>
+        <ConfirmModal
+            title="Deploy MegaDB".to_string()
+            message="Apply the generated MegaDB resource? A cluster with the same name is updated in place.".to_string()
+            show=Signal::derive(move || show_deploy_confirm.get())
+            on_confirm=on_confirm_deploy
+            on_cancel=on_cancel_deploy
+        />
+
+        {move || {
+            let config = last_config.get().filter(|_| show_yaml.get())?;
+            let yaml = generated_yaml.get();
+            Some(view! { <ManifestOutputPanel yaml=yaml config=config /> })
+        }}
+    }
+    .into_any();
+
     view! {
         <div class="configurator-page">
             <div class="configurator-header">
@@ -210,16 +388,26 @@ pub fn ConfiguratorPage() -> impl IntoView {
                         {move || {
                             catalog.get().map(|result| {
                                 match result {
-                                    Ok(families) => {
+                                    Ok(catalog) => {
                                         let filter = selected_filter.get();
-                                        let filtered: Vec<InstanceFamily> = families
+                                        let version = format!(
+                                            "Catalog {} · updated {}",
+                                            catalog.catalog_version,
+                                            catalog.last_updated,
+                                        );
+                                        let filtered: Vec<InstanceFamily> = catalog
+                                            .families
                                             .into_iter()
                                             .filter(|f| match filter {
                                                 Some(t) => f.accelerator_type == t,
                                                 None => true,
                                             })
                                             .collect();
-                                        view! { <InstanceCatalogPanel families=filtered /> }.into_any()
+                                        view! {
+                                            <p class="catalog-version">{version}</p>
+                                            <InstanceCatalogPanel families=filtered />
+                                        }
+                                        .into_any()
                                     }
                                     Err(e) => view! {
                                         <div class="error-panel">
@@ -231,20 +419,8 @@ pub fn ConfiguratorPage() -> impl IntoView {
                         }}
                     </Suspense>
 
-                    <div class="configurator-actions">
-                        <button class="btn btn-primary" on:click=on_generate>
-                            "Generate CRD YAML"
-                        </button>
-                    </div>
-
-                    {move || {
-                        if show_yaml.get() {
-                            let yaml = generated_yaml.get();
-                            Some(view! { <CrdYamlPanel yaml=yaml /> })
-                        } else {
-                            None
-                        }
-                    }}
+                    {editor}
+                    {actions}
                 </div>
 
                 // Right: Cluster Hardware Status
@@ -275,6 +451,184 @@ pub fn ConfiguratorPage() -> impl IntoView {
 
 // ─── Sub-Components ─────────────────────────────────────────────────────────
 
+/// Start from an existing MegaDB: a resource in the cluster, or a manifest
+/// pasted or uploaded as YAML.
+#[component]
+fn ManifestImportPanel(on_import: Callback<DeploymentConfig>) -> impl IntoView {
+    let megadbs = Resource::new(|| (), |_| list_megadbs());
+    let (selected, set_selected) = signal(String::new());
+    let (yaml, set_yaml) = signal(String::new());
+    let (error, set_error) = signal(Option::<String>::None);
+
+    let load_action = Action::new(|name: &String| {
+        let name = name.clone();
+        async move { load_megadb_config(name).await }
+    });
+    let parse_action = Action::new(|yaml: &String| {
+        let yaml = yaml.clone();
+        async move { parse_manifest(yaml).await }
+    });
+    for action in [load_action, parse_action] {
+        Effect::new(move || {
+            if let Some(result) = action.value().get() {
+                match result {
+                    Ok(config) => {
+                        set_error.set(None);
+                        on_import.run(config);
+                    }
+                    Err(e) => set_error.set(Some(format!("Import failed: {e}"))),
+                }
+            }
+        });
+    }
+    let pending = move || load_action.pending().get() || parse_action.pending().get();
+
+    let on_file = move |ev: leptos::ev::Event| {
+        read_selected_file(&ev, move |text| set_yaml.set(text));
+    };
+
+    view! {
+        <div class="manifest-import">
+            <h3>"Import"</h3>
+            <div class="form-row">
+                <div class="form-field">
+                    <label>"From Cluster"</label>
+                    <select on:change=move |ev| set_selected.set(event_target_value(&ev))>
+                        <option value="" selected=move || selected.get().is_empty()>"Select a MegaDB"</option>
+                        <Suspense>
+                            {move || megadbs.get().map(|result| {
+                                result.unwrap_or_default().into_iter().map(|megadb| {
+                                    let name = megadb.name.clone();
+                                    let is_selected = {
+                                        let name = name.clone();
+                                        move || selected.get() == name
+                                    };
+                                    view! {
+                                        <option value=name.clone() selected=is_selected>{name.clone()}</option>
+                                    }
+                                }).collect::<Vec<_>>()
+                            })}
+                        </Suspense>
+                    </select>
+                </div>
+                <button
+                    class="btn"
+                    disabled=move || selected.get().is_empty() || pending()
+                    on:click=move |_| {
+                        load_action.dispatch(selected.get_untracked());
+                    }
+                >
+                    "Load from Cluster"
+                </button>
+            </div>
+            <div class="form-field">
+                <label>"Manifest YAML"</label>
+                <textarea
+                    rows="8"
+                    placeholder="Paste a MegaDB manifest"
+                    prop:value=move || yaml.get()
+                    on:input=move |ev| set_yaml.set(event_target_value(&ev))
+                ></textarea>
+            </div>
+            <div class="form-row">
+                <input type="file" accept=".yaml,.yml" on:change=on_file />
+                <button
+                    class="btn"
+                    disabled=move || yaml.with(|y| y.trim().is_empty()) || pending()
+                    on:click=move |_| {
+                        parse_action.dispatch(yaml.get_untracked());
+                    }
+                >
+                    "Import YAML"
+                </button>
+            </div>
+            {move || error.get().map(|e| view! {
+                <div class="error-panel">
+                    <p>{e}</p>
+                </div>
+            })}
+        </div>
+    }
+}
+
+/// Read the file picked in a file input as text.
+#[cfg(target_arch = "wasm32")]
+fn read_selected_file(ev: &leptos::ev::Event, on_text: impl FnOnce(String) + 'static) {
+    use wasm_bindgen::JsCast;
+
+    let Some(file) = ev
+        .target()
+        .and_then(|t| t.dyn_into::<web_sys::HtmlInputElement>().ok())
+        .and_then(|input| input.files())
+        .and_then(|files| files.get(0))
+    else {
+        return;
+    };
+    leptos::task::spawn_local(async move {
+        if let Ok(text) = wasm_bindgen_futures::JsFuture::from(file.text()).await {
+            if let Some(text) = text.as_string() {
+                on_text(text);
+            }
+        }
+    });
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_selected_file(_ev: &leptos::ev::Event, _on_text: impl FnOnce(String) + 'static) {
+    // No-op on server
+}
+
+/// What the edits change relative to the imported (or last deployed)
+/// MegaDB, shown before applying.
+#[component]
+fn ConfigDiffPanel(
+    name: Signal<String>,
+    changes: Signal<Vec<ConfigChange>>,
+    on_revert: Callback<()>,
+) -> impl IntoView {
+    view! {
+        <div class="config-diff">
+            <div class="status-header">
+                <h3>{move || format!("Changes to {}", name.get())}</h3>
+                <button
+                    class="btn btn-sm"
+                    disabled=move || changes.with(Vec::is_empty)
+                    on:click=move |_| on_revert.run(())
+                >
+                    "Revert"
+                </button>
+            </div>
+            {move || {
+                let changes = changes.get();
+                if changes.is_empty() {
+                    view! { <p class="config-diff-empty">"No changes"</p> }.into_any()
+                } else {
+                    view! {
+                        <table class="config-diff-table">
+                            <thead>
+                                <tr>
+                                    <th>"Field"</th>
+                                    <th>"Current"</th>
+                                    <th>"New"</th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                {changes.into_iter().map(|change| view! {
+                                    <tr>
+                                        <td>{change.field}</td>
+                                        <td class="diff-removed">{change.old.unwrap_or_else(|| "—".to_string())}</td>
+                                        <td class="diff-added">{change.new.unwrap_or_else(|| "—".to_string())}</td>
+                                    </tr>
+                                }).collect::<Vec<_>>()}
+                            </tbody>
+                        </table>
+                    }.into_any()
+                }
+            }}
+        </div>
+    }
+}
+
 /// Cloud provider selection tabs.
 #[component]
 fn ProviderTabs(
@@ -445,6 +799,14 @@ fn ClusterHardwarePanel(status: ClusterHardwareStatus) -> impl IntoView {
     let tpu_available = status.tpu_available;
     let has_tpus = total_tpus != 0;
 
+    // Each node's catalog entry, in node order
+    let cost = status.cost.clone();
+    let mut matches = cost
+        .as_ref()
+        .map(|c| c.nodes.clone())
+        .unwrap_or_default()
+        .into_iter();
+
     view! {
         <div class="cluster-hardware-panel">
             <div class="hw-status-row">
@@ -456,6 +818,27 @@ fn ClusterHardwarePanel(status: ClusterHardwareStatus) -> impl IntoView {
                 <span class="hw-value">{provider_label}</span>
             </div>
 
+            {cost.map(|cost| {
+                let unmatched = cost.unknown_nodes + cost.unpriced_nodes;
+                view! {
+                    <div class="hw-status-row">
+                        <span class="hw-label">"Current Cost"</span>
+                        <span class="hw-value">
+                            {format!(
+                                "${:.2}/hr · ${:.0}/mo",
+                                cost.hourly_cost_usd,
+                                cost.monthly_cost_usd,
+                            )}
+                        </span>
+                    </div>
+                    {(unmatched > 0).then(|| view! {
+                        <p class="hw-cost-note">
+                            {format!("{unmatched} node(s) not priced: instance type unknown to the catalog or without a price")}
+                        </p>
+                    })}
+                }
+            })}
+
             <h4>"Accelerator Summary"</h4>
             <div class="hw-summary-grid">
                 <HardwareSummaryCard
@@ -487,11 +870,24 @@ fn ClusterHardwarePanel(status: ClusterHardwareStatus) -> impl IntoView {
             <h4>"Node Details"</h4>
             <div class="node-list">
                 {status.nodes.into_iter().map(|node| {
+                    let catalog_match = matches.next();
+                    let price = match catalog_match.as_ref() {
+                        Some(m) if m.instance.is_none() => "not in catalog".to_string(),
+                        Some(m) => m
+                            .price_per_hour_usd()
+                            .map(|p| format!("${p:.2}/hr"))
+                            .unwrap_or_else(|| "no price".to_string()),
+                        None => String::new(),
+                    };
+                    let price_class = match catalog_match.as_ref().and_then(|m| m.instance.as_ref()) {
+                        Some(_) => "node-price",
+                        None => "node-price node-price--unknown",
+                    };
                     let node_name = node.node_name.clone();
                     let node_title = node.node_name.clone();
                     let instance_type = node.provider_instance_type.clone()
                         .unwrap_or_else(|| "unknown".to_string());
-                    let cpu = node.cpu_allocatable.clone();
+                    let cpu = node.cpu_cores();
                     let mem_gb = node.memory_allocatable_bytes / (1024 * 1024 * 1024);
                     let has_accel = !node.accelerators.is_empty();
                     view! {
@@ -499,6 +895,7 @@ fn ClusterHardwarePanel(status: ClusterHardwareStatus) -> impl IntoView {
                             <div class="node-card-header">
                                 <span class="node-name" title=node_title>{node_name}</span>
                                 <span class="node-instance-type">{instance_type}</span>
+                                <span class=price_class>{price}</span>
                             </div>
                             <div class="node-card-resources">
                                 <span>{format!("{cpu} vCPU")}</span>
@@ -563,15 +960,100 @@ fn HardwareSummaryCard(
     }
 }
 
-/// CRD YAML output panel with copy button.
+/// Generated output in the selected format, one block per file, with a
+/// zip download of the files.
 #[component]
-fn CrdYamlPanel(yaml: String) -> impl IntoView {
+fn ManifestOutputPanel(yaml: String, config: DeploymentConfig) -> impl IntoView {
+    let (format, set_format) = signal(ExportFormat::Crd);
+    let cluster_name = config.cluster_name.clone();
+    let archive_name = move || format!("{cluster_name}-{}.zip", format.get_untracked().slug());
+    let files = Resource::new(
+        move || format.get(),
+        move |format| {
+            let (config, yaml) = (config.clone(), yaml.clone());
+            async move {
+                match format {
+                    // Already generated by the Generate button
+                    ExportFormat::Crd => Ok(vec![ExportFile {
+                        path: "megadb.yaml".to_string(),
+                        content: yaml,
+                    }]),
+                    _ => export_config(config, format).await,
+                }
+            }
+        },
+    );
+
+    let on_download = move |_| {
+        if let Some(Ok(files)) = files.get_untracked() {
+            download_bytes(&zip_files(&files), &archive_name(), "application/zip");
+        }
+    };
+
     view! {
         <div class="crd-yaml-panel">
             <div class="yaml-header">
-                <h4>"Generated MegaDB CRD"</h4>
+                <h4>"Generated Output"</h4>
+                <select on:change=move |ev| {
+                    let value = event_target_value(&ev);
+                    if let Some(f) = ExportFormat::all().iter().find(|f| f.slug() == value) {
+                        set_format.set(*f);
+                    }
+                }>
+                    {ExportFormat::all().iter().map(|f| {
+                        let f = *f;
+                        view! {
+                            <option value=f.slug() selected=move || format.get() == f>{f.label()}</option>
+                        }
+                    }).collect::<Vec<_>>()}
+                </select>
+                <button class="btn btn-sm" on:click=on_download>"Download .zip"</button>
             </div>
-            <pre class="yaml-content"><code>{yaml}</code></pre>
+            <Suspense fallback=|| view! { <p class="loading">"Rendering..."</p> }>
+                {move || files.get().map(|result| match result {
+                    Ok(files) => files.into_iter().map(|file| view! {
+                        <div class="yaml-file">
+                            <h5 class="yaml-file-path">{file.path}</h5>
+                            <pre class="yaml-content"><code>{file.content}</code></pre>
+                        </div>
+                    }).collect::<Vec<_>>().into_any(),
+                    Err(e) => view! {
+                        <div class="error-panel">
+                            <p>{format!("Failed to render: {e}")}</p>
+                        </div>
+                    }.into_any(),
+                })}
+            </Suspense>
         </div>
     }
 }
+
+#[cfg(target_arch = "wasm32")]
+fn download_bytes(bytes: &[u8], filename: &str, mime_type: &str) {
+    use wasm_bindgen::JsCast;
+
+    let Some(document) = web_sys::window().and_then(|w| w.document()) else {
+        return;
+    };
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(bytes));
+    let opts = web_sys::BlobPropertyBag::new();
+    opts.set_type(mime_type);
+    let Ok(blob) = web_sys::Blob::new_with_u8_array_sequence_and_options(&parts, &opts) else {
+        return;
+    };
+    let Ok(url) = web_sys::Url::create_object_url_with_blob(&blob) else {
+        return;
+    };
+    if let Ok(element) = document.create_element("a") {
+        let a: web_sys::HtmlAnchorElement = element.unchecked_into();
+        a.set_href(&url);
+        a.set_download(filename);
+        a.click();
+    }
+    let _ = web_sys::Url::revoke_object_url(&url);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn download_bytes(_bytes: &[u8], _filename: &str, _mime_type: &str) {
+    // No-op on server
+}
@@ -1,87 +1,104 @@
 use leptos::prelude::*;
+use megafactory_sql_types::connection::ConnectionConfig;
+use megafactory_sql_types::exec::ExecPolicy;
 use megafactory_sql_types::k8s::*;
+use megafactory_sql_types::toast::ToastLevel;
+use megafactory_sql_types::ws::K8sEvent;
 
 use crate::components::auto_refresh::{AutoRefreshControl, RefreshInterval};
+use crate::components::event_timeline::EventTimeline;
+use crate::components::log_viewer::LogViewer;
+use crate::components::modal::ConfirmModal;
 use crate::components::pod_card::PodCard;
 use crate::components::scaling_panel::ScalingPanel;
 use crate::components::storage_panel::StoragePanel;
+use crate::components::terminal::PodTerminal;
+use crate::state::connection::use_connection_state;
+use crate::state::toast::{push_toast, use_toast_write};
 
-/// Server function to get cluster status.
-/// Returns mock data in Phase 1.
+/// Server function to get cluster status from the Kubernetes API.
 #[server(GetClusterStatus, "/api")]
 pub async fn get_cluster_status() -> Result<ClusterStatus, ServerFnError> {
-    Ok(ClusterStatus {
-        phase: "Ready".to_string(),
-        ready_replicas: 3,
-        total_replicas: 3,
-        coordinator_endpoint: "10.0.1.5:5432".to_string(),
-        message: "All pods healthy".to_string(),
-        pods: vec![
-            PodInfo {
-                name: "megadb-coordinator-0".into(),
-                role: "coordinator".into(),
-                status: "Running".into(),
-                cpu_usage_percent: 23.0,
-                memory_bytes: 4_400_000_000,
-                memory_limit_bytes: 8_000_000_000,
-                disk_usage_percent: 45.0,
-                restart_count: 0,
-                age_seconds: 86400 * 3,
-            },
-            PodInfo {
-                name: "megadb-worker-0".into(),
-                role: "worker".into(),
-                status: "Running".into(),
-                cpu_usage_percent: 67.0,
-                memory_bytes: 12_700_000_000,
-                memory_limit_bytes: 16_000_000_000,
-                disk_usage_percent: 72.0,
-                restart_count: 0,
-                age_seconds: 86400 * 3,
-            },
-            PodInfo {
-                name: "megadb-worker-1".into(),
-                role: "worker".into(),
-                status: "Running".into(),
-                cpu_usage_percent: 45.0,
-                memory_bytes: 9_100_000_000,
-                memory_limit_bytes: 16_000_000_000,
-                disk_usage_percent: 31.0,
-                restart_count: 1,
-                age_seconds: 86400 * 2,
-            },
-        ],
-        volumes: vec![
-            VolumeInfo {
-                name: "wal-pvc".into(),
-                access_mode: "ReadWriteMany".into(),
-                capacity_bytes: 50 * 1024 * 1024 * 1024,
-                used_bytes: 12 * 1024 * 1024 * 1024,
-                bound_pod: "shared".into(),
-            },
-            VolumeInfo {
-                name: "cache-pvc-worker-0".into(),
-                access_mode: "ReadWriteOnce".into(),
-                capacity_bytes: 100 * 1024 * 1024 * 1024,
-                used_bytes: 72 * 1024 * 1024 * 1024,
-                bound_pod: "megadb-worker-0".into(),
-            },
-            VolumeInfo {
-                name: "cache-pvc-worker-1".into(),
-                access_mode: "ReadWriteOnce".into(),
-                capacity_bytes: 100 * 1024 * 1024 * 1024,
-                used_bytes: 31 * 1024 * 1024 * 1024,
-                bound_pod: "megadb-worker-1".into(),
-            },
-        ],
-    })
+    let ctx = crate::server::server_context()?;
+    Ok(ctx.k8s.cluster_status().await?)
 }
 
-/// Server function to scale the cluster.
+/// Server function to get logged cluster events newer than id `since`,
+/// oldest first.
+#[server(GetK8sEvents, "/api")]
+pub async fn get_k8s_events(since: u64) -> Result<Vec<K8sEvent>, ServerFnError> {
+    let ctx = crate::server::server_context()?;
+    Ok(ctx.k8s.events_since(since))
+}
+
+/// Server function to scale the MegaDB worker StatefulSet.
 #[server(ScaleCluster, "/api")]
-pub async fn scale_cluster(replicas: i32) -> Result<String, ServerFnError> {
-    // Phase 1: mock. Phase 3: proxy to K8s API.
-    Ok(format!("Scaling to {replicas} replicas (mock)"))
+pub async fn scale_cluster(request: ScaleRequest) -> Result<ScaleResponse, ServerFnError> {
+    let ctx = crate::server::server_context()?;
+    Ok(ctx.k8s.scale(&request).await?)
+}
+
+/// Server function to get the replica bounds enforced when scaling.
+#[server(GetScalingLimits, "/api")]
+pub async fn get_scaling_limits() -> Result<KedaConfig, ServerFnError> {
+    let ctx = crate::server::server_context()?;
+    Ok(ctx.k8s.scaling().clone())
+}
+
+/// Server function to get the worker ScaledObject; `None` when the workers
+/// aren't autoscaled by KEDA.
+#[server(GetAutoscaler, "/api")]
+pub async fn get_autoscaler() -> Result<Option<AutoscalerStatus>, ServerFnError> {
+    let ctx = crate::server::server_context()?;
+    Ok(ctx.k8s.autoscaler().await?)
+}
+
+/// Server function to change the worker ScaledObject's bounds and thresholds.
+#[server(UpdateAutoscaler, "/api")]
+pub async fn update_autoscaler(
+    update: AutoscalerUpdate,
+) -> Result<AutoscalerStatus, ServerFnError> {
+    let ctx = crate::server::server_context()?;
+    Ok(ctx.k8s.update_autoscaler(&update).await?)
+}
+
+/// Server function to get the commands the exec terminal may run.
+#[server(GetExecPolicy, "/api")]
+pub async fn get_exec_policy() -> Result<ExecPolicy, ServerFnError> {
+    let ctx = crate::server::server_context()?;
+    Ok(ctx.k8s.exec_policy().clone())
+}
+
+/// Server function to expand one of the cluster's PVCs.
+#[server(ResizeClusterVolume, "/api")]
+pub async fn resize_cluster_volume(name: String, size_gib: u64) -> Result<(), ServerFnError> {
+    let ctx = crate::server::server_context()?;
+    Ok(ctx
+        .k8s
+        .resize_volume(&name, &VolumeResizeRequest { size_gib })
+        .await?)
+}
+
+/// Server function to restart, delete or drain cluster pods. Draining goes
+/// through the MegaDB HTTP API of `connection`.
+#[server(RunPodAction, "/api")]
+pub async fn run_pod_action(
+    action: PodAction,
+    connection: Option<ConnectionConfig>,
+) -> Result<(), ServerFnError> {
+    let ctx = crate::server::server_context()?;
+    match action {
+        PodAction::RollingRestart => ctx.k8s.rolling_restart().await?,
+        PodAction::DeletePod { pod } => ctx.k8s.delete_pod(&pod).await?,
+        PodAction::DrainWorker { pod } => {
+            let connection = connection
+                .ok_or_else(|| ServerFnError::new("Draining needs an active connection"))?;
+            ctx.k8s
+                .drain_worker(&ctx.megadb, &connection.http_base_url(), &pod)
+                .await?
+        }
+    }
+    Ok(())
 }
 
 /// Kubernetes Dashboard page.
@@ -89,11 +106,68 @@ pub async fn scale_cluster(replicas: i32) -> Result<String, ServerFnError> {
 pub fn K8sDashboardPage() -> impl IntoView {
     let (refresh_counter, set_refresh_counter) = signal(0u32);
     let cluster = Resource::new(move || refresh_counter.get(), |_| get_cluster_status());
+    let scaling = Resource::new(|| (), |_| get_scaling_limits());
+    let autoscaler = Resource::new(move || refresh_counter.get(), |_| get_autoscaler());
+    let exec_policy = Resource::new(|| (), |_| get_exec_policy());
 
     let on_refresh = Callback::new(move |_: ()| {
         set_refresh_counter.update(|c| *c += 1);
     });
 
+    // Pods whose logs / exec terminal are open; kept outside the refreshed
+    // cluster view so a refresh doesn't end the stream or session
+    let (log_pod, set_log_pod) = signal(Option::<String>::None);
+    let on_logs = Callback::new(move |pod: String| set_log_pod.set(Some(pod)));
+    let (exec_pod, set_exec_pod) = signal(Option::<String>::None);
+    let on_exec = Callback::new(move |pod: String| set_exec_pod.set(Some(pod)));
+    // Restart / delete / drain awaiting confirmation
+    let (conn_state, _) = use_connection_state();
+    let toast = use_toast_write();
+    let (pending_action, set_pending_action) = signal(Option::<PodAction>::None);
+    let on_action = Callback::new(move |action: PodAction| set_pending_action.set(Some(action)));
+    let pod_action = Action::new(
+        move |(action, connection): &(PodAction, Option<ConnectionConfig>)| {
+            let action = action.clone();
+            let connection = connection.clone();
+            async move {
+                let title = action.title();
+                (title, run_pod_action(action, connection).await)
+            }
+        },
+    );
+
+    Effect::new(move || {
+        if let Some((title, result)) = pod_action.value().get() {
+            match result {
+                Ok(()) => {
+                    push_toast(toast, ToastLevel::Success, format!("{title} started"));
+                    set_refresh_counter.update(|c| *c += 1);
+                }
+                Err(e) => push_toast(toast, ToastLevel::Error, format!("{title} failed: {e}")),
+            }
+        }
+    });
+
+    let on_action_confirm = Callback::new(move |_: ()| {
+        let Some(action) = pending_action.get_untracked() else {
+            return;
+        };
+        set_pending_action.set(None);
+        let connection = conn_state.get_untracked().active;
+        if matches!(action, PodAction::DrainWorker { .. }) && connection.is_none() {
+            push_toast(toast, ToastLevel::Error, "No active connection");
+            return;
+        }
+        pod_action.dispatch((action, connection));
+    });
+
+    let exec_enabled = Signal::derive(move || {
+        exec_policy
+            .get()
+            .and_then(Result::ok)
+            .is_some_and(|p| p.enabled)
+    });
+
     view! {
         <div class="k8s-dashboard-page">
             <div class="k8s-dashboard-header">
@@ -106,10 +180,23 @@ pub fn K8sDashboardPage() -> impl IntoView {
 
             <Suspense fallback=|| view! { <p>"Loading cluster status..."</p> }>
                 {move || {
+                    let scaling = scaling.get()?;
+                    // Without KEDA (or access to it) the panel is just manual scaling
+                    let autoscaler = autoscaler.get()?.ok().flatten();
                     cluster.get().map(|result| {
-                        match result {
-                            Ok(status) => view! { <ClusterView status=status /> }.into_any(),
-                            Err(e) => view! {
+                        match (result, scaling) {
+                            (Ok(status), Ok(scaling)) => view! {
+                                <ClusterView
+                                    status=status
+                                    scaling=scaling
+                                    autoscaler=autoscaler.clone()
+                                    on_scaled=on_refresh
+                                    on_logs=on_logs
+                                    on_exec=exec_enabled.get().then_some(on_exec)
+                                    on_action=on_action
+                                />
+                            }.into_any(),
+                            (Err(e), _) | (_, Err(e)) => view! {
                                 <div class="error-panel">
                                     <p>{format!("Failed to load cluster status: {e}")}</p>
                                 </div>
@@ -118,38 +205,174 @@ pub fn K8sDashboardPage() -> impl IntoView {
                     })
                 }}
             </Suspense>
+
+            {move || log_pod.get().map(|pod| view! {
+                <LogViewer pod=pod on_close=Callback::new(move |_| set_log_pod.set(None)) />
+            })}
+
+            {move || {
+                let pod = exec_pod.get()?;
+                let policy = exec_policy.get()?.ok()?;
+                Some(view! {
+                    <PodTerminal
+                        pod=pod
+                        policy=policy
+                        on_close=Callback::new(move |_| set_exec_pod.set(None))
+                    />
+                })
+            }}
+
+            {move || pending_action.get().map(|action| view! {
+                <ConfirmModal
+                    title=action.title().to_string()
+                    message=action.description()
+                    show=Signal::derive(|| true)
+                    on_confirm=on_action_confirm
+                    on_cancel=Callback::new(move |_| set_pending_action.set(None))
+                />
+            })}
+
+            <EventTimeline />
         </div>
     }
 }
 
 #[component]
-fn ClusterView(status: ClusterStatus) -> impl IntoView {
+fn ClusterView(
+    status: ClusterStatus,
+    scaling: KedaConfig,
+    autoscaler: Option<AutoscalerStatus>,
+    /// Called after a scale or resize request succeeds, to reload the status.
+    on_scaled: Callback<()>,
+    /// Opens the log viewer for a pod.
+    on_logs: Callback<String>,
+    /// Opens the exec terminal for a pod; `None` when exec is disabled.
+    on_exec: Option<Callback<String>>,
+    /// Asks for confirmation of a restart, delete or drain.
+    on_action: Callback<PodAction>,
+) -> impl IntoView {
+    let toast = use_toast_write();
     let phase_class = match status.phase.as_str() {
         "Ready" => "cluster-phase--ready",
+        "Degraded" | "Unavailable" => "cluster-phase--degraded",
         _ => "cluster-phase--unknown",
     };
 
     let current_replicas = Signal::derive({
-        let r = status.ready_replicas;
+        let r = status.total_replicas;
         move || r
     });
-    let min_replicas = Signal::derive(|| 1i32);
-    let max_replicas = Signal::derive(|| 32i32);
+    let (min, max) = (scaling.min_replicas, scaling.max_replicas);
+    let min_replicas = Signal::derive(move || min);
+    let max_replicas = Signal::derive(move || max);
 
     let scale_action = Action::new(move |replicas: &i32| {
-        let replicas = *replicas;
-        async move { scale_cluster(replicas).await }
+        let request = ScaleRequest {
+            replicas: *replicas,
+        };
+        async move { scale_cluster(request).await }
+    });
+
+    Effect::new(move || {
+        if let Some(result) = scale_action.value().get() {
+            match result {
+                Ok(resp) => {
+                    push_toast(
+                        toast,
+                        ToastLevel::Success,
+                        format!(
+                            "Scaling workers {} -> {} ({} running)",
+                            resp.previous_replicas, resp.replicas, resp.current_replicas
+                        ),
+                    );
+                    on_scaled.run(());
+                }
+                Err(e) => push_toast(toast, ToastLevel::Error, format!("Scale failed: {e}")),
+            }
+        }
     });
 
     let on_scale = Callback::new(move |replicas: i32| {
         scale_action.dispatch(replicas);
     });
 
+    let autoscaler_action = Action::new(move |update: &AutoscalerUpdate| {
+        let update = update.clone();
+        async move { update_autoscaler(update).await }
+    });
+
+    Effect::new(move || {
+        if let Some(result) = autoscaler_action.value().get() {
+            match result {
+                Ok(status) => {
+                    push_toast(
+                        toast,
+                        ToastLevel::Success,
+                        format!(
+                            "Autoscaling between {} and {} workers",
+                            status.min_replicas, status.max_replicas
+                        ),
+                    );
+                    on_scaled.run(());
+                }
+                Err(e) => push_toast(
+                    toast,
+                    ToastLevel::Error,
+                    format!("Autoscaler update failed: {e}"),
+                ),
+            }
+        }
+    });
+
+    let on_autoscaler_update = Callback::new(move |update: AutoscalerUpdate| {
+        if let Err(e) = update.validate() {
+            push_toast(toast, ToastLevel::Error, e);
+            return;
+        }
+        autoscaler_action.dispatch(update);
+    });
+    let keda_active = scaling.enabled || autoscaler.is_some();
+
     let volumes = Signal::derive({
         let v = status.volumes.clone();
         move || v.clone()
     });
 
+    // Volume expansion can't be undone, so it is confirmed first
+    let (pending_resize, set_pending_resize) = signal(Option::<(String, u64)>::None);
+    let resize_action = Action::new(move |(name, size_gib): &(String, u64)| {
+        let (name, size_gib) = (name.clone(), *size_gib);
+        async move {
+            resize_cluster_volume(name.clone(), size_gib)
+                .await
+                .map(|()| (name, size_gib))
+        }
+    });
+
+    Effect::new(move || {
+        if let Some(result) = resize_action.value().get() {
+            match result {
+                Ok((name, size_gib)) => {
+                    push_toast(
+                        toast,
+                        ToastLevel::Success,
+                        format!("Expanding {name} to {size_gib} GiB"),
+                    );
+                    on_scaled.run(());
+                }
+                Err(e) => push_toast(toast, ToastLevel::Error, format!("Expand failed: {e}")),
+            }
+        }
+    });
+
+    let on_resize = Callback::new(move |req: (String, u64)| set_pending_resize.set(Some(req)));
+    let on_resize_confirm = Callback::new(move |_: ()| {
+        if let Some(req) = pending_resize.get_untracked() {
+            set_pending_resize.set(None);
+            resize_action.dispatch(req);
+        }
+    });
+
     view! {
         <div class="cluster-view">
             <div class="cluster-header">
@@ -161,23 +384,96 @@ fn ClusterView(status: ClusterStatus) -> impl IntoView {
                     <span class="cluster-endpoint">
                         {format!("Coordinator: {}", status.coordinator_endpoint)}
                     </span>
+                    <span class="cluster-message">{status.message.clone()}</span>
                 </div>
+                <button
+                    class="btn btn-sm btn-danger"
+                    on:click=move |_| on_action.run(PodAction::RollingRestart)
+                >
+                    "Rolling Restart"
+                </button>
             </div>
 
+            {(!status.conditions.is_empty()).then(|| view! {
+                <ClusterConditions conditions=status.conditions.clone() />
+            })}
+
             <ScalingPanel
                 current_replicas=current_replicas
                 min_replicas=min_replicas
                 max_replicas=max_replicas
                 on_scale=on_scale
+                autoscaler=autoscaler
+                on_autoscaler_update=on_autoscaler_update
             />
+            {keda_active.then(|| view! {
+                <p class="scaling-info">
+                    "KEDA autoscaling is enabled; it may override manual changes."
+                </p>
+            })}
 
             <div class="pod-grid">
                 {status.pods.iter().map(|pod| {
-                    view! { <PodCard pod=pod.clone() /> }
+                    match on_exec {
+                        Some(on_exec) => view! {
+                            <PodCard
+                                pod=pod.clone()
+                                on_logs=on_logs
+                                on_exec=on_exec
+                                on_action=on_action
+                            />
+                        }.into_any(),
+                        None => view! {
+                            <PodCard pod=pod.clone() on_logs=on_logs on_action=on_action />
+                        }.into_any(),
+                    }
                 }).collect::<Vec<_>>()}
             </div>
 
-            <StoragePanel volumes=volumes />
+            <StoragePanel volumes=volumes on_resize=on_resize />
+            {move || pending_resize.get().map(|(name, size_gib)| view! {
+                <ConfirmModal
+                    title="Expand Volume".to_string()
+                    message=format!(
+                        "Expand {name} to {size_gib} GiB? Volumes can't be shrunk afterwards."
+                    )
+                    show=Signal::derive(|| true)
+                    on_confirm=on_resize_confirm
+                    on_cancel=Callback::new(move |_| set_pending_resize.set(None))
+                />
+            })}
         </div>
     }
 }
+
+/// Conditions reported by the MegaDB operator on the cluster's resource.
+#[component]
+fn ClusterConditions(conditions: Vec<ClusterCondition>) -> impl IntoView {
+    view! {
+        <table class="cluster-conditions">
+            <thead>
+                <tr>
+                    <th>"Condition"</th>
+                    <th>"Status"</th>
+                    <th>"Reason"</th>
+                    <th>"Message"</th>
+                    <th>"Since"</th>
+                </tr>
+            </thead>
+            <tbody>
+                {conditions.into_iter().map(|c| {
+                    let class = format!("condition-status condition-status--{}", c.status.to_lowercase());
+                    view! {
+                        <tr>
+                            <td>{c.condition_type}</td>
+                            <td class=class>{c.status}</td>
+                            <td>{c.reason}</td>
+                            <td>{c.message}</td>
+                            <td>{c.last_transition_time.unwrap_or_default()}</td>
+                        </tr>
+                    }
+                }).collect::<Vec<_>>()}
+            </tbody>
+        </table>
+    }
+}
@@ -1,8 +1,14 @@
 use leptos::prelude::*;
+use megafactory_sql_types::connection::ConnectionConfig;
 use megafactory_sql_types::metrics::*;
+use megafactory_sql_types::toast::ToastLevel;
 
 use crate::components::auto_refresh::{AutoRefreshControl, RefreshInterval};
 use crate::components::chart::{ChartConfig, ChartSeries, ChartType, SvgChart};
+use crate::components::modal::ConfirmModal;
+use crate::state::connection::use_connection_state;
+use crate::state::monitoring::use_monitoring_state;
+use crate::state::toast::{push_toast, use_toast_write};
 
 /// Server function to get query metrics.
 #[server(GetQueryMetrics, "/api")]
@@ -39,6 +45,19 @@ pub async fn get_active_queries() -> Result<Vec<ActiveQuery>, ServerFnError> {
     ])
 }
 
+/// Server function to kill a MegaDB session, stopping its running query.
+#[server(KillSession, "/api")]
+pub async fn kill_session(
+    session_id: String,
+    connection: ConnectionConfig,
+) -> Result<(), ServerFnError> {
+    let ctx = crate::server::server_context()?;
+    Ok(ctx
+        .megadb
+        .kill_session(&connection.http_base_url(), &session_id)
+        .await?)
+}
+
 /// Server function to get mock time series data for charts.
 #[server(GetMetricsTimeSeries, "/api")]
 pub async fn get_metrics_time_series() -> Result<Vec<TimeSeries>, ServerFnError> {
@@ -77,6 +96,9 @@ pub async fn get_metrics_time_series() -> Result<Vec<TimeSeries>, ServerFnError>
 /// Performance Monitoring page.
 #[component]
 pub fn MonitoringPage() -> impl IntoView {
+    let (conn_state, _) = use_connection_state();
+    let (monitoring_state, _) = use_monitoring_state();
+    let toast = use_toast_write();
     let (refresh_counter, set_refresh_counter) = signal(0u32);
     let metrics = Resource::new(move || refresh_counter.get(), |_| get_query_metrics());
     let active_queries = Resource::new(move || refresh_counter.get(), |_| get_active_queries());
@@ -86,6 +108,40 @@ pub fn MonitoringPage() -> impl IntoView {
         set_refresh_counter.update(|c| *c += 1);
     });
 
+    // Session awaiting kill confirmation
+    let (pending_kill, set_pending_kill) = signal(Option::<String>::None);
+    let kill_action = Action::new(
+        move |(session_id, connection): &(String, ConnectionConfig)| {
+            let session_id = session_id.clone();
+            let connection = connection.clone();
+            async move { kill_session(session_id, connection).await }
+        },
+    );
+
+    Effect::new(move || {
+        if let Some(result) = kill_action.value().get() {
+            match result {
+                Ok(()) => {
+                    push_toast(toast, ToastLevel::Success, "Session killed");
+                    set_refresh_counter.update(|c| *c += 1);
+                }
+                Err(e) => push_toast(toast, ToastLevel::Error, format!("Kill failed: {e}")),
+            }
+        }
+    });
+
+    let on_kill_confirm = Callback::new(move |_: ()| {
+        let Some(session_id) = pending_kill.get_untracked() else {
+            return;
+        };
+        set_pending_kill.set(None);
+        let Some(connection) = conn_state.get_untracked().active else {
+            push_toast(toast, ToastLevel::Error, "No active connection");
+            return;
+        };
+        kill_action.dispatch((session_id, connection));
+    });
+
     view! {
         <div class="monitoring-page">
             <div class="monitoring-header">
@@ -96,11 +152,15 @@ pub fn MonitoringPage() -> impl IntoView {
                 />
             </div>
 
+            // Live WebSocket metrics take over from the fetched snapshot once they arrive
             <Suspense fallback=|| view! { <p>"Loading metrics..."</p> }>
                 {move || {
+                    if let Some(m) = monitoring_state.with(|s| s.live_metrics.clone()) {
+                        return Some(view! { <MetricsOverview metrics=m live=true /> }.into_any());
+                    }
                     metrics.get().map(|result| {
                         match result {
-                            Ok(m) => view! { <MetricsOverview metrics=m /> }.into_any(),
+                            Ok(m) => view! { <MetricsOverview metrics=m live=false /> }.into_any(),
                             Err(e) => view! { <p class="error">{format!("Error: {e}")}</p> }.into_any(),
                         }
                     })
@@ -164,21 +224,37 @@ pub fn MonitoringPage() -> impl IntoView {
                     {move || {
                         active_queries.get().map(|result| {
                             match result {
-                                Ok(queries) => view! { <ActiveQueriesTable queries=queries /> }.into_any(),
+                                Ok(queries) => view! {
+                                    <ActiveQueriesTable
+                                        queries=queries
+                                        on_kill=Callback::new(move |id: String| set_pending_kill.set(Some(id)))
+                                    />
+                                }.into_any(),
                                 Err(e) => view! { <p class="error">{format!("Error: {e}")}</p> }.into_any(),
                             }
                         })
                     }}
                 </Suspense>
             </div>
+
+            <ConfirmModal
+                title="Kill Session".to_string()
+                message="Terminate this session? Its running query will be cancelled.".to_string()
+                show=Signal::derive(move || pending_kill.get().is_some())
+                on_confirm=on_kill_confirm
+                on_cancel=Callback::new(move |_| set_pending_kill.set(None))
+            />
         </div>
     }
 }
 
 #[component]
-fn MetricsOverview(metrics: QueryMetrics) -> impl IntoView {
+fn MetricsOverview(metrics: QueryMetrics, live: bool) -> impl IntoView {
     view! {
         <div class="metrics-overview">
+            {live.then(|| view! {
+                <span class="metrics-live" title="Streaming over WebSocket">"Live"</span>
+            })}
             <div class="metrics-grid">
                 <MetricCard label="Queries/sec" value=format!("{:.0}", metrics.queries_per_second) />
                 <MetricCard label="Avg Latency" value=format!("{:.0}ms", metrics.avg_latency_ms) />
@@ -202,7 +278,7 @@ fn MetricCard(label: &'static str, value: String) -> impl IntoView {
 }
 
 #[component]
-fn ActiveQueriesTable(queries: Vec<ActiveQuery>) -> impl IntoView {
+fn ActiveQueriesTable(queries: Vec<ActiveQuery>, on_kill: Callback<String>) -> impl IntoView {
     view! {
         <table class="active-queries-table">
             <thead>
@@ -211,6 +287,7 @@ fn ActiveQueriesTable(queries: Vec<ActiveQuery>) -> impl IntoView {
                     <th>"Duration"</th>
                     <th>"State"</th>
                     <th>"Query"</th>
+                    <th></th>
                 </tr>
             </thead>
             <tbody>
@@ -220,6 +297,7 @@ fn ActiveQueriesTable(queries: Vec<ActiveQuery>) -> impl IntoView {
                     } else {
                         format!("{}ms", q.duration_ms)
                     };
+                    let session_id = q.session_id.clone();
                     view! {
                         <tr>
                             <td class="session-id">{q.session_id.clone()}</td>
@@ -232,6 +310,15 @@ fn ActiveQueriesTable(queries: Vec<ActiveQuery>) -> impl IntoView {
                             <td class="query-preview" title=q.sql_preview.clone()>
                                 {q.sql_preview.clone()}
                             </td>
+                            <td>
+                                <button
+                                    class="btn btn-sm btn-danger"
+                                    title="Kill session"
+                                    on:click=move |_| on_kill.run(session_id.clone())
+                                >
+                                    "Kill"
+                                </button>
+                            </td>
                         </tr>
                     }
                 }).collect::<Vec<_>>()}
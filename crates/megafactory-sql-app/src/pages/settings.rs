@@ -85,6 +85,34 @@ pub fn SettingsPage() -> impl IntoView {
                         }
                     />
                 </div>
+                <div class="setting-item">
+                    <label>"Demo Mode (mock results)"</label>
+                    <input
+                        type="checkbox"
+                        prop:checked=move || settings.get().demo_mode
+                        on:change=move |_| {
+                            set_settings.update(|s| s.demo_mode = !s.demo_mode);
+                        }
+                    />
+                </div>
+            </div>
+
+            <div class="settings-section">
+                <h3>"Kubernetes"</h3>
+                <div class="setting-item">
+                    <label>"Volume Usage Warning (%)"</label>
+                    <input
+                        type="number"
+                        min="1"
+                        max="100"
+                        prop:value=move || settings.get().volume_warn_percent.to_string()
+                        on:input=move |ev| {
+                            if let Ok(v) = event_target_value(&ev).parse::<u32>() {
+                                set_settings.update(|s| s.volume_warn_percent = v.clamp(1, 100));
+                            }
+                        }
+                    />
+                </div>
             </div>
 
             <div class="settings-section">
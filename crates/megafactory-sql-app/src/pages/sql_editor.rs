@@ -1,5 +1,7 @@
 use leptos::prelude::*;
-use megafactory_sql_types::query::{QueryHistoryEntry, QueryResult};
+use megafactory_sql_types::query::{
+    QueryHistoryEntry, QueryRequest, QueryResult, QueryResultChunk, StreamQueryRequest,
+};
 use megafactory_sql_types::toast::ToastLevel;
 
 use crate::components::codemirror::CodeMirrorEditor;
@@ -7,11 +9,13 @@ use crate::components::query_history::QueryHistoryPanel;
 use crate::components::query_plan::QueryPlanViewer;
 use crate::components::result_table::ResultTable;
 use crate::components::tab_bar::{Tab, TabBar};
+use crate::query_stream::stream_query;
+use crate::state::connection::use_connection_state;
 use crate::state::query::use_query_state;
+use crate::state::settings::use_settings_state;
 use crate::state::toast::{push_toast, use_toast_write};
 
-/// Server function to execute SQL against MegaDB.
-/// In Phase 1, returns mock data. Will proxy to MegaDB HTTP API later.
+/// Canned query results served when demo mode is enabled in Settings.
 #[cfg(feature = "ssr")]
 async fn mock_execute_query(sql: &str) -> QueryResult {
     use megafactory_sql_types::query::QueryColumn;
@@ -102,15 +106,27 @@ async fn mock_execute_query(sql: &str) -> QueryResult {
     }
 }
 
+/// Server function serving demo mode's canned results. Real queries are
+/// streamed through [`stream_query`].
 #[server(ExecuteQuery, "/api")]
-pub async fn execute_query(sql: String, _database: String) -> Result<QueryResult, ServerFnError> {
-    Ok(mock_execute_query(&sql).await)
+pub async fn execute_query(request: QueryRequest) -> Result<QueryResult, ServerFnError> {
+    Ok(mock_execute_query(&request.sql).await)
+}
+
+/// Server function to cancel a running query by its execution id.
+/// Returns `false` if the query already finished.
+#[server(CancelQuery, "/api")]
+pub async fn cancel_query(query_id: uuid::Uuid) -> Result<bool, ServerFnError> {
+    let ctx = crate::server::server_context()?;
+    Ok(ctx.megadb.cancel(query_id).await?)
 }
 
 /// SQL Editor page with multi-tab support, query history, and EXPLAIN visualization.
 #[component]
 pub fn SqlEditorPage() -> impl IntoView {
     let (query_state, set_query_state) = use_query_state();
+    let (conn_state, _) = use_connection_state();
+    let (settings, _) = use_settings_state();
     let toast = use_toast_write();
 
     let (show_history, set_show_history) = signal(false);
@@ -130,8 +146,10 @@ pub fn SqlEditorPage() -> impl IntoView {
     });
 
     let active_signal = Signal::derive(move || query_state.get().active_tab_index);
+    let active_tab_id = Memo::new(move |_| query_state.with(|s| s.active_tab().id));
     let result_signal = Signal::derive(move || query_state.get().active_tab().result.clone());
     let is_running = Signal::derive(move || query_state.get().active_tab().is_running);
+    let progress = Signal::derive(move || query_state.get().active_tab().progress.clone());
     let sql_content = Signal::derive(move || query_state.get().active_tab().sql.clone());
 
     // Track whether current result is an EXPLAIN plan
@@ -162,84 +180,162 @@ pub fn SqlEditorPage() -> impl IntoView {
             .and_then(|v| v.as_str().map(String::from))
     });
 
-    // Execute query action
-    let execute_action = Action::new(move |sql: &String| {
-        let sql = sql.clone();
-        async move { execute_query(sql, "megadb".to_string()).await }
+    // Record a finished execution: toast, history entry, and the tab's final
+    // result. Ignored if the execution is no longer current (e.g. cancelled).
+    let finish_query = move |query_id: uuid::Uuid, result: QueryResult, truncated: bool| {
+        let Some(sql) =
+            query_state.with_untracked(|s| s.running_tab(query_id).map(|t| t.sql.clone()))
+        else {
+            return;
+        };
+        if !result.is_ok() {
+            push_toast(
+                toast,
+                ToastLevel::Error,
+                result.error.clone().unwrap_or_default(),
+            );
+        } else if truncated {
+            push_toast(
+                toast,
+                ToastLevel::Warning,
+                format!(
+                    "Showing the first {} rows (row limit reached) in {}ms",
+                    result.row_count, result.execution_time_ms
+                ),
+            );
+        } else {
+            push_toast(
+                toast,
+                ToastLevel::Success,
+                format!(
+                    "Query returned {} rows in {}ms",
+                    result.row_count, result.execution_time_ms
+                ),
+            );
+        }
+        let database = conn_state
+            .get_untracked()
+            .active
+            .map(|c| c.database)
+            .unwrap_or_default();
+        let entry = QueryHistoryEntry {
+            id: uuid::Uuid::new_v4(),
+            sql,
+            database,
+            execution_time_ms: result.execution_time_ms,
+            row_count: result.row_count,
+            executed_at: chrono::Utc::now(),
+            success: result.is_ok(),
+        };
+        set_query_state.update(|s| {
+            if let Some(tab) = s.running_tab_mut(query_id) {
+                tab.result = Some(result);
+                tab.is_running = false;
+                tab.query_id = None;
+                tab.progress = None;
+            }
+            s.push_history(entry);
+        });
+    };
+
+    // Demo mode: one-shot server function returning the whole result
+    let execute_action = Action::new(move |(query_id, request): &(uuid::Uuid, QueryRequest)| {
+        let query_id = *query_id;
+        let request = request.clone();
+        async move { (query_id, execute_query(request).await) }
     });
 
     // Update result and history when action completes
     Effect::new(move || {
-        if let Some(result_value) = execute_action.value().get() {
-            let sql = query_state.get_untracked().active_tab().sql.clone();
-            match result_value {
-                Ok(ref r) => {
-                    let result = r.clone();
-                    if result.is_ok() {
-                        push_toast(
-                            toast,
-                            ToastLevel::Success,
-                            format!(
-                                "Query returned {} rows in {}ms",
-                                result.row_count, result.execution_time_ms
-                            ),
-                        );
-                    } else {
-                        push_toast(
-                            toast,
-                            ToastLevel::Error,
-                            result.error.clone().unwrap_or_default(),
-                        );
-                    }
-                    let entry = QueryHistoryEntry {
-                        id: uuid::Uuid::new_v4(),
-                        sql,
-                        database: "megadb".to_string(),
-                        execution_time_ms: result.execution_time_ms,
-                        row_count: result.row_count,
-                        executed_at: chrono::Utc::now(),
-                        success: result.is_ok(),
-                    };
-                    set_query_state.update(|s| {
-                        s.active_tab_mut().result = Some(result);
-                        s.active_tab_mut().is_running = false;
-                        s.push_history(entry);
-                    });
-                }
-                Err(ref e) => {
-                    let err_msg = e.to_string();
-                    push_toast(toast, ToastLevel::Error, &err_msg);
-                    let entry = QueryHistoryEntry {
-                        id: uuid::Uuid::new_v4(),
-                        sql,
-                        database: "megadb".to_string(),
-                        execution_time_ms: 0,
-                        row_count: 0,
-                        executed_at: chrono::Utc::now(),
-                        success: false,
-                    };
-                    set_query_state.update(|s| {
-                        s.active_tab_mut().result = Some(QueryResult {
-                            columns: vec![],
-                            rows: vec![],
-                            row_count: 0,
-                            execution_time_ms: 0,
-                            error: Some(err_msg),
-                        });
-                        s.active_tab_mut().is_running = false;
-                        s.push_history(entry);
-                    });
-                }
-            }
+        if let Some((query_id, result_value)) = execute_action.value().get() {
+            let result = result_value.unwrap_or_else(|e| QueryResult {
+                error: Some(e.to_string()),
+                ..QueryResult::empty()
+            });
+            finish_query(query_id, result, false);
         }
     });
 
     let on_execute = Callback::new(move |sql: String| {
+        let Some(connection) = conn_state.get_untracked().active else {
+            push_toast(
+                toast,
+                ToastLevel::Error,
+                "No active connection. Add one on the Connections page.",
+            );
+            return;
+        };
+        let settings = settings.get_untracked();
+        let query_id = uuid::Uuid::new_v4();
+        let request = QueryRequest {
+            sql: sql.clone(),
+            database: connection.database.clone(),
+            limit: Some(settings.row_limit),
+            query_id: Some(query_id),
+        };
         set_query_state.update(|s| {
-            s.active_tab_mut().sql = sql.clone();
-            s.active_tab_mut().is_running = true;
+            let tab = s.active_tab_mut();
+            tab.sql = sql;
+            tab.result = None;
+            tab.is_running = true;
+            tab.query_id = Some(query_id);
+            tab.progress = None;
+        });
+
+        if settings.demo_mode {
+            execute_action.dispatch((query_id, request));
+            return;
+        }
+
+        // Stream rows into the tab as they arrive
+        let body = StreamQueryRequest {
+            request,
+            connection: Some(connection),
+        };
+        let max_rows = settings.row_limit as usize;
+        leptos::task::spawn_local(async move {
+            let mut truncated = false;
+            let outcome = stream_query(&body, |chunk| {
+                if let QueryResultChunk::Done { truncated: t, .. } = chunk {
+                    truncated = t;
+                }
+                set_query_state.update(|s| {
+                    if let Some(tab) = s.running_tab_mut(query_id) {
+                        tab.result
+                            .get_or_insert_with(QueryResult::empty)
+                            .apply_chunk(chunk, max_rows);
+                    }
+                });
+            })
+            .await;
+
+            let mut result = query_state
+                .with_untracked(|s| s.running_tab(query_id).and_then(|t| t.result.clone()))
+                .unwrap_or_else(QueryResult::empty);
+            if let Err(e) = outcome {
+                result.error = Some(e);
+            }
+            finish_query(query_id, result, truncated);
+        });
+    });
+
+    // Stop the active tab's query: record it as cancelled right away, then ask
+    // the server to abort it. Late results for it are ignored.
+    let on_cancel = Callback::new(move |_: ()| {
+        let Some((query_id, mut result)) = query_state.with_untracked(|s| {
+            let tab = s.active_tab();
+            tab.query_id
+                .map(|id| (id, tab.result.clone().unwrap_or_else(QueryResult::empty)))
+        }) else {
+            return;
+        };
+        result.error = Some("Query cancelled".to_string());
+        finish_query(query_id, result, false);
+        leptos::task::spawn_local(async move {
+            if let Err(e) = cancel_query(query_id).await {
+                push_toast(toast, ToastLevel::Error, format!("Cancel failed: {e}"));
+            }
         });
-        execute_action.dispatch(sql);
     });
 
     let on_tab_select = Callback::new(move |i: usize| {
@@ -288,6 +384,11 @@ pub fn SqlEditorPage() -> impl IntoView {
                         >
                             {move || if is_running.get() { "Running..." } else { "Run (Ctrl+Enter)" }}
                         </button>
+                        <Show when=move || is_running.get()>
+                            <button class="btn btn-danger" on:click=move |_| on_cancel.run(())>
+                                "Cancel"
+                            </button>
+                        </Show>
                         <button
                             class="btn btn-secondary"
                             on:click=move |_| set_show_history.update(|v| *v = !*v)
@@ -295,6 +396,22 @@ pub fn SqlEditorPage() -> impl IntoView {
                             {move || if show_history.get() { "Hide History" } else { "History" }}
                         </button>
                     </div>
+                    {move || progress.get().map(|p| {
+                        let pct = p.progress_pct.clamp(0.0, 100.0);
+                        view! {
+                            <div class="query-progress">
+                                <div class="query-progress-track">
+                                    <div
+                                        class="query-progress-bar"
+                                        style=format!("width: {pct:.1}%")
+                                    ></div>
+                                </div>
+                                <span class="query-progress-label">
+                                    {format!("{} · {:.0}% · {} rows", p.stage, pct, p.rows_processed)}
+                                </span>
+                            </div>
+                        }
+                    })}
                 </div>
 
                 <QueryHistoryPanel
@@ -304,10 +421,14 @@ pub fn SqlEditorPage() -> impl IntoView {
                 />
 
                 <div class="results-pane">
-                    {move || if is_explain.get() && plan_text.get().is_some() {
-                        view! { <QueryPlanViewer plan_text=plan_text /> }.into_any()
-                    } else {
-                        view! { <ResultTable result=result_signal /> }.into_any()
+                    {move || {
+                        // Remount per tab: ResultTable keys rows by position
+                        active_tab_id.track();
+                        if is_explain.get() && plan_text.get().is_some() {
+                            view! { <QueryPlanViewer plan_text=plan_text /> }.into_any()
+                        } else {
+                            view! { <ResultTable result=result_signal /> }.into_any()
+                        }
                     }}
                 </div>
             </div>
@@ -8,6 +8,12 @@ use megafactory_sql_types::toast::ToastLevel;
 use crate::components::codemirror::CodeMirrorEditor;
 use crate::state::toast::{push_toast, use_toast_write};
 
+/// Speedup of an operator on an accelerator, from the shared figures.
+#[cfg(feature = "ssr")]
+fn speedup(op: megafactory_sql_types::advisor::AccelerableOp, backend: AcceleratorBackend) -> f64 {
+    op.speedup(backend).unwrap_or(1.0)
+}
+
 /// Mock workbench analysis for Phase A (heuristic, no live MegaDB connection).
 /// Will be replaced by a server function calling MegaDB's EXPLAIN + profiling APIs.
 #[cfg(feature = "ssr")]
@@ -55,7 +61,7 @@ fn mock_analyze(sql: &str) -> WorkbenchResult {
             },
             BackendOption {
                 backend: AcceleratorBackend::Fpga,
-                estimated_speedup: 5.0,
+                estimated_speedup: speedup(AccelerableOp::Decompression, AcceleratorBackend::Fpga),
                 estimated_time_ms: 1700.0,
                 estimated_cost_usd: 0.012,
                 available: true,
@@ -80,7 +86,10 @@ fn mock_analyze(sql: &str) -> WorkbenchResult {
                 },
                 BackendOption {
                     backend: AcceleratorBackend::Gpu,
-                    estimated_speedup: 9.3,
+                    estimated_speedup: speedup(
+                        AccelerableOp::HashAggregate,
+                        AcceleratorBackend::Gpu,
+                    ),
                     estimated_time_ms: 2516.0,
                     estimated_cost_usd: 0.083,
                     available: true,
@@ -115,7 +124,10 @@ fn mock_analyze(sql: &str) -> WorkbenchResult {
                 },
                 BackendOption {
                     backend: AcceleratorBackend::Gpu,
-                    estimated_speedup: 45.0,
+                    estimated_speedup: speedup(
+                        AccelerableOp::GraphTraversal,
+                        AcceleratorBackend::Gpu,
+                    ),
                     estimated_time_ms: 267.0,
                     estimated_cost_usd: 0.035,
                     available: true,
@@ -141,7 +153,10 @@ fn mock_analyze(sql: &str) -> WorkbenchResult {
                 },
                 BackendOption {
                     backend: AcceleratorBackend::Gpu,
-                    estimated_speedup: 50.0,
+                    estimated_speedup: speedup(
+                        AccelerableOp::VectorDistance,
+                        AcceleratorBackend::Gpu,
+                    ),
                     estimated_time_ms: 9.0,
                     estimated_cost_usd: 0.001,
                     available: true,
@@ -167,7 +182,10 @@ fn mock_analyze(sql: &str) -> WorkbenchResult {
                 },
                 BackendOption {
                     backend: AcceleratorBackend::Npu,
-                    estimated_speedup: 8.0,
+                    estimated_speedup: speedup(
+                        AccelerableOp::CostAnalytics,
+                        AcceleratorBackend::Npu,
+                    ),
                     estimated_time_ms: 150.0,
                     estimated_cost_usd: 0.002,
                     available: true,
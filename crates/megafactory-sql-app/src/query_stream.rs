@@ -0,0 +1,117 @@
+use megafactory_sql_types::query::{QueryResultChunk, StreamQueryRequest};
+
+/// Streaming query endpoint served by megafactory-sql-server.
+pub const STREAM_PATH: &str = "/proxy/megadb/query/stream";
+
+/// POST `body` to the streaming endpoint and call `on_chunk` for every
+/// NDJSON chunk as soon as it arrives.
+/// Always fails on the server (SSR).
+#[cfg(target_arch = "wasm32")]
+pub async fn stream_query(
+    body: &StreamQueryRequest,
+    on_chunk: impl FnMut(QueryResultChunk),
+) -> Result<(), String> {
+    use wasm_bindgen::{JsCast, JsValue};
+    use wasm_bindgen_futures::JsFuture;
+
+    let window = web_sys::window().ok_or("No window")?;
+    let payload = serde_json::to_string(body).map_err(|e| e.to_string())?;
+
+    let headers = web_sys::Headers::new().map_err(js_error)?;
+    headers
+        .set("Content-Type", "application/json")
+        .map_err(js_error)?;
+    let init = web_sys::RequestInit::new();
+    init.set_method("POST");
+    init.set_headers(&headers);
+    init.set_body(&JsValue::from_str(&payload));
+    let request = web_sys::Request::new_with_str_and_init(STREAM_PATH, &init).map_err(js_error)?;
+
+    let response: web_sys::Response = JsFuture::from(window.fetch_with_request(&request))
+        .await
+        .map_err(js_error)?
+        .unchecked_into();
+    read_ndjson(response, on_chunk).await
+}
+
+/// Read an NDJSON response body, calling `on_item` for every line as soon
+/// as it arrives. A non-2xx response fails with the proxy's `error` message.
+#[cfg(target_arch = "wasm32")]
+pub(crate) async fn read_ndjson<T: serde::de::DeserializeOwned>(
+    response: web_sys::Response,
+    mut on_item: impl FnMut(T),
+) -> Result<(), String> {
+    use wasm_bindgen::{JsCast, JsValue};
+    use wasm_bindgen_futures::JsFuture;
+
+    if !response.ok() {
+        let text = match response.text() {
+            Ok(promise) => JsFuture::from(promise)
+                .await
+                .ok()
+                .and_then(|v| v.as_string())
+                .unwrap_or_default(),
+            Err(_) => String::new(),
+        };
+        return Err(error_message(&text).unwrap_or_else(|| format!("HTTP {}", response.status())));
+    }
+
+    let reader: web_sys::ReadableStreamDefaultReader = response
+        .body()
+        .ok_or("Empty response body")?
+        .get_reader()
+        .unchecked_into();
+
+    let mut buf: Vec<u8> = Vec::new();
+    loop {
+        let next = JsFuture::from(reader.read()).await.map_err(js_error)?;
+        let done = js_sys::Reflect::get(&next, &JsValue::from_str("done"))
+            .map(|v| v.is_truthy())
+            .unwrap_or(true);
+        if done {
+            break;
+        }
+        let value = js_sys::Reflect::get(&next, &JsValue::from_str("value")).map_err(js_error)?;
+        buf.extend(js_sys::Uint8Array::new(&value).to_vec());
+
+        while let Some(pos) = buf.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = buf.drain(..=pos).collect();
+            emit_line(&line, &mut on_item)?;
+        }
+    }
+    emit_line(&buf, &mut on_item)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn stream_query(
+    _body: &StreamQueryRequest,
+    _on_chunk: impl FnMut(QueryResultChunk),
+) -> Result<(), String> {
+    Err("Streaming queries are only available in the browser".to_string())
+}
+
+/// Parse one NDJSON line; blank lines are skipped.
+#[cfg(target_arch = "wasm32")]
+fn emit_line<T: serde::de::DeserializeOwned>(
+    line: &[u8],
+    on_item: &mut impl FnMut(T),
+) -> Result<(), String> {
+    if line.iter().all(u8::is_ascii_whitespace) {
+        return Ok(());
+    }
+    let item = serde_json::from_slice(line).map_err(|e| format!("Malformed stream line: {e}"))?;
+    on_item(item);
+    Ok(())
+}
+
+/// Extract `error` from a proxy error body (`{"error": "..."}`).
+#[cfg(target_arch = "wasm32")]
+fn error_message(body: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(body).ok()?;
+    value.get("error")?.as_str().map(str::to_string)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn js_error(e: wasm_bindgen::JsValue) -> String {
+    e.as_string().unwrap_or_else(|| format!("{e:?}"))
+}
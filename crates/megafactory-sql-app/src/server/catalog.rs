@@ -0,0 +1,150 @@
+//! Instance catalogs served to the configurator: the catalogs bundled with
+//! `megafactory-sql-types`, replaced per provider by `<provider>.json` in an
+//! override directory (`MEGAFACTORY_CATALOG_DIR`). Overrides are read on
+//! every request, so price updates and custom on-premises SKUs apply without
+//! a rebuild or restart.
+
+use std::path::{Path, PathBuf};
+
+use megafactory_sql_types::configurator::{CatalogError, CloudProvider, InstanceCatalog};
+
+/// Errors returned by [`CatalogStore::load`].
+#[derive(Debug, thiserror::Error)]
+pub enum CatalogLoadError {
+    #[error("Failed to read {}: {source}", path.display())]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("{}: {source}", path.display())]
+    Invalid { path: PathBuf, source: CatalogError },
+}
+
+/// Where instance catalogs come from.
+#[derive(Debug, Clone, Default)]
+pub struct CatalogStore {
+    dir: Option<PathBuf>,
+}
+
+impl CatalogStore {
+    /// A store overriding the bundled catalogs with the files in `dir`.
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        Self { dir }
+    }
+
+    pub fn dir(&self) -> Option<&Path> {
+        self.dir.as_deref()
+    }
+
+    /// The override file for `provider`, if it exists.
+    pub async fn override_path(&self, provider: CloudProvider) -> Option<PathBuf> {
+        let path = self.dir.as_ref()?.join(format!("{}.json", provider.slug()));
+        let metadata = tokio::fs::metadata(&path).await.ok()?;
+        metadata.is_file().then_some(path)
+    }
+
+    /// The catalog for `provider`: its override file when there is one,
+    /// the bundled catalog otherwise. An invalid override is an error rather
+    /// than silently falling back, so a bad price update gets noticed.
+    pub async fn load(&self, provider: CloudProvider) -> Result<InstanceCatalog, CatalogLoadError> {
+        let Some(path) = self.override_path(provider).await else {
+            return Ok(InstanceCatalog::bundled(provider));
+        };
+        let json = match tokio::fs::read_to_string(&path).await {
+            Ok(json) => json,
+            Err(source) => return Err(CatalogLoadError::Read { path, source }),
+        };
+        let catalog = InstanceCatalog::from_json(&json).and_then(|catalog| {
+            if catalog.provider == provider {
+                Ok(catalog)
+            } else {
+                Err(CatalogError::Invalid(format!(
+                    "catalog is for {}, not {}",
+                    catalog.provider.label(),
+                    provider.label()
+                )))
+            }
+        });
+        catalog.map_err(|source| CatalogLoadError::Invalid { path, source })
+    }
+
+    /// Load every provider's catalog, for reporting bad overrides at startup.
+    pub async fn check_all(&self) -> Vec<CatalogLoadError> {
+        let mut errors = vec![];
+        for &provider in CloudProvider::all() {
+            if let Err(e) = self.load(provider).await {
+                errors.push(e);
+            }
+        }
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh override directory under the system temp dir.
+    fn override_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("megafactory-catalog-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_bundled_without_overrides() {
+        let store = CatalogStore::default();
+        let catalog = store.load(CloudProvider::Gcp).await.unwrap();
+        assert_eq!(catalog.provider, CloudProvider::Gcp);
+        assert!(store.check_all().await.is_empty());
+
+        // A directory without a file for the provider also falls back
+        let dir = override_dir("empty");
+        let store = CatalogStore::new(Some(dir.clone()));
+        assert_eq!(
+            store
+                .load(CloudProvider::Aws)
+                .await
+                .unwrap()
+                .catalog_version,
+            InstanceCatalog::bundled(CloudProvider::Aws).catalog_version
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_override_file() {
+        let dir = override_dir("override");
+        let mut on_prem = InstanceCatalog::bundled(CloudProvider::OnPrem);
+        on_prem.catalog_version = "rack-b".into();
+        on_prem.families[0].instance_types[0].name = "dell-r760".into();
+        on_prem.families[0].instance_types[0].price_per_hour_usd = Some(1.25);
+        std::fs::write(
+            dir.join("on_prem.json"),
+            serde_json::to_string(&on_prem).unwrap(),
+        )
+        .unwrap();
+
+        let store = CatalogStore::new(Some(dir.clone()));
+        let catalog = store.load(CloudProvider::OnPrem).await.unwrap();
+        assert_eq!(catalog.catalog_version, "rack-b");
+        assert_eq!(catalog.families[0].instance_types[0].name, "dell-r760");
+        // Other providers keep the bundled catalog
+        assert!(store.load(CloudProvider::Aws).await.is_ok());
+
+        // A catalog for another provider under this provider's name
+        std::fs::rename(dir.join("on_prem.json"), dir.join("azure.json")).unwrap();
+        assert!(matches!(
+            store.load(CloudProvider::Azure).await,
+            Err(CatalogLoadError::Invalid { .. })
+        ));
+
+        std::fs::write(dir.join("gcp.json"), "{ not json").unwrap();
+        let err = store.load(CloudProvider::Gcp).await.unwrap_err();
+        assert!(err.to_string().contains("gcp.json"));
+        assert_eq!(store.check_all().await.len(), 2);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
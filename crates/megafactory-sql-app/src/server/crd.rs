@@ -0,0 +1,601 @@
+//! Typed `megadb.io/v1alpha1` `MegaDB` custom resource.
+//!
+//! Mirrors `MegaDBSpec` in megadb-k8s/src/crd.rs (snake_case spec fields).
+//! What the spec has no place for, such as instance types, is kept in the
+//! [`CONFIGURATOR_ANNOTATION`] so a resource converts back into the
+//! `DeploymentConfig` it came from.
+
+use std::collections::BTreeMap;
+
+use kube::CustomResource;
+use megafactory_sql_types::configurator::{
+    AcceleratorType, CloudProvider, DeploymentConfig, PoolAccelerator, PreservedFields,
+    StorageBackend, TolerationConfig, WorkerPoolConfig,
+};
+use megafactory_sql_types::k8s::{ClusterCondition, MegaDbSummary};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::keda::ScaledObject;
+use super::manifest::ManifestError;
+
+/// Annotation holding the configurator settings the spec can't express
+/// (JSON): the cloud provider, each pool's instance type and replica range,
+/// and the GPU pool's device plugin resource.
+pub const CONFIGURATOR_ANNOTATION: &str = "megadb.io/configurator";
+
+/// GPU resource assumed for resources without a [`CONFIGURATOR_ANNOTATION`].
+const DEFAULT_GPU_RESOURCE: &str = "nvidia.com/gpu";
+
+#[derive(CustomResource, Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[kube(
+    group = "megadb.io",
+    version = "v1alpha1",
+    kind = "MegaDB",
+    plural = "megadbs",
+    namespaced,
+    status = "MegaDBStatus"
+)]
+pub struct MegaDBSpec {
+    /// CPU worker replicas.
+    pub replicas: i32,
+    #[serde(default = "default_version")]
+    pub version: String,
+    pub storage: StorageSpec,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gpu_workers: Option<WorkerGroupSpec>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fpga_workers: Option<WorkerGroupSpec>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub npu_workers: Option<WorkerGroupSpec>,
+    /// Accelerators attached to the CPU workers. Manifests from before
+    /// `fpga_workers` carry the FPGA pool here.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accelerators: Option<AcceleratorSpec>,
+    /// Fields this dashboard doesn't know, kept as they are.
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_json::Value>,
+}
+
+fn default_version() -> String {
+    "latest".into()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct StorageSpec {
+    /// "s3", "gcs", "azure" or "local"
+    pub storage_type: String,
+}
+
+/// An additional worker group scheduled onto accelerator nodes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct WorkerGroupSpec {
+    pub replicas: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accelerators: Option<AcceleratorSpec>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub node_selector: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tolerations: Vec<TolerationSpec>,
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AcceleratorSpec {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gpu: Option<GpuResourceSpec>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fpga: Option<DeviceResourceSpec>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub npu: Option<DeviceResourceSpec>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct GpuResourceSpec {
+    pub count: u32,
+    pub product: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mig_profile: Option<String>,
+}
+
+/// An FPGA or NPU requested through its device plugin resource.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct DeviceResourceSpec {
+    pub resource_name: String,
+    pub count: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub product: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TolerationSpec {
+    pub key: String,
+    pub operator: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    pub effect: String,
+}
+
+/// Status written by the MegaDB operator.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct MegaDBStatus {
+    #[serde(default)]
+    pub phase: Option<String>,
+    #[serde(default)]
+    pub ready_replicas: Option<i32>,
+    #[serde(default)]
+    pub conditions: Vec<MegaDBCondition>,
+}
+
+/// Standard Kubernetes condition (camelCase, as in `metav1.Condition`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MegaDBCondition {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub status: String,
+    #[serde(default)]
+    pub reason: Option<String>,
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub last_transition_time: Option<String>,
+}
+
+/// Configurator settings kept in the [`CONFIGURATOR_ANNOTATION`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct ConfiguratorAnnotation {
+    provider: CloudProvider,
+    /// Keyed by pool: "cpu", "gpu", "fpga" or "npu".
+    pools: BTreeMap<String, PoolPlacement>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct PoolPlacement {
+    instance_type: String,
+    min_replicas: i32,
+    max_replicas: i32,
+    /// Device plugin resource of a GPU pool, which the GPU spec has no
+    /// field for ("amd.com/gpu", ...).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    gpu_resource_name: Option<String>,
+}
+
+impl From<&WorkerPoolConfig> for PoolPlacement {
+    fn from(pool: &WorkerPoolConfig) -> Self {
+        Self {
+            instance_type: pool.instance_type.clone(),
+            min_replicas: pool.min_replicas,
+            max_replicas: pool.max_replicas,
+            gpu_resource_name: pool
+                .accelerator
+                .as_ref()
+                .filter(|a| a.accelerator_type == AcceleratorType::Gpu)
+                .map(|a| a.k8s_resource_name.clone()),
+        }
+    }
+}
+
+impl From<&TolerationConfig> for TolerationSpec {
+    fn from(t: &TolerationConfig) -> Self {
+        Self {
+            key: t.key.clone(),
+            operator: t.operator.clone(),
+            value: t.value.clone(),
+            effect: t.effect.clone(),
+        }
+    }
+}
+
+impl From<&TolerationSpec> for TolerationConfig {
+    fn from(t: &TolerationSpec) -> Self {
+        Self {
+            key: t.key.clone(),
+            operator: t.operator.clone(),
+            value: t.value.clone(),
+            effect: t.effect.clone(),
+        }
+    }
+}
+
+impl From<&MegaDBCondition> for ClusterCondition {
+    fn from(c: &MegaDBCondition) -> Self {
+        Self {
+            condition_type: c.type_.clone(),
+            status: c.status.clone(),
+            reason: c.reason.clone().unwrap_or_default(),
+            message: c.message.clone().unwrap_or_default(),
+            last_transition_time: c.last_transition_time.clone(),
+        }
+    }
+}
+
+fn storage_type(backend: StorageBackend) -> &'static str {
+    match backend {
+        StorageBackend::S3 => "s3",
+        StorageBackend::Gcs => "gcs",
+        StorageBackend::AzureBlob => "azure",
+        StorageBackend::Local => "local",
+    }
+}
+
+/// Accelerators of a pool that the CRD can express (GPU, FPGA and NPU).
+fn pool_accelerators(pool: &WorkerPoolConfig) -> Option<AcceleratorSpec> {
+    let accel = pool.accelerator.as_ref()?;
+    let device = || DeviceResourceSpec {
+        resource_name: accel.k8s_resource_name.clone(),
+        count: accel.count_per_pod,
+        product: Some(accel.device_name.clone()).filter(|name| !name.is_empty()),
+    };
+    let mut spec = AcceleratorSpec {
+        gpu: None,
+        fpga: None,
+        npu: None,
+    };
+    match accel.accelerator_type {
+        AcceleratorType::Gpu => {
+            spec.gpu = Some(GpuResourceSpec {
+                count: accel.count_per_pod,
+                product: accel.device_name.clone(),
+                mig_profile: accel.mig_profile.clone(),
+            })
+        }
+        AcceleratorType::Fpga => spec.fpga = Some(device()),
+        AcceleratorType::Npu => spec.npu = Some(device()),
+        AcceleratorType::Tpu | AcceleratorType::None => return None,
+    }
+    Some(spec)
+}
+
+/// The worker group `key` ("gpu_workers", ...) of `config`, with the
+/// fields preserved from an imported resource.
+fn worker_group(config: &DeploymentConfig, key: &str) -> Option<WorkerGroupSpec> {
+    let pool = match key {
+        "gpu_workers" => config.gpu_worker_pool.as_ref(),
+        "fpga_workers" => config.fpga_worker_pool.as_ref(),
+        "npu_workers" => config.npu_worker_pool.as_ref(),
+        _ => None,
+    }?;
+    Some(WorkerGroupSpec {
+        replicas: pool.replicas,
+        accelerators: pool_accelerators(pool),
+        node_selector: pool.node_selector.iter().cloned().collect(),
+        tolerations: pool.tolerations.iter().map(Into::into).collect(),
+        extra: config
+            .preserved
+            .worker_groups
+            .get(key)
+            .cloned()
+            .unwrap_or_default(),
+    })
+}
+
+/// The pool accelerator of the `expected` type in `spec`, if any. GPUs are
+/// requested as `gpu_resource_name`, or [`DEFAULT_GPU_RESOURCE`] without one.
+fn pool_accelerator(
+    spec: &AcceleratorSpec,
+    expected: AcceleratorType,
+    gpu_resource_name: Option<&str>,
+) -> Option<PoolAccelerator> {
+    let device = |d: &DeviceResourceSpec| PoolAccelerator {
+        accelerator_type: expected,
+        k8s_resource_name: d.resource_name.clone(),
+        count_per_pod: d.count,
+        device_name: d.product.clone().unwrap_or_default(),
+        mig_profile: None,
+    };
+    match expected {
+        AcceleratorType::Gpu => spec.gpu.as_ref().map(|gpu| PoolAccelerator {
+            accelerator_type: AcceleratorType::Gpu,
+            k8s_resource_name: gpu_resource_name.unwrap_or(DEFAULT_GPU_RESOURCE).into(),
+            count_per_pod: gpu.count,
+            device_name: gpu.product.clone(),
+            mig_profile: gpu.mig_profile.clone(),
+        }),
+        AcceleratorType::Fpga => spec.fpga.as_ref().map(device),
+        AcceleratorType::Npu => spec.npu.as_ref().map(device),
+        AcceleratorType::Tpu | AcceleratorType::None => None,
+    }
+}
+
+/// A worker pool from its group in the spec; without a placement the
+/// instance type is unknown and the replica range is fixed.
+fn worker_pool(
+    group: &WorkerGroupSpec,
+    expected: AcceleratorType,
+    placement: Option<&PoolPlacement>,
+) -> WorkerPoolConfig {
+    WorkerPoolConfig {
+        instance_type: placement
+            .map(|p| p.instance_type.clone())
+            .unwrap_or_default(),
+        replicas: group.replicas,
+        min_replicas: placement.map_or(group.replicas, |p| p.min_replicas),
+        max_replicas: placement.map_or(group.replicas, |p| p.max_replicas),
+        accelerator: group.accelerators.as_ref().and_then(|a| {
+            let gpu_resource_name = placement.and_then(|p| p.gpu_resource_name.as_deref());
+            pool_accelerator(a, expected, gpu_resource_name)
+        }),
+        node_selector: group.node_selector.clone().into_iter().collect(),
+        tolerations: group.tolerations.iter().map(Into::into).collect(),
+    }
+}
+
+impl From<&DeploymentConfig> for MegaDBSpec {
+    fn from(config: &DeploymentConfig) -> Self {
+        Self {
+            replicas: config.cpu_worker_pool.replicas,
+            version: config
+                .preserved
+                .version
+                .clone()
+                .unwrap_or_else(default_version),
+            storage: StorageSpec {
+                storage_type: storage_type(config.storage_backend).into(),
+            },
+            gpu_workers: worker_group(config, "gpu_workers"),
+            fpga_workers: worker_group(config, "fpga_workers"),
+            npu_workers: worker_group(config, "npu_workers"),
+            accelerators: None,
+            extra: config.preserved.spec.clone(),
+        }
+    }
+}
+
+impl MegaDB {
+    /// The resource the configurator deploys for `config`, carrying the
+    /// labels, annotations and fields preserved from an imported resource.
+    pub fn from_config(config: &DeploymentConfig) -> Self {
+        let mut pools = BTreeMap::from([("cpu".to_string(), (&config.cpu_worker_pool).into())]);
+        let optional = [
+            ("gpu", &config.gpu_worker_pool),
+            ("fpga", &config.fpga_worker_pool),
+            ("npu", &config.npu_worker_pool),
+        ];
+        for (key, pool) in optional {
+            if let Some(pool) = pool {
+                pools.insert(key.to_string(), pool.into());
+            }
+        }
+        let annotation = ConfiguratorAnnotation {
+            provider: config.provider,
+            pools,
+        };
+
+        let mut annotations = config.preserved.annotations.clone();
+        annotations.insert(
+            CONFIGURATOR_ANNOTATION.to_string(),
+            serde_json::to_string(&annotation).unwrap_or_default(),
+        );
+
+        let mut megadb = Self::new(&config.cluster_name, config.into());
+        megadb.metadata.annotations = Some(annotations);
+        megadb.metadata.labels =
+            Some(config.preserved.labels.clone()).filter(|labels| !labels.is_empty());
+        megadb
+    }
+
+    /// The deployment config this resource describes, with KEDA settings
+    /// from its `scaled_object`. Without the [`CONFIGURATOR_ANNOTATION`] the
+    /// provider is inferred from the storage type and instance types are
+    /// left empty. Labels, other annotations and unknown spec fields end up
+    /// in [`DeploymentConfig::preserved`].
+    pub fn to_config(
+        &self,
+        scaled_object: Option<&ScaledObject>,
+    ) -> Result<DeploymentConfig, ManifestError> {
+        let name = self
+            .metadata
+            .name
+            .clone()
+            .ok_or_else(|| ManifestError::Invalid("MegaDB has no metadata.name".into()))?;
+        let storage_backend = match self.spec.storage.storage_type.as_str() {
+            "s3" => StorageBackend::S3,
+            "gcs" => StorageBackend::Gcs,
+            "azure" => StorageBackend::AzureBlob,
+            "local" => StorageBackend::Local,
+            other => {
+                return Err(ManifestError::Invalid(format!(
+                    "Unknown storage type \"{other}\""
+                )))
+            }
+        };
+        let annotation: Option<ConfiguratorAnnotation> = self
+            .metadata
+            .annotations
+            .as_ref()
+            .and_then(|a| a.get(CONFIGURATOR_ANNOTATION))
+            .map(|json| serde_json::from_str(json))
+            .transpose()
+            .map_err(|e| {
+                ManifestError::Invalid(format!("Invalid {CONFIGURATOR_ANNOTATION} annotation: {e}"))
+            })?;
+        let provider = annotation.as_ref().map_or(
+            match storage_backend {
+                StorageBackend::S3 => CloudProvider::Aws,
+                StorageBackend::Gcs => CloudProvider::Gcp,
+                StorageBackend::AzureBlob => CloudProvider::Azure,
+                StorageBackend::Local => CloudProvider::OnPrem,
+            },
+            |a| a.provider,
+        );
+        let placement = |key: &str| annotation.as_ref().and_then(|a| a.pools.get(key));
+
+        let cpu_group = WorkerGroupSpec {
+            replicas: self.spec.replicas,
+            accelerators: None,
+            node_selector: BTreeMap::new(),
+            tolerations: vec![],
+            extra: BTreeMap::new(),
+        };
+        let mut cpu_worker_pool = worker_pool(&cpu_group, AcceleratorType::None, placement("cpu"));
+        // KEDA owns the CPU workers' range when it scales them
+        let scaled_object =
+            scaled_object.filter(|so| so.spec.scale_target_ref.name == format!("{name}-worker"));
+        if let Some(so) = scaled_object {
+            if let Some(min) = so.spec.min_replica_count {
+                cpu_worker_pool.min_replicas = min;
+            }
+            if let Some(max) = so.spec.max_replica_count {
+                cpu_worker_pool.max_replicas = max;
+            }
+        }
+
+        let pool = |group: Option<&WorkerGroupSpec>, expected, key| {
+            group.map(|g| worker_pool(g, expected, placement(key)))
+        };
+        // Older manifests attach the FPGAs to the CPU workers
+        let legacy_fpga = self.spec.accelerators.as_ref().map(|a| WorkerGroupSpec {
+            accelerators: Some(a.clone()),
+            ..cpu_group.clone()
+        });
+        let fpga_group = self.spec.fpga_workers.as_ref().or(legacy_fpga.as_ref());
+
+        let mut annotations = self.metadata.annotations.clone().unwrap_or_default();
+        annotations.remove(CONFIGURATOR_ANNOTATION);
+        let groups = [
+            ("gpu_workers", &self.spec.gpu_workers),
+            ("fpga_workers", &self.spec.fpga_workers),
+            ("npu_workers", &self.spec.npu_workers),
+        ];
+        let preserved = PreservedFields {
+            version: Some(self.spec.version.clone()).filter(|v| *v != default_version()),
+            labels: self.metadata.labels.clone().unwrap_or_default(),
+            annotations,
+            spec: self.spec.extra.clone(),
+            worker_groups: groups
+                .into_iter()
+                .filter_map(|(key, group)| {
+                    let extra = &group.as_ref()?.extra;
+                    (!extra.is_empty()).then(|| (key.to_string(), extra.clone()))
+                })
+                .collect(),
+        };
+
+        Ok(DeploymentConfig {
+            cluster_name: name,
+            provider,
+            cpu_worker_pool,
+            gpu_worker_pool: pool(self.spec.gpu_workers.as_ref(), AcceleratorType::Gpu, "gpu"),
+            fpga_worker_pool: pool(fpga_group, AcceleratorType::Fpga, "fpga"),
+            npu_worker_pool: pool(self.spec.npu_workers.as_ref(), AcceleratorType::Npu, "npu"),
+            storage_backend,
+            keda_enabled: scaled_object.is_some(),
+            estimated_monthly_cost_usd: 0.0,
+            preserved,
+        })
+    }
+
+    pub fn summary(&self) -> MegaDbSummary {
+        let status = self.status.clone().unwrap_or_default();
+        MegaDbSummary {
+            name: self.metadata.name.clone().unwrap_or_default(),
+            namespace: self.metadata.namespace.clone().unwrap_or_default(),
+            version: self.spec.version.clone(),
+            replicas: self.spec.replicas,
+            ready_replicas: status.ready_replicas.unwrap_or(0),
+            phase: status.phase.unwrap_or_else(|| "Pending".into()),
+            conditions: status.conditions.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use megafactory_sql_types::configurator::{CloudProvider, PoolAccelerator};
+
+    fn pool(replicas: i32, accelerator: Option<PoolAccelerator>) -> WorkerPoolConfig {
+        WorkerPoolConfig {
+            instance_type: "g6.12xlarge".into(),
+            replicas,
+            min_replicas: 1,
+            max_replicas: 8,
+            accelerator,
+            node_selector: vec![("megadb.io/node-pool".into(), "gpu-pool".into())],
+            tolerations: vec![TolerationConfig {
+                key: "nvidia.com/gpu".into(),
+                operator: "Exists".into(),
+                value: None,
+                effect: "NoSchedule".into(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_spec_from_deployment_config() {
+        let config = DeploymentConfig {
+            cluster_name: "analytics".into(),
+            provider: CloudProvider::Aws,
+            cpu_worker_pool: pool(3, None),
+            gpu_worker_pool: Some(pool(
+                2,
+                Some(PoolAccelerator {
+                    accelerator_type: AcceleratorType::Gpu,
+                    k8s_resource_name: "nvidia.com/gpu".into(),
+                    count_per_pod: 4,
+                    device_name: "NVIDIA L4 24GB".into(),
+                    mig_profile: None,
+                }),
+            )),
+            fpga_worker_pool: None,
+            npu_worker_pool: None,
+            storage_backend: StorageBackend::S3,
+            keda_enabled: true,
+            estimated_monthly_cost_usd: 0.0,
+            preserved: PreservedFields::default(),
+        };
+        let megadb = MegaDB::from_config(&config);
+        assert_eq!(megadb.metadata.name.as_deref(), Some("analytics"));
+        assert_eq!(megadb.spec.replicas, 3);
+        assert_eq!(megadb.spec.storage.storage_type, "s3");
+
+        let gpu = megadb.spec.gpu_workers.as_ref().unwrap();
+        assert_eq!(gpu.replicas, 2);
+        assert_eq!(gpu.node_selector["megadb.io/node-pool"], "gpu-pool");
+        assert_eq!(gpu.tolerations[0].effect, "NoSchedule");
+        let accel = gpu.accelerators.as_ref().unwrap().gpu.as_ref().unwrap();
+        assert_eq!((accel.count, accel.product.as_str()), (4, "NVIDIA L4 24GB"));
+
+        // Same field names as the YAML the configurator shows
+        let json = serde_json::to_value(&megadb).unwrap();
+        assert_eq!(json["apiVersion"], "megadb.io/v1alpha1");
+        assert_eq!(json["spec"]["gpu_workers"]["replicas"], 2);
+    }
+
+    #[test]
+    fn test_gpu_resource_name_roundtrip() {
+        let amd = PoolAccelerator {
+            accelerator_type: AcceleratorType::Gpu,
+            k8s_resource_name: "amd.com/gpu".into(),
+            count_per_pod: 8,
+            device_name: "AMD Instinct MI300X".into(),
+            mig_profile: None,
+        };
+        let config = DeploymentConfig {
+            cluster_name: "analytics".into(),
+            provider: CloudProvider::Azure,
+            cpu_worker_pool: pool(3, None),
+            gpu_worker_pool: Some(pool(2, Some(amd.clone()))),
+            fpga_worker_pool: None,
+            npu_worker_pool: None,
+            storage_backend: StorageBackend::AzureBlob,
+            keda_enabled: false,
+            estimated_monthly_cost_usd: 0.0,
+            preserved: PreservedFields::default(),
+        };
+        let mut megadb = MegaDB::from_config(&config);
+        let parsed = megadb.to_config(None).unwrap();
+        assert_eq!(
+            serde_json::to_value(parsed.gpu_worker_pool.unwrap().accelerator).unwrap(),
+            serde_json::to_value(Some(amd)).unwrap()
+        );
+
+        // Without the annotation the GPU is assumed to be NVIDIA's
+        megadb.metadata.annotations = None;
+        let parsed = megadb.to_config(None).unwrap();
+        let accel = parsed.gpu_worker_pool.unwrap().accelerator.unwrap();
+        assert_eq!(accel.k8s_resource_name, DEFAULT_GPU_RESOURCE);
+    }
+}
@@ -0,0 +1,114 @@
+//! In-memory log of recent cluster events, so dashboards can catch up on
+//! what they missed while disconnected (`/proxy/k8s/events?since=`).
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use megafactory_sql_types::ws::K8sEvent;
+
+/// Events kept by [`EventLog::default`].
+pub const DEFAULT_EVENT_LOG_CAPACITY: usize = 1000;
+
+/// Ring buffer of the most recent events, each given an increasing id.
+#[derive(Debug)]
+pub struct EventLog {
+    capacity: usize,
+    inner: Mutex<LogInner>,
+}
+
+#[derive(Debug, Default)]
+struct LogInner {
+    /// Oldest first.
+    events: VecDeque<K8sEvent>,
+    last_id: u64,
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        Self::new(DEFAULT_EVENT_LOG_CAPACITY)
+    }
+}
+
+impl EventLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Mutex::default(),
+        }
+    }
+
+    /// Append `event` with the next id, dropping the oldest event when full.
+    /// An event with the UID of one already logged replaces it.
+    pub fn record(&self, mut event: K8sEvent) -> K8sEvent {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.last_id += 1;
+        event.id = inner.last_id;
+        if let Some(uid) = &event.uid {
+            inner.events.retain(|e| e.uid.as_ref() != Some(uid));
+        }
+        inner.events.push_back(event.clone());
+        while inner.events.len() > self.capacity {
+            inner.events.pop_front();
+        }
+        event
+    }
+
+    /// Events recorded after id `since`, oldest first.
+    pub fn since(&self, since: u64) -> Vec<K8sEvent> {
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner
+            .events
+            .iter()
+            .filter(|e| e.id > since)
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_log_since_and_capacity() {
+        let log = EventLog::new(3);
+        for i in 0..5 {
+            log.record(K8sEvent::new(
+                "MODIFIED",
+                "Pod",
+                format!("pod-{i}"),
+                "changed",
+            ));
+        }
+        let ids: Vec<u64> = log.since(0).iter().map(|e| e.id).collect();
+        assert_eq!(ids, [3, 4, 5]);
+        let names: Vec<String> = log.since(4).into_iter().map(|e| e.pod_name).collect();
+        assert_eq!(names, ["pod-4"]);
+        assert!(log.since(5).is_empty());
+    }
+
+    #[test]
+    fn test_event_log_replaces_updates() {
+        let log = EventLog::default();
+        let backoff = K8sEvent {
+            uid: Some("uid-1".into()),
+            ..K8sEvent::new("Warning", "Pod", "megadb-worker-0", "Back-off")
+        };
+        log.record(backoff.clone());
+        log.record(K8sEvent::new(
+            "SCALED",
+            "StatefulSet",
+            "megadb-worker",
+            "2 -> 3",
+        ));
+        let updated = log.record(K8sEvent {
+            count: 2,
+            ..backoff
+        });
+
+        let events = log.since(0);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1], updated);
+        assert_eq!((updated.id, updated.count), (3, 2));
+    }
+}
@@ -0,0 +1,357 @@
+//! Renderings of a deployment config for GitOps repositories: the raw
+//! MegaDB manifest, a `values.yaml` for the MegaDB Helm chart, or a
+//! kustomization whose worker pool scheduling lives in per-pool patches.
+
+use std::collections::BTreeMap;
+
+use kube::Resource;
+use megafactory_sql_types::configurator::{DeploymentConfig, WorkerPoolConfig};
+use megafactory_sql_types::export::{ExportFile, ExportFormat};
+use serde::Serialize;
+
+use super::crd::{MegaDB, TolerationSpec};
+use super::manifest::{self, ManifestError};
+
+const KUSTOMIZE_API_VERSION: &str = "kustomize.config.k8s.io/v1beta1";
+
+/// The files of `config` in `format`.
+pub fn render(
+    config: &DeploymentConfig,
+    format: ExportFormat,
+) -> Result<Vec<ExportFile>, ManifestError> {
+    match format {
+        ExportFormat::Crd => Ok(vec![file("megadb.yaml", manifest::to_yaml(config)?)]),
+        ExportFormat::Helm => Ok(vec![file(
+            "values.yaml",
+            serde_yaml::to_string(&HelmValues::from(config))?,
+        )]),
+        ExportFormat::Kustomize => kustomize(config),
+    }
+}
+
+fn file(path: &str, content: String) -> ExportFile {
+    ExportFile {
+        path: path.to_string(),
+        content,
+    }
+}
+
+/// Accelerator pools with their CRD worker group names.
+fn accelerator_pools(config: &DeploymentConfig) -> [(&'static str, Option<&WorkerPoolConfig>); 3] {
+    [
+        ("gpu_workers", config.gpu_worker_pool.as_ref()),
+        ("fpga_workers", config.fpga_worker_pool.as_ref()),
+        ("npu_workers", config.npu_worker_pool.as_ref()),
+    ]
+}
+
+// ─── Helm ───────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HelmValues {
+    fullname_override: String,
+    image: ImageValues,
+    storage: StorageValues,
+    workers: PoolValues,
+    gpu_workers: PoolValues,
+    fpga_workers: PoolValues,
+    npu_workers: PoolValues,
+}
+
+#[derive(Debug, Serialize)]
+struct ImageValues {
+    tag: String,
+}
+
+#[derive(Debug, Serialize)]
+struct StorageValues {
+    #[serde(rename = "type")]
+    type_: String,
+}
+
+/// A worker pool; disabled pools carry only `enabled: false`.
+#[derive(Debug, Serialize)]
+struct PoolValues {
+    enabled: bool,
+    #[serde(flatten)]
+    settings: Option<WorkerValues>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WorkerValues {
+    replicas: i32,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    instance_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    autoscaling: Option<AutoscalingValues>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    accelerator: Option<AcceleratorValues>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    node_selector: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tolerations: Vec<TolerationSpec>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AutoscalingValues {
+    enabled: bool,
+    min_replicas: i32,
+    max_replicas: i32,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AcceleratorValues {
+    resource_name: String,
+    count: u32,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    product: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mig_profile: Option<String>,
+}
+
+impl From<&WorkerPoolConfig> for WorkerValues {
+    fn from(pool: &WorkerPoolConfig) -> Self {
+        Self {
+            replicas: pool.replicas,
+            instance_type: pool.instance_type.clone(),
+            autoscaling: None,
+            accelerator: pool.accelerator.as_ref().map(|a| AcceleratorValues {
+                resource_name: a.k8s_resource_name.clone(),
+                count: a.count_per_pod,
+                product: a.device_name.clone(),
+                mig_profile: a.mig_profile.clone(),
+            }),
+            node_selector: pool.node_selector.iter().cloned().collect(),
+            tolerations: pool.tolerations.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<&DeploymentConfig> for HelmValues {
+    fn from(config: &DeploymentConfig) -> Self {
+        // Same spec as the CRD, so version and storage type agree with it
+        let spec = MegaDB::from_config(config).spec;
+        let pool = |pool: Option<&WorkerPoolConfig>| PoolValues {
+            enabled: pool.is_some(),
+            settings: pool.map(Into::into),
+        };
+        let mut workers = WorkerValues::from(&config.cpu_worker_pool);
+        workers.autoscaling = config.keda_enabled.then_some(AutoscalingValues {
+            enabled: true,
+            min_replicas: config.cpu_worker_pool.min_replicas,
+            max_replicas: config.cpu_worker_pool.max_replicas,
+        });
+        Self {
+            fullname_override: config.cluster_name.clone(),
+            image: ImageValues { tag: spec.version },
+            storage: StorageValues {
+                type_: spec.storage.storage_type,
+            },
+            workers: PoolValues {
+                enabled: true,
+                settings: Some(workers),
+            },
+            gpu_workers: pool(config.gpu_worker_pool.as_ref()),
+            fpga_workers: pool(config.fpga_worker_pool.as_ref()),
+            npu_workers: pool(config.npu_worker_pool.as_ref()),
+        }
+    }
+}
+
+// ─── Kustomize ──────────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Kustomization {
+    api_version: &'static str,
+    kind: &'static str,
+    resources: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    patches: Vec<KustomizePatch>,
+}
+
+#[derive(Debug, Serialize)]
+struct KustomizePatch {
+    path: String,
+    target: PatchTarget,
+}
+
+#[derive(Debug, Serialize)]
+struct PatchTarget {
+    group: String,
+    version: String,
+    kind: String,
+    name: String,
+}
+
+/// A MegaDB patch setting the scheduling of one worker group.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SchedulingPatch {
+    api_version: String,
+    kind: String,
+    metadata: PatchMetadata,
+    spec: BTreeMap<&'static str, GroupScheduling>,
+}
+
+#[derive(Debug, Serialize)]
+struct PatchMetadata {
+    name: String,
+}
+
+#[derive(Debug, Serialize)]
+struct GroupScheduling {
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    node_selector: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tolerations: Vec<TolerationSpec>,
+}
+
+/// `megadb.yaml` without any pool scheduling, plus one patch per
+/// accelerator pool that has a node selector or tolerations.
+fn kustomize(config: &DeploymentConfig) -> Result<Vec<ExportFile>, ManifestError> {
+    let mut base = config.clone();
+    for pool in [
+        &mut base.gpu_worker_pool,
+        &mut base.fpga_worker_pool,
+        &mut base.npu_worker_pool,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        pool.node_selector.clear();
+        pool.tolerations.clear();
+    }
+
+    let mut files = vec![file("megadb.yaml", manifest::to_yaml(&base)?)];
+    let mut patches = Vec::new();
+    for (group, pool) in accelerator_pools(config) {
+        let Some(pool) = pool else { continue };
+        if pool.node_selector.is_empty() && pool.tolerations.is_empty() {
+            continue;
+        }
+        let patch = SchedulingPatch {
+            api_version: MegaDB::api_version(&()).into_owned(),
+            kind: MegaDB::kind(&()).into_owned(),
+            metadata: PatchMetadata {
+                name: config.cluster_name.clone(),
+            },
+            spec: BTreeMap::from([(
+                group,
+                GroupScheduling {
+                    node_selector: pool.node_selector.iter().cloned().collect(),
+                    tolerations: pool.tolerations.iter().map(Into::into).collect(),
+                },
+            )]),
+        };
+        let path = format!("patches/{}.yaml", group.replace('_', "-"));
+        files.push(file(&path, serde_yaml::to_string(&patch)?));
+        patches.push(KustomizePatch {
+            path,
+            target: PatchTarget {
+                group: MegaDB::group(&()).into_owned(),
+                version: MegaDB::version(&()).into_owned(),
+                kind: MegaDB::kind(&()).into_owned(),
+                name: config.cluster_name.clone(),
+            },
+        });
+    }
+
+    let kustomization = Kustomization {
+        api_version: KUSTOMIZE_API_VERSION,
+        kind: "Kustomization",
+        resources: vec!["megadb.yaml".to_string()],
+        patches,
+    };
+    files.insert(
+        0,
+        file("kustomization.yaml", serde_yaml::to_string(&kustomization)?),
+    );
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use megafactory_sql_types::configurator::{find_instance, instance_catalog, CloudProvider};
+
+    fn config() -> DeploymentConfig {
+        let catalog = instance_catalog(CloudProvider::Aws);
+        let mut config = DeploymentConfig::default_for(CloudProvider::Aws, &catalog);
+        config.cluster_name = "analytics".to_string();
+        let g6 = find_instance(&catalog, "g6.12xlarge").unwrap().1;
+        config.gpu_worker_pool = Some(WorkerPoolConfig::for_instance(g6, 2));
+        config
+    }
+
+    #[test]
+    fn test_helm_values() {
+        let files = render(&config(), ExportFormat::Helm).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "values.yaml");
+        let values: serde_yaml::Value = serde_yaml::from_str(&files[0].content).unwrap();
+
+        assert_eq!(values["fullnameOverride"], "analytics");
+        assert_eq!(values["image"]["tag"], "latest");
+        assert_eq!(values["storage"]["type"], "s3");
+        assert_eq!(values["workers"]["replicas"], 3);
+        assert_eq!(values["workers"]["autoscaling"]["maxReplicas"], 10);
+        let gpu = &values["gpuWorkers"];
+        assert_eq!(gpu["enabled"], true);
+        assert_eq!(gpu["instanceType"], "g6.12xlarge");
+        assert_eq!(gpu["accelerator"]["resourceName"], "nvidia.com/gpu");
+        assert_eq!(gpu["nodeSelector"]["megadb.io/node-pool"], "gpu-pool");
+        assert_eq!(gpu["tolerations"][0]["effect"], "NoSchedule");
+        // Disabled pools are just switched off
+        assert_eq!(
+            values["npuWorkers"],
+            serde_yaml::from_str::<serde_yaml::Value>("enabled: false").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_kustomize_overlay() {
+        let files = render(&config(), ExportFormat::Kustomize).unwrap();
+        let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "kustomization.yaml",
+                "megadb.yaml",
+                "patches/gpu-workers.yaml"
+            ]
+        );
+
+        let kustomization: serde_yaml::Value = serde_yaml::from_str(&files[0].content).unwrap();
+        assert_eq!(kustomization["kind"], "Kustomization");
+        assert_eq!(kustomization["resources"][0], "megadb.yaml");
+        let patch = &kustomization["patches"][0];
+        assert_eq!(patch["path"], "patches/gpu-workers.yaml");
+        assert_eq!(patch["target"]["kind"], "MegaDB");
+        assert_eq!(patch["target"]["group"], "megadb.io");
+        assert_eq!(patch["target"]["name"], "analytics");
+
+        // Scheduling moves out of the resource into the patch
+        assert!(!files[1].content.contains("node_selector"));
+        assert!(files[1].content.contains("kind: ScaledObject"));
+        let patch: serde_yaml::Value = serde_yaml::from_str(&files[2].content).unwrap();
+        assert_eq!(patch["apiVersion"], "megadb.io/v1alpha1");
+        assert_eq!(patch["metadata"]["name"], "analytics");
+        let gpu = &patch["spec"]["gpu_workers"];
+        assert_eq!(gpu["node_selector"]["megadb.io/node-pool"], "gpu-pool");
+        assert_eq!(gpu["tolerations"][0]["key"], "nvidia.com/gpu");
+    }
+
+    #[test]
+    fn test_crd_export() {
+        let config = config();
+        let files = render(&config, ExportFormat::Crd).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "megadb.yaml");
+        assert_eq!(files[0].content, manifest::to_yaml(&config).unwrap());
+    }
+}
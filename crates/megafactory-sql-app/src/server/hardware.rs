@@ -0,0 +1,330 @@
+//! Accelerator and instance detection from `Node` objects, for the
+//! configurator's cluster hardware panel.
+//!
+//! Accelerators are the extended resources their device plugins advertise;
+//! device names come from the labels NVIDIA GPU Feature Discovery, Node
+//! Feature Discovery and the cloud providers put on nodes.
+
+use std::collections::BTreeMap;
+
+use k8s_openapi::api::core::v1::{Node, Pod};
+use megafactory_sql_types::configurator::{
+    AcceleratorType, CloudProvider, ClusterHardwareStatus, DetectedAccelerator, NodeHardware,
+};
+
+use super::k8s::parse_quantity;
+
+/// Instance type label set by the cloud controller, then its deprecated form.
+const INSTANCE_TYPE_LABELS: [&str; 2] = [
+    "node.kubernetes.io/instance-type",
+    "beta.kubernetes.io/instance-type",
+];
+
+/// GPU model from GPU Feature Discovery ("NVIDIA-A100-SXM4-80GB").
+const GPU_PRODUCT_LABEL: &str = "nvidia.com/gpu.product";
+/// GPU memory in MiB from GPU Feature Discovery.
+const GPU_MEMORY_LABEL: &str = "nvidia.com/gpu.memory";
+/// GPU model on GKE ("nvidia-tesla-a100").
+const GKE_ACCELERATOR_LABEL: &str = "cloud.google.com/gke-accelerator";
+/// TPU model on GKE ("tpu-v5-lite-podslice").
+const GKE_TPU_LABEL: &str = "cloud.google.com/gke-tpu-accelerator";
+
+/// The accelerator an extended resource counts, if it is one.
+fn accelerator_type(resource: &str) -> Option<AcceleratorType> {
+    match resource {
+        "nvidia.com/gpu" => Some(AcceleratorType::Gpu),
+        "aws.amazon.com/neuron" => Some(AcceleratorType::Npu),
+        "google.com/tpu" => Some(AcceleratorType::Tpu),
+        r if r.starts_with("amd.com/xilinx_") => Some(AcceleratorType::Fpga),
+        _ => None,
+    }
+}
+
+/// Cloud provider from a node's `spec.providerID` ("aws:///us-east-1a/i-0abc").
+/// Nodes without a known cloud scheme are treated as on-premises.
+pub fn provider_from_id(provider_id: Option<&str>) -> CloudProvider {
+    match provider_id.and_then(|id| id.split_once("://")) {
+        Some(("aws", _)) => CloudProvider::Aws,
+        Some(("gce", _)) => CloudProvider::Gcp,
+        Some(("azure", _)) => CloudProvider::Azure,
+        _ => CloudProvider::OnPrem,
+    }
+}
+
+/// Human-readable device name for an accelerator resource on a node.
+fn device_name(resource: &str, labels: &BTreeMap<String, String>) -> Option<String> {
+    match accelerator_type(resource)? {
+        AcceleratorType::Gpu => {
+            if let Some(product) = labels.get(GPU_PRODUCT_LABEL) {
+                let name = product.replace('-', " ");
+                let memory_gb = labels
+                    .get(GPU_MEMORY_LABEL)
+                    .and_then(|mib| mib.parse::<u64>().ok())
+                    .map(|mib| mib.div_ceil(1024));
+                return Some(match memory_gb {
+                    // The memory is usually part of the product name already
+                    Some(gb) if !name.contains("GB") => format!("{name} {gb}GB"),
+                    _ => name,
+                });
+            }
+            labels.get(GKE_ACCELERATOR_LABEL).cloned()
+        }
+        AcceleratorType::Tpu => labels.get(GKE_TPU_LABEL).cloned(),
+        // "amd.com/xilinx_u250_gen3x16_xdma_shell_4_1-0" -> "Xilinx U250"
+        AcceleratorType::Fpga => resource
+            .strip_prefix("amd.com/xilinx_")
+            .and_then(|shell| shell.split('_').next())
+            .map(|card| format!("Xilinx {}", card.to_uppercase())),
+        AcceleratorType::Npu => Some("AWS Neuron".to_string()),
+        AcceleratorType::None => None,
+    }
+}
+
+/// Amount of `resource` a pod requests: its containers together, or its
+/// largest init container if that is more, as the scheduler counts it.
+fn pod_request(pod: &Pod, resource: &str) -> f64 {
+    let Some(spec) = pod.spec.as_ref() else {
+        return 0.0;
+    };
+    let request = |c: &k8s_openapi::api::core::v1::Container| {
+        c.resources
+            .as_ref()
+            .and_then(|r| r.requests.as_ref().or(r.limits.as_ref()))
+            .and_then(|r| r.get(resource))
+            .and_then(|q| parse_quantity(&q.0))
+            .unwrap_or(0.0)
+    };
+    let containers: f64 = spec.containers.iter().map(request).sum();
+    let init = spec
+        .init_containers
+        .iter()
+        .flatten()
+        .map(request)
+        .fold(0.0, f64::max);
+    containers.max(init)
+}
+
+/// Whether a pod still holds its node's resources.
+fn is_active(pod: &Pod) -> bool {
+    let phase = pod.status.as_ref().and_then(|s| s.phase.as_deref());
+    !matches!(phase, Some("Succeeded" | "Failed"))
+}
+
+/// Hardware of one node, with accelerators allocated to the active pods
+/// scheduled on it.
+pub fn node_hardware(node: &Node, pods: &[Pod]) -> NodeHardware {
+    let name = node.metadata.name.clone().unwrap_or_default();
+    let labels = node.metadata.labels.clone().unwrap_or_default();
+    let allocatable = node
+        .status
+        .as_ref()
+        .and_then(|s| s.allocatable.clone())
+        .unwrap_or_default();
+    let node_pods: Vec<&Pod> = pods
+        .iter()
+        .filter(|p| is_active(p))
+        .filter(|p| p.spec.as_ref().and_then(|s| s.node_name.as_deref()) == Some(&name))
+        .collect();
+
+    let accelerators = allocatable
+        .iter()
+        .filter_map(|(resource, quantity)| {
+            let accelerator_type = accelerator_type(resource)?;
+            let allocatable = parse_quantity(&quantity.0).unwrap_or(0.0) as u32;
+            if allocatable == 0 {
+                return None;
+            }
+            let allocated: f64 = node_pods.iter().map(|p| pod_request(p, resource)).sum();
+            Some(DetectedAccelerator {
+                resource_name: resource.clone(),
+                accelerator_type,
+                allocatable,
+                allocated: (allocated as u32).min(allocatable),
+                device_name: device_name(resource, &labels),
+            })
+        })
+        .collect();
+
+    NodeHardware {
+        provider_instance_type: INSTANCE_TYPE_LABELS
+            .iter()
+            .find_map(|l| labels.get(*l).cloned()),
+        cpu_allocatable: allocatable
+            .get("cpu")
+            .map(|q| q.0.clone())
+            .unwrap_or_default(),
+        memory_allocatable_bytes: allocatable
+            .get("memory")
+            .and_then(|q| parse_quantity(&q.0))
+            .unwrap_or(0.0) as u64,
+        accelerators,
+        labels: labels.into_iter().collect(),
+        node_name: name,
+    }
+}
+
+/// Cluster hardware from all nodes and the pods scheduled on them. The
+/// provider is the one most nodes report.
+pub fn build_cluster_hardware(nodes: &[Node], pods: &[Pod]) -> ClusterHardwareStatus {
+    let mut status = ClusterHardwareStatus {
+        k8s_connected: true,
+        ..Default::default()
+    };
+
+    let mut providers: Vec<(CloudProvider, usize)> = Vec::new();
+    for node in nodes {
+        let provider = provider_from_id(node.spec.as_ref().and_then(|s| s.provider_id.as_deref()));
+        match providers.iter_mut().find(|(p, _)| *p == provider) {
+            Some((_, count)) => *count += 1,
+            None => providers.push((provider, 1)),
+        }
+
+        let hardware = node_hardware(node, pods);
+        for acc in &hardware.accelerators {
+            let (total, available) = match acc.accelerator_type {
+                AcceleratorType::Gpu => (&mut status.total_gpus, &mut status.gpu_available),
+                AcceleratorType::Fpga => (&mut status.total_fpgas, &mut status.fpga_available),
+                AcceleratorType::Npu => (&mut status.total_npus, &mut status.npu_available),
+                AcceleratorType::Tpu => (&mut status.total_tpus, &mut status.tpu_available),
+                AcceleratorType::None => continue,
+            };
+            *total += acc.allocatable;
+            *available += acc.allocatable - acc.allocated;
+        }
+        status.nodes.push(hardware);
+    }
+    // max_by_key keeps the last maximum; iterate in reverse so ties go to
+    // the provider seen first
+    status.detected_provider = providers
+        .into_iter()
+        .rev()
+        .max_by_key(|(_, count)| *count)
+        .map(|(provider, _)| provider);
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn node(name: &str, provider_id: &str, labels: serde_json::Value) -> Node {
+        serde_json::from_value(json!({
+            "metadata": { "name": name, "labels": labels },
+            "spec": { "providerID": provider_id },
+            "status": {
+                "allocatable": {
+                    "cpu": "47500m",
+                    "memory": "192Gi",
+                    "nvidia.com/gpu": "4",
+                    "pods": "110",
+                },
+            },
+        }))
+        .unwrap()
+    }
+
+    fn gpu_pod(node: &str, gpus: u32, phase: &str) -> Pod {
+        serde_json::from_value(json!({
+            "metadata": { "name": "trainer" },
+            "spec": {
+                "nodeName": node,
+                "containers": [{
+                    "name": "main",
+                    "resources": { "limits": { "nvidia.com/gpu": gpus.to_string() } },
+                }],
+            },
+            "status": { "phase": phase },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_provider_from_id() {
+        let cases = [
+            (Some("aws:///us-east-1a/i-0abc"), CloudProvider::Aws),
+            (
+                Some("gce://project/us-central1-a/node-1"),
+                CloudProvider::Gcp,
+            ),
+            (Some("azure:///subscriptions/x/vm-0"), CloudProvider::Azure),
+            (
+                Some("kind://docker/kind/kind-control-plane"),
+                CloudProvider::OnPrem,
+            ),
+            (None, CloudProvider::OnPrem),
+        ];
+        for (id, provider) in cases {
+            assert_eq!(provider_from_id(id), provider, "{id:?}");
+        }
+    }
+
+    #[test]
+    fn test_device_names() {
+        let gfd: BTreeMap<String, String> = [
+            (GPU_PRODUCT_LABEL.to_string(), "NVIDIA-L4".to_string()),
+            (GPU_MEMORY_LABEL.to_string(), "23034".to_string()),
+        ]
+        .into();
+        assert_eq!(
+            device_name("nvidia.com/gpu", &gfd).as_deref(),
+            Some("NVIDIA L4 23GB")
+        );
+        assert_eq!(
+            device_name(
+                "amd.com/xilinx_u250_gen3x16_xdma_shell_4_1-0",
+                &BTreeMap::new()
+            )
+            .as_deref(),
+            Some("Xilinx U250")
+        );
+        assert_eq!(device_name("nvidia.com/gpu", &BTreeMap::new()), None);
+        assert_eq!(device_name("cpu", &gfd), None);
+    }
+
+    #[test]
+    fn test_build_cluster_hardware() {
+        let nodes = [
+            node(
+                "gpu-a",
+                "aws:///us-east-1a/i-0a",
+                json!({
+                    "node.kubernetes.io/instance-type": "g6.12xlarge",
+                    GPU_PRODUCT_LABEL: "NVIDIA-L4",
+                }),
+            ),
+            node("gpu-b", "aws:///us-east-1b/i-0b", json!({})),
+        ];
+        let pods = [
+            gpu_pod("gpu-a", 2, "Running"),
+            gpu_pod("gpu-a", 1, "Pending"),
+            // Finished pods have released their GPUs
+            gpu_pod("gpu-a", 1, "Succeeded"),
+            gpu_pod("gpu-b", 1, "Running"),
+        ];
+
+        let status = build_cluster_hardware(&nodes, &pods);
+        assert!(status.k8s_connected);
+        assert_eq!(status.detected_provider, Some(CloudProvider::Aws));
+        assert_eq!((status.total_gpus, status.gpu_available), (8, 4));
+
+        let a = &status.nodes[0];
+        assert_eq!(a.provider_instance_type.as_deref(), Some("g6.12xlarge"));
+        assert_eq!(a.cpu_allocatable, "47500m");
+        assert_eq!(a.memory_allocatable_bytes, 192 << 30);
+        assert_eq!(a.accelerators.len(), 1);
+        assert_eq!(
+            (a.accelerators[0].allocatable, a.accelerators[0].allocated),
+            (4, 3)
+        );
+        assert_eq!(a.accelerators[0].device_name.as_deref(), Some("NVIDIA L4"));
+        assert_eq!(status.nodes[1].provider_instance_type, None);
+    }
+
+    #[test]
+    fn test_build_cluster_hardware_empty() {
+        let status = build_cluster_hardware(&[], &[]);
+        assert_eq!(status.detected_provider, None);
+        assert!(status.nodes.is_empty());
+    }
+}
@@ -0,0 +1,1881 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+
+use futures::stream::BoxStream;
+use futures::{AsyncBufReadExt, StreamExt, TryStreamExt};
+use k8s_openapi::api::apps::v1::StatefulSet;
+use k8s_openapi::api::autoscaling::v2::HorizontalPodAutoscaler;
+use k8s_openapi::api::core::v1::{Event, Node, PersistentVolumeClaim, Pod};
+use k8s_openapi::api::storage::v1::StorageClass;
+use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+use kube::api::{
+    Api, AttachParams, AttachedProcess, DeleteParams, ListParams, LogParams, Patch, PatchParams,
+};
+use megafactory_sql_types::configurator::{ClusterHardwareStatus, DeploymentConfig};
+use megafactory_sql_types::exec::ExecPolicy;
+use megafactory_sql_types::k8s::{
+    AutoscalerStatus, AutoscalerUpdate, ClusterStatus, KedaConfig, MegaDbSummary, PodInfo,
+    ScaleRequest, ScaleResponse, VolumeInfo, VolumeResizeRequest,
+};
+use megafactory_sql_types::logs::LogQuery;
+use megafactory_sql_types::ws::K8sEvent;
+use tokio::sync::{broadcast, OnceCell};
+
+use super::crd::MegaDB;
+use super::events::EventLog;
+use super::hardware::build_cluster_hardware;
+use super::keda::ScaledObject;
+use super::megadb::{MegaDbClient, MegaDbError};
+
+/// Label naming the MegaDB cluster a resource belongs to.
+pub const INSTANCE_LABEL: &str = "app.kubernetes.io/instance";
+/// Label holding a pod's MegaDB role ("coordinator" or "worker").
+pub const COMPONENT_LABEL: &str = "app.kubernetes.io/component";
+
+/// Pod template annotation bumped to trigger a rolling restart, as
+/// `kubectl rollout restart` does.
+const RESTARTED_AT_ANNOTATION: &str = "kubectl.kubernetes.io/restartedAt";
+
+/// Field manager for server-side apply.
+const FIELD_MANAGER: &str = "megafactory-sql";
+
+/// Port reported in `coordinator_endpoint` when the pod spec names none.
+const DEFAULT_COORDINATOR_PORT: i32 = 5432;
+
+/// Errors returned by [`K8sClient`].
+#[derive(Debug, thiserror::Error)]
+pub enum K8sError {
+    #[error("Kubernetes not configured: {0}")]
+    Unavailable(String),
+    #[error("Kubernetes API error: {0}")]
+    Api(#[from] kube::Error),
+    /// The request was rejected before reaching the API.
+    #[error("{0}")]
+    Invalid(String),
+    /// The object exists but isn't part of this cluster.
+    #[error("{0}")]
+    NotFound(String),
+    /// Refused by server configuration (e.g. the exec policy).
+    #[error("{0}")]
+    Forbidden(String),
+    /// MegaDB refused or failed a step of the operation (e.g. a drain).
+    #[error(transparent)]
+    MegaDb(#[from] MegaDbError),
+}
+
+impl K8sError {
+    /// HTTP status a proxy should answer with for this error.
+    pub fn status_code(&self) -> u16 {
+        match self {
+            Self::Unavailable(_) => 503,
+            Self::Api(kube::Error::Api(resp)) => resp.code,
+            Self::Api(_) => 502,
+            Self::Invalid(_) => 400,
+            Self::NotFound(_) => 404,
+            Self::Forbidden(_) => 403,
+            Self::MegaDb(e) => e.status_code(),
+        }
+    }
+}
+
+/// Kubernetes access scoped to one MegaDB cluster in one namespace.
+///
+/// The kube client is inferred on first use (in-cluster service account or
+/// kubeconfig), so the server starts without a cluster. Cheap to clone;
+/// clones share the client, the event log and the channel of new events.
+#[derive(Clone)]
+pub struct K8sClient {
+    namespace: String,
+    cluster: String,
+    scaling: KedaConfig,
+    exec: ExecPolicy,
+    client: Arc<OnceCell<kube::Client>>,
+    events_tx: broadcast::Sender<K8sEvent>,
+    events: Arc<EventLog>,
+}
+
+impl std::fmt::Debug for K8sClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("K8sClient")
+            .field("namespace", &self.namespace)
+            .field("cluster", &self.cluster)
+            .field("scaling", &self.scaling)
+            .field("exec", &self.exec)
+            .field("connected", &self.client.initialized())
+            .finish()
+    }
+}
+
+impl K8sClient {
+    pub fn new(namespace: impl Into<String>, cluster: impl Into<String>) -> Self {
+        Self::with_cell(OnceCell::new(), namespace.into(), cluster.into())
+    }
+
+    /// Use an existing kube client instead of inferring one.
+    pub fn with_client(
+        client: kube::Client,
+        namespace: impl Into<String>,
+        cluster: impl Into<String>,
+    ) -> Self {
+        Self::with_cell(
+            OnceCell::new_with(Some(client)),
+            namespace.into(),
+            cluster.into(),
+        )
+    }
+
+    fn with_cell(client: OnceCell<kube::Client>, namespace: String, cluster: String) -> Self {
+        let (events_tx, _) = broadcast::channel(64);
+        Self {
+            namespace,
+            cluster,
+            scaling: KedaConfig::default(),
+            exec: ExecPolicy::default(),
+            client: Arc::new(client),
+            events_tx,
+            events: Arc::new(EventLog::default()),
+        }
+    }
+
+    /// Replica bounds enforced by [`scale`](Self::scale).
+    pub fn with_scaling(mut self, scaling: KedaConfig) -> Self {
+        self.scaling = scaling;
+        self
+    }
+
+    pub fn scaling(&self) -> &KedaConfig {
+        &self.scaling
+    }
+
+    /// Commands [`exec`](Self::exec) may run; exec is disabled by default.
+    pub fn with_exec(mut self, exec: ExecPolicy) -> Self {
+        self.exec = exec;
+        self
+    }
+
+    pub fn exec_policy(&self) -> &ExecPolicy {
+        &self.exec
+    }
+
+    /// Events as they are recorded: changes made through this client
+    /// (scaling, restarts, ...) and whatever the cluster watchers report,
+    /// for the WebSocket `k8s` topic.
+    pub fn subscribe_events(&self) -> broadcast::Receiver<K8sEvent> {
+        self.events_tx.subscribe()
+    }
+
+    /// Add `event` to the event log and broadcast it with its assigned id.
+    pub fn record_event(&self, event: K8sEvent) -> K8sEvent {
+        let event = self.events.record(event);
+        // No receivers just means no WebSocket hub is relaying events.
+        let _ = self.events_tx.send(event.clone());
+        event
+    }
+
+    /// Logged events newer than id `since`, oldest first.
+    pub fn events_since(&self, since: u64) -> Vec<K8sEvent> {
+        self.events.since(since)
+    }
+
+    fn emit(&self, event_type: &str, object_kind: &str, object: &str, message: String) {
+        self.record_event(K8sEvent::new(event_type, object_kind, object, message));
+    }
+
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /// MegaDB cluster name, the value of [`INSTANCE_LABEL`] on its resources.
+    pub fn cluster(&self) -> &str {
+        &self.cluster
+    }
+
+    /// The kube client; failures are not cached, so a later call retries.
+    pub async fn client(&self) -> Result<kube::Client, K8sError> {
+        self.client
+            .get_or_try_init(kube::Client::try_default)
+            .await
+            .cloned()
+            .map_err(|e| K8sError::Unavailable(e.to_string()))
+    }
+
+    /// Name of the StatefulSet running the cluster's workers.
+    pub fn worker_statefulset(&self) -> String {
+        format!("{}-worker", self.cluster)
+    }
+
+    /// Label selector matching every resource of the cluster.
+    fn selector(&self) -> String {
+        format!("{INSTANCE_LABEL}={}", self.cluster)
+    }
+
+    /// Hardware of every node in the cluster, with accelerators allocated
+    /// to pods in any namespace, for the configurator.
+    pub async fn cluster_hardware(&self) -> Result<ClusterHardwareStatus, K8sError> {
+        let client = self.client().await?;
+        let nodes: Api<Node> = Api::all(client.clone());
+        let pods: Api<Pod> = Api::all(client);
+
+        let nodes = nodes.list(&ListParams::default()).await?.items;
+        let pods = pods
+            .list(&ListParams::default().fields("status.phase!=Succeeded,status.phase!=Failed"))
+            .await?
+            .items;
+        Ok(build_cluster_hardware(&nodes, &pods))
+    }
+
+    /// Assemble the dashboard's view of the cluster: worker StatefulSet
+    /// replicas, pods with live CPU/memory, and the PVCs they mount.
+    ///
+    /// Usage from metrics.k8s.io and kubelet stats is best-effort: without
+    /// metrics-server or node proxy access those figures read as 0.
+    pub async fn cluster_status(&self) -> Result<ClusterStatus, K8sError> {
+        let client = self.client().await?;
+        let statefulsets: Api<StatefulSet> = Api::namespaced(client.clone(), &self.namespace);
+        let pods: Api<Pod> = Api::namespaced(client.clone(), &self.namespace);
+        let pvcs: Api<PersistentVolumeClaim> = Api::namespaced(client.clone(), &self.namespace);
+
+        let megadbs: Api<MegaDB> = Api::namespaced(client.clone(), &self.namespace);
+
+        let workers = statefulsets.get_opt(&self.worker_statefulset()).await?;
+        // Absent when the operator's CRD isn't installed or the cluster predates it
+        let megadb = megadbs.get_opt(&self.cluster).await.unwrap_or_else(|e| {
+            tracing::debug!("MegaDB resource unavailable: {e}");
+            None
+        });
+        let pods = pods
+            .list(&ListParams::default().labels(&self.selector()))
+            .await?
+            .items;
+        let pvcs = pvcs.list(&ListParams::default()).await?.items;
+
+        let pod_usage = self.pod_usage(&client).await;
+        let volume_usage = self.volume_usage(&client, &pod_nodes(&pods)).await;
+        let expandable_classes = expandable_classes(&client).await;
+
+        let resources = ClusterResources {
+            workers,
+            megadb,
+            pods,
+            pvcs,
+            pod_usage,
+            volume_usage,
+            expandable_classes,
+        };
+        Ok(build_cluster_status(
+            &self.worker_statefulset(),
+            &resources,
+            chrono::Utc::now(),
+        ))
+    }
+
+    /// The PVCs the cluster's pods mount, as in
+    /// [`cluster_status`](Self::cluster_status) but without its workload and
+    /// pod metrics lookups.
+    pub async fn volumes(&self) -> Result<Vec<VolumeInfo>, K8sError> {
+        let client = self.client().await?;
+        let pods: Api<Pod> = Api::namespaced(client.clone(), &self.namespace);
+        let pvcs: Api<PersistentVolumeClaim> = Api::namespaced(client.clone(), &self.namespace);
+
+        // Needed to tell which claims are the cluster's and where they're mounted
+        let pods = pods
+            .list(&ListParams::default().labels(&self.selector()))
+            .await?
+            .items;
+        let pvcs = pvcs.list(&ListParams::default()).await?.items;
+        let volume_usage = self.volume_usage(&client, &pod_nodes(&pods)).await;
+        let expandable_classes = expandable_classes(&client).await;
+        Ok(cluster_volumes(
+            &pods,
+            &pvcs,
+            &volume_usage,
+            &expandable_classes,
+        ))
+    }
+
+    /// Set the worker StatefulSet's replicas through its scale subresource,
+    /// within the [`KedaConfig`] bounds.
+    pub async fn scale(&self, req: &ScaleRequest) -> Result<ScaleResponse, K8sError> {
+        self.scaling
+            .validate_replicas(req.replicas)
+            .map_err(K8sError::Invalid)?;
+
+        let client = self.client().await?;
+        let statefulsets: Api<StatefulSet> = Api::namespaced(client, &self.namespace);
+        let name = self.worker_statefulset();
+
+        let previous = statefulsets.get_scale(&name).await?;
+        let previous_replicas = previous.spec.and_then(|s| s.replicas).unwrap_or(0);
+        let patch = serde_json::json!({ "spec": { "replicas": req.replicas } });
+        let scale = statefulsets
+            .patch_scale(&name, &PatchParams::default(), &Patch::Merge(&patch))
+            .await?;
+
+        let response = ScaleResponse {
+            previous_replicas,
+            replicas: scale.spec.and_then(|s| s.replicas).unwrap_or(req.replicas),
+            current_replicas: scale.status.map(|s| s.replicas).unwrap_or(0),
+        };
+        tracing::info!(
+            "Scaled {name}: {} -> {} replicas",
+            response.previous_replicas,
+            response.replicas
+        );
+        self.emit(
+            "SCALED",
+            "StatefulSet",
+            &name,
+            format!(
+                "Workers scaled {} -> {}",
+                response.previous_replicas, response.replicas
+            ),
+        );
+        Ok(response)
+    }
+
+    /// Grow one of the cluster's PVCs by raising its storage request; the
+    /// CSI driver then expands the volume (and its filesystem) online.
+    pub async fn resize_volume(
+        &self,
+        name: &str,
+        req: &VolumeResizeRequest,
+    ) -> Result<(), K8sError> {
+        let client = self.client().await?;
+        let pods: Api<Pod> = Api::namespaced(client.clone(), &self.namespace);
+        let pvcs: Api<PersistentVolumeClaim> = Api::namespaced(client.clone(), &self.namespace);
+        // Only claims the cluster's pods mount
+        let mounted = pods
+            .list(&ListParams::default().labels(&self.selector()))
+            .await?
+            .items
+            .iter()
+            .any(|pod| pod_claims(pod).any(|claim| claim == name));
+        let pvc = if mounted {
+            pvcs.get_opt(name).await?
+        } else {
+            None
+        };
+        let Some(mut volume) = pvc.as_ref().and_then(volume_info) else {
+            return Err(K8sError::NotFound(format!(
+                "PVC {name} is not mounted by cluster {}",
+                self.cluster
+            )));
+        };
+        if !volume.storage_class.is_empty() {
+            let classes: Api<StorageClass> = Api::all(client);
+            volume.expandable = classes
+                .get_opt(&volume.storage_class)
+                .await?
+                .and_then(|c| c.allow_volume_expansion)
+                .unwrap_or(false);
+        }
+        volume
+            .validate_resize(req.size_gib)
+            .map_err(K8sError::Invalid)?;
+
+        let size = format!("{}Gi", req.size_gib);
+        let patch = serde_json::json!({
+            "spec": { "resources": { "requests": { "storage": size } } }
+        });
+        pvcs.patch(name, &PatchParams::default(), &Patch::Merge(&patch))
+            .await?;
+        tracing::info!("Resizing PVC {name} to {size}");
+        self.emit(
+            "RESIZED",
+            "PersistentVolumeClaim",
+            name,
+            format!("Expanding volume to {size}"),
+        );
+        Ok(())
+    }
+
+    /// Restart the workers one at a time by bumping the StatefulSet's pod
+    /// template annotation, like `kubectl rollout restart`.
+    pub async fn rolling_restart(&self) -> Result<(), K8sError> {
+        let statefulsets: Api<StatefulSet> = Api::namespaced(self.client().await?, &self.namespace);
+        let name = self.worker_statefulset();
+        let now = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+        let patch = serde_json::json!({
+            "spec": { "template": { "metadata": { "annotations": {
+                RESTARTED_AT_ANNOTATION: now,
+            } } } }
+        });
+        statefulsets
+            .patch(&name, &PatchParams::default(), &Patch::Merge(&patch))
+            .await?;
+        tracing::info!("Rolling restart of {name}");
+        self.emit(
+            "RESTARTED",
+            "StatefulSet",
+            &name,
+            "Rolling restart started".into(),
+        );
+        Ok(())
+    }
+
+    /// Delete one of the cluster's pods; the StatefulSet recreates it.
+    pub async fn delete_pod(&self, pod: &str) -> Result<(), K8sError> {
+        self.cluster_pods(pod)
+            .await?
+            .delete(pod, &DeleteParams::default())
+            .await?;
+        tracing::info!("Deleted pod {pod}");
+        self.emit(
+            "DELETED",
+            "Pod",
+            pod,
+            "Pod deleted from the dashboard".into(),
+        );
+        Ok(())
+    }
+
+    /// Drain a worker through MegaDB at `base_url`, then delete its pod so
+    /// it comes back empty. Coordinators can't be drained.
+    pub async fn drain_worker(
+        &self,
+        megadb: &MegaDbClient,
+        base_url: &str,
+        pod: &str,
+    ) -> Result<(), K8sError> {
+        let pods = self.cluster_pods(pod).await?;
+        let role = pods.get(pod).await.map(|p| pod_role(&p))?;
+        if role != "worker" {
+            return Err(K8sError::Invalid(format!(
+                "{pod} is a {role}; only workers can be drained"
+            )));
+        }
+        megadb.drain_worker(base_url, pod).await?;
+        self.emit("DRAINED", "Pod", pod, "Worker drained".into());
+        pods.delete(pod, &DeleteParams::default()).await?;
+        tracing::info!("Drained and deleted worker {pod}");
+        self.emit("DELETED", "Pod", pod, "Drained worker deleted".into());
+        Ok(())
+    }
+
+    fn megadbs(&self, client: kube::Client) -> Api<MegaDB> {
+        Api::namespaced(client, &self.namespace)
+    }
+
+    /// MegaDB custom resources in the namespace.
+    pub async fn list_megadbs(&self) -> Result<Vec<MegaDbSummary>, K8sError> {
+        let list = self
+            .megadbs(self.client().await?)
+            .list(&ListParams::default())
+            .await?;
+        Ok(list.items.iter().map(MegaDB::summary).collect())
+    }
+
+    pub async fn get_megadb(&self, name: &str) -> Result<Option<MegaDB>, K8sError> {
+        Ok(self.megadbs(self.client().await?).get_opt(name).await?)
+    }
+
+    /// The deployment config of a live MegaDB resource, with the KEDA
+    /// settings of its worker ScaledObject when there is one.
+    pub async fn megadb_config(&self, name: &str) -> Result<DeploymentConfig, K8sError> {
+        let client = self.client().await?;
+        let megadb = self
+            .megadbs(client.clone())
+            .get_opt(name)
+            .await?
+            .ok_or_else(|| K8sError::NotFound(format!("MegaDB {name} not found")))?;
+        let scaled_objects: Api<ScaledObject> = Api::namespaced(client, &self.namespace);
+        // Without KEDA installed the resource type is missing, which reads
+        // the same as no autoscaling
+        let scaled_object = scaled_objects
+            .get_opt(&format!("{name}-worker"))
+            .await
+            .unwrap_or_else(|e| {
+                tracing::debug!("ScaledObject unavailable: {e}");
+                None
+            });
+        megadb
+            .to_config(scaled_object.as_ref())
+            .map_err(|e| K8sError::Invalid(e.to_string()))
+    }
+
+    /// Create or update the cluster's MegaDB resource with server-side
+    /// apply; other names are refused. A dry run is validated and defaulted
+    /// by the API server but not persisted.
+    pub async fn apply_megadb(&self, megadb: &MegaDB, dry_run: bool) -> Result<MegaDB, K8sError> {
+        let name = megadb
+            .metadata
+            .name
+            .clone()
+            .ok_or_else(|| K8sError::Invalid("MegaDB resource has no name".into()))?;
+        self.check_cluster_name(&name)?;
+        let mut params = PatchParams::apply(FIELD_MANAGER);
+        if dry_run {
+            params = params.dry_run();
+        }
+        // Apply bodies must not carry server-managed metadata
+        let mut body = MegaDB::new(&name, megadb.spec.clone());
+        let mut labels = megadb.metadata.labels.clone().unwrap_or_default();
+        labels.insert(INSTANCE_LABEL.into(), self.cluster.clone());
+        body.metadata.labels = Some(labels);
+        body.metadata.annotations = megadb.metadata.annotations.clone();
+
+        let applied = self
+            .megadbs(self.client().await?)
+            .patch(&name, &params, &Patch::Apply(&body))
+            .await?;
+        if !dry_run {
+            self.emit(
+                "APPLIED",
+                "MegaDB",
+                &name,
+                format!("MegaDB applied with {} workers", applied.spec.replicas),
+            );
+        }
+        Ok(applied)
+    }
+
+    /// Delete the cluster's MegaDB resource. Other names, and resources
+    /// without the cluster's [`INSTANCE_LABEL`], are refused.
+    pub async fn delete_megadb(&self, name: &str) -> Result<(), K8sError> {
+        self.check_cluster_name(name)?;
+        let megadbs = self.megadbs(self.client().await?);
+        let in_cluster = megadbs
+            .get_opt(name)
+            .await?
+            .and_then(|m| m.metadata.labels?.remove(INSTANCE_LABEL))
+            .is_some_and(|instance| instance == self.cluster);
+        if !in_cluster {
+            return Err(K8sError::NotFound(format!(
+                "MegaDB {name} is not part of cluster {}",
+                self.cluster
+            )));
+        }
+        megadbs.delete(name, &DeleteParams::default()).await?;
+        self.emit(
+            "DELETED",
+            "MegaDB",
+            name,
+            "MegaDB deletion requested".into(),
+        );
+        Ok(())
+    }
+
+    /// The KEDA ScaledObject autoscaling the workers, with live trigger
+    /// values and recent scaling; `None` when the workers aren't autoscaled
+    /// (or KEDA isn't installed).
+    pub async fn autoscaler(&self) -> Result<Option<AutoscalerStatus>, K8sError> {
+        let client = self.client().await?;
+        let scaled_objects: Api<ScaledObject> = Api::namespaced(client.clone(), &self.namespace);
+        match scaled_objects.get_opt(&self.worker_statefulset()).await? {
+            Some(scaled_object) => Ok(Some(self.autoscaler_status(&client, &scaled_object).await)),
+            None => Ok(None),
+        }
+    }
+
+    /// Change the worker ScaledObject's replica bounds and trigger
+    /// thresholds; KEDA picks the change up on its next poll.
+    pub async fn update_autoscaler(
+        &self,
+        update: &AutoscalerUpdate,
+    ) -> Result<AutoscalerStatus, K8sError> {
+        update.validate().map_err(K8sError::Invalid)?;
+        let client = self.client().await?;
+        let scaled_objects: Api<ScaledObject> = Api::namespaced(client.clone(), &self.namespace);
+        let name = self.worker_statefulset();
+        let current = scaled_objects
+            .get_opt(&name)
+            .await?
+            .ok_or_else(|| K8sError::NotFound(format!("ScaledObject {name} not found")))?;
+
+        let mut triggers = current.spec.triggers.clone();
+        for (trigger_name, threshold) in &update.thresholds {
+            let trigger = triggers
+                .iter_mut()
+                .enumerate()
+                .find(|(i, t)| {
+                    t.to_scaling_trigger(*i)
+                        .is_some_and(|t| &t.name == trigger_name)
+                })
+                .map(|(_, t)| t)
+                .ok_or_else(|| {
+                    K8sError::Invalid(format!("{name} has no Prometheus trigger {trigger_name}"))
+                })?;
+            trigger
+                .metadata
+                .insert("threshold".into(), threshold.to_string());
+        }
+        // Merge patches replace lists whole, so every trigger is sent back
+        let patch = serde_json::json!({
+            "spec": {
+                "minReplicaCount": update.min_replicas,
+                "maxReplicaCount": update.max_replicas,
+                "triggers": triggers,
+            }
+        });
+        let updated = scaled_objects
+            .patch(&name, &PatchParams::default(), &Patch::Merge(&patch))
+            .await?;
+        tracing::info!(
+            "Updated autoscaler {name}: {}..{} replicas",
+            update.min_replicas,
+            update.max_replicas
+        );
+        self.emit(
+            "AUTOSCALER_UPDATED",
+            "ScaledObject",
+            &name,
+            format!(
+                "Autoscaling between {} and {} workers",
+                update.min_replicas, update.max_replicas
+            ),
+        );
+        Ok(self.autoscaler_status(&client, &updated).await)
+    }
+
+    /// Create or update a ScaledObject with server-side apply, like
+    /// [`apply_megadb`](Self::apply_megadb).
+    pub async fn apply_scaled_object(
+        &self,
+        scaled_object: &ScaledObject,
+        dry_run: bool,
+    ) -> Result<ScaledObject, K8sError> {
+        let name = scaled_object
+            .metadata
+            .name
+            .clone()
+            .ok_or_else(|| K8sError::Invalid("ScaledObject has no name".into()))?;
+        let mut params = PatchParams::apply(FIELD_MANAGER);
+        if dry_run {
+            params = params.dry_run();
+        }
+        let body = ScaledObject::new(&name, scaled_object.spec.clone());
+        let scaled_objects: Api<ScaledObject> =
+            Api::namespaced(self.client().await?, &self.namespace);
+        let applied = scaled_objects
+            .patch(&name, &params, &Patch::Apply(&body))
+            .await?;
+        if !dry_run {
+            self.emit(
+                "APPLIED",
+                "ScaledObject",
+                &name,
+                "ScaledObject applied".into(),
+            );
+        }
+        Ok(applied)
+    }
+
+    /// Join a ScaledObject with its HPA and events. Both are best-effort:
+    /// without them values and history are just empty.
+    async fn autoscaler_status(
+        &self,
+        client: &kube::Client,
+        scaled_object: &ScaledObject,
+    ) -> AutoscalerStatus {
+        let hpas: Api<HorizontalPodAutoscaler> = Api::namespaced(client.clone(), &self.namespace);
+        let hpa = hpas
+            .get_opt(&scaled_object.hpa_name())
+            .await
+            .unwrap_or_else(|e| {
+                tracing::debug!("HPA unavailable: {e}");
+                None
+            });
+        let events: Api<Event> = Api::namespaced(client.clone(), &self.namespace);
+        let events = match events.list(&ListParams::default()).await {
+            Ok(list) => list.items,
+            Err(e) => {
+                tracing::debug!("Events unavailable: {e}");
+                Vec::new()
+            }
+        };
+        scaled_object.autoscaler_status(hpa.as_ref(), &events)
+    }
+
+    /// Stream the log lines of one of the cluster's pods, coordinator or
+    /// worker. Other pods in the namespace are refused.
+    pub async fn pod_logs(
+        &self,
+        pod: &str,
+        query: &LogQuery,
+    ) -> Result<BoxStream<'static, Result<String, K8sError>>, K8sError> {
+        let pods = self.cluster_pods(pod).await?;
+        let params = LogParams {
+            container: query.container.clone(),
+            follow: query.follow,
+            tail_lines: query.tail_lines,
+            previous: query.previous,
+            since_seconds: query.since_seconds,
+            // The API rejects both at once
+            since_time: query.since_time.filter(|_| query.since_seconds.is_none()),
+            ..LogParams::default()
+        };
+        let lines = pods
+            .log_stream(pod, &params)
+            .await?
+            .lines()
+            .map_err(|e| K8sError::Api(kube::Error::ReadEvents(e)));
+        Ok(lines.boxed())
+    }
+
+    /// Run `command` in one of the cluster's pods, if the [`ExecPolicy`]
+    /// allows it. With `tty` the process gets a terminal and stderr is
+    /// merged into stdout.
+    pub async fn exec(
+        &self,
+        pod: &str,
+        command: Vec<String>,
+        tty: bool,
+    ) -> Result<AttachedProcess, K8sError> {
+        self.exec.check(&command).map_err(K8sError::Forbidden)?;
+        let pods = self.cluster_pods(pod).await?;
+        let params = if tty {
+            AttachParams::interactive_tty()
+        } else {
+            AttachParams::default().stdin(true)
+        };
+        let process = pods.exec(pod, command.clone(), &params).await?;
+        tracing::info!("Exec in {pod}: {}", command.join(" "));
+        self.emit("EXEC", "Pod", pod, format!("Exec: {}", command.join(" ")));
+        Ok(process)
+    }
+
+    /// Refuse MegaDB resources other than the cluster's own, so other
+    /// instances in the namespace stay out of reach.
+    fn check_cluster_name(&self, name: &str) -> Result<(), K8sError> {
+        if name == self.cluster {
+            Ok(())
+        } else {
+            Err(K8sError::NotFound(format!(
+                "MegaDB {name} is not cluster {}",
+                self.cluster
+            )))
+        }
+    }
+
+    /// The pod API, after checking `pod` belongs to this cluster so other
+    /// workloads in the namespace stay out of reach.
+    async fn cluster_pods(&self, pod: &str) -> Result<Api<Pod>, K8sError> {
+        let pods: Api<Pod> = Api::namespaced(self.client().await?, &self.namespace);
+        let in_cluster = pods
+            .get_opt(pod)
+            .await?
+            .and_then(|p| p.metadata.labels?.remove(INSTANCE_LABEL))
+            .is_some_and(|instance| instance == self.cluster);
+        if !in_cluster {
+            return Err(K8sError::NotFound(format!(
+                "Pod {pod} is not part of cluster {}",
+                self.cluster
+            )));
+        }
+        Ok(pods)
+    }
+
+    /// CPU (cores) and memory (bytes) per pod from metrics.k8s.io.
+    async fn pod_usage(&self, client: &kube::Client) -> HashMap<String, ResourceUsage> {
+        let url = format!(
+            "/apis/metrics.k8s.io/v1beta1/namespaces/{}/pods?labelSelector={}",
+            self.namespace,
+            self.selector(),
+        );
+        let list = match get_json::<PodMetricsList>(client, &url).await {
+            Ok(list) => list,
+            Err(e) => {
+                tracing::debug!("Pod metrics unavailable: {e}");
+                return HashMap::new();
+            }
+        };
+        list.items
+            .into_iter()
+            .map(|pod| {
+                let usage = pod
+                    .containers
+                    .iter()
+                    .fold(ResourceUsage::default(), |acc, c| ResourceUsage {
+                        cpu_cores: acc.cpu_cores + quantity(c.usage.get("cpu")),
+                        memory_bytes: acc.memory_bytes + quantity(c.usage.get("memory")),
+                    });
+                (pod.metadata.name.unwrap_or_default(), usage)
+            })
+            .collect()
+    }
+
+    /// Used bytes per PVC name in this namespace, from each node's kubelet stats.
+    async fn volume_usage(&self, client: &kube::Client, nodes: &[&str]) -> HashMap<String, u64> {
+        let summaries = futures::future::join_all(nodes.iter().map(|node| async move {
+            let url = format!("/api/v1/nodes/{node}/proxy/stats/summary");
+            get_json::<StatsSummary>(client, &url).await
+        }))
+        .await;
+
+        let mut usage = HashMap::new();
+        for summary in summaries {
+            let summary = match summary {
+                Ok(summary) => summary,
+                Err(e) => {
+                    tracing::debug!("Kubelet stats unavailable: {e}");
+                    continue;
+                }
+            };
+            let volumes = summary.pods.into_iter().flat_map(|p| p.volume);
+            for volume in volumes {
+                if let (Some(pvc), Some(used)) = (volume.pvc_ref, volume.used_bytes) {
+                    if pvc.namespace == self.namespace {
+                        usage.insert(pvc.name, used);
+                    }
+                }
+            }
+        }
+        usage
+    }
+}
+
+/// Storage classes with `allowVolumeExpansion`. Best-effort like usage:
+/// without access to the cluster-scoped classes no volume is expandable.
+async fn expandable_classes(client: &kube::Client) -> HashSet<String> {
+    let classes: Api<StorageClass> = Api::all(client.clone());
+    match classes.list(&ListParams::default()).await {
+        Ok(list) => list
+            .items
+            .into_iter()
+            .filter(|c| c.allow_volume_expansion == Some(true))
+            .filter_map(|c| c.metadata.name)
+            .collect(),
+        Err(e) => {
+            tracing::debug!("Storage classes unavailable: {e}");
+            HashSet::new()
+        }
+    }
+}
+
+async fn get_json<T: serde::de::DeserializeOwned>(
+    client: &kube::Client,
+    url: &str,
+) -> Result<T, kube::Error> {
+    let request = http::Request::get(url)
+        .body(Vec::new())
+        .map_err(kube::Error::HttpError)?;
+    client.request(request).await
+}
+
+/// Live resource usage of one pod, summed over its containers.
+#[derive(Debug, Clone, Copy, Default)]
+struct ResourceUsage {
+    cpu_cores: f64,
+    memory_bytes: f64,
+}
+
+/// `GET /apis/metrics.k8s.io/v1beta1/namespaces/{ns}/pods`
+#[derive(Debug, serde::Deserialize)]
+struct PodMetricsList {
+    items: Vec<PodMetrics>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct PodMetrics {
+    metadata: kube::api::ObjectMeta,
+    #[serde(default)]
+    containers: Vec<ContainerMetrics>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct ContainerMetrics {
+    #[serde(default)]
+    usage: BTreeMap<String, Quantity>,
+}
+
+/// `GET /api/v1/nodes/{node}/proxy/stats/summary` (only the fields used here).
+#[derive(Debug, serde::Deserialize)]
+struct StatsSummary {
+    #[serde(default)]
+    pods: Vec<PodStats>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct PodStats {
+    #[serde(default)]
+    volume: Vec<VolumeStats>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VolumeStats {
+    used_bytes: Option<u64>,
+    pvc_ref: Option<PvcRef>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct PvcRef {
+    name: String,
+    namespace: String,
+}
+
+/// Parse a Kubernetes quantity ("250m", "1.5", "512Mi", "2G", "1e3") as a
+/// plain number: cores for CPU, bytes for memory and storage.
+pub fn parse_quantity(s: &str) -> Option<f64> {
+    const SUFFIXES: [(&str, f64); 14] = [
+        ("Ki", 1024.0),
+        ("Mi", 1024.0 * 1024.0),
+        ("Gi", 1024.0 * 1024.0 * 1024.0),
+        ("Ti", 1024.0 * 1024.0 * 1024.0 * 1024.0),
+        ("Pi", 1024.0 * 1024.0 * 1024.0 * 1024.0 * 1024.0),
+        ("Ei", 1024.0 * 1024.0 * 1024.0 * 1024.0 * 1024.0 * 1024.0),
+        ("n", 1e-9),
+        ("u", 1e-6),
+        ("m", 1e-3),
+        ("k", 1e3),
+        ("M", 1e6),
+        ("G", 1e9),
+        ("T", 1e12),
+        ("P", 1e15),
+    ];
+    let s = s.trim();
+    for (suffix, factor) in SUFFIXES {
+        if let Some(number) = s.strip_suffix(suffix) {
+            return number.parse::<f64>().ok().map(|n| n * factor);
+        }
+    }
+    // Plain numbers, exponents ("1e3") and the rare "E" (exa) suffix
+    match s.strip_suffix('E') {
+        Some(number) => number.parse::<f64>().ok().map(|n| n * 1e18),
+        None => s.parse().ok(),
+    }
+}
+
+fn quantity(q: Option<&Quantity>) -> f64 {
+    q.and_then(|q| parse_quantity(&q.0)).unwrap_or(0.0)
+}
+
+/// Sum of a resource over a pod's containers, from limits or else requests.
+fn pod_resource(pod: &Pod, resource: &str) -> f64 {
+    let Some(spec) = pod.spec.as_ref() else {
+        return 0.0;
+    };
+    spec.containers
+        .iter()
+        .filter_map(|c| c.resources.as_ref())
+        .map(|r| {
+            let limit = r.limits.as_ref().and_then(|l| l.get(resource));
+            let request = r.requests.as_ref().and_then(|l| l.get(resource));
+            quantity(limit.or(request))
+        })
+        .sum()
+}
+
+/// The pod's role from [`COMPONENT_LABEL`], else inferred from its name.
+fn pod_role(pod: &Pod) -> String {
+    let label = pod
+        .metadata
+        .labels
+        .as_ref()
+        .and_then(|l| l.get(COMPONENT_LABEL));
+    match label {
+        Some(role) => role.clone(),
+        None if pod
+            .metadata
+            .name
+            .as_deref()
+            .is_some_and(|n| n.contains("coordinator")) =>
+        {
+            "coordinator".into()
+        }
+        None => "worker".into(),
+    }
+}
+
+fn pod_status(pod: &Pod) -> String {
+    if pod.metadata.deletion_timestamp.is_some() {
+        return "Terminating".into();
+    }
+    pod.status
+        .as_ref()
+        .and_then(|s| s.phase.clone())
+        .unwrap_or_else(|| "Unknown".into())
+}
+
+/// Claim names of the PVCs a pod mounts.
+fn pod_claims(pod: &Pod) -> impl Iterator<Item = &str> {
+    pod.spec
+        .iter()
+        .flat_map(|s| s.volumes.iter().flatten())
+        .filter_map(|v| v.persistent_volume_claim.as_ref())
+        .map(|pvc| pvc.claim_name.as_str())
+}
+
+/// What a claim itself says about its volume; usage, the pods mounting it
+/// and expandability come from elsewhere.
+fn volume_info(pvc: &PersistentVolumeClaim) -> Option<VolumeInfo> {
+    let spec = pvc.spec.as_ref();
+    let capacity = pvc
+        .status
+        .as_ref()
+        .and_then(|s| s.capacity.as_ref())
+        .and_then(|c| c.get("storage"));
+    Some(VolumeInfo {
+        name: pvc.metadata.name.clone()?,
+        access_mode: spec
+            .and_then(|s| s.access_modes.as_ref()?.first().cloned())
+            .unwrap_or_default(),
+        capacity_bytes: quantity(capacity) as u64,
+        used_bytes: 0,
+        bound_pod: String::new(),
+        storage_class: spec
+            .and_then(|s| s.storage_class_name.clone())
+            .unwrap_or_default(),
+        expandable: false,
+    })
+}
+
+/// `ip:port` of the first coordinator pod with an IP.
+fn coordinator_endpoint(pods: &[Pod]) -> String {
+    pods.iter()
+        .filter(|p| pod_role(p) == "coordinator")
+        .find_map(|p| {
+            let ip = p.status.as_ref()?.pod_ip.clone()?;
+            let port = p
+                .spec
+                .as_ref()
+                .and_then(|s| s.containers.first())
+                .and_then(|c| c.ports.as_ref()?.first())
+                .map(|port| port.container_port)
+                .unwrap_or(DEFAULT_COORDINATOR_PORT);
+            Some(format!("{ip}:{port}"))
+        })
+        .unwrap_or_default()
+}
+
+/// Everything [`K8sClient::cluster_status`] fetches for one cluster.
+struct ClusterResources {
+    workers: Option<StatefulSet>,
+    megadb: Option<MegaDB>,
+    pods: Vec<Pod>,
+    pvcs: Vec<PersistentVolumeClaim>,
+    pod_usage: HashMap<String, ResourceUsage>,
+    /// Used bytes per PVC name.
+    volume_usage: HashMap<String, u64>,
+    /// Names of storage classes that allow volume expansion.
+    expandable_classes: HashSet<String>,
+}
+
+/// Nodes running `pods`, once each.
+fn pod_nodes(pods: &[Pod]) -> Vec<&str> {
+    let mut nodes: Vec<&str> = pods
+        .iter()
+        .filter_map(|p| p.spec.as_ref()?.node_name.as_deref())
+        .collect();
+    nodes.sort_unstable();
+    nodes.dedup();
+    nodes
+}
+
+/// The claims among `pvcs` that `pods` mount, with usage and expandability.
+fn cluster_volumes(
+    pods: &[Pod],
+    pvcs: &[PersistentVolumeClaim],
+    volume_usage: &HashMap<String, u64>,
+    expandable_classes: &HashSet<String>,
+) -> Vec<VolumeInfo> {
+    // Which pods mount each claim; a claim shared by several pods is "shared".
+    let mut claim_pods: HashMap<&str, Vec<&str>> = HashMap::new();
+    for pod in pods {
+        let name = pod.metadata.name.as_deref().unwrap_or_default();
+        for claim in pod_claims(pod) {
+            claim_pods.entry(claim).or_default().push(name);
+        }
+    }
+
+    pvcs.iter()
+        .filter_map(|pvc| {
+            let volume = volume_info(pvc)?;
+            let bound = claim_pods.get(volume.name.as_str())?;
+            Some(VolumeInfo {
+                used_bytes: volume_usage.get(&volume.name).copied().unwrap_or(0),
+                bound_pod: match bound.as_slice() {
+                    [pod] => pod.to_string(),
+                    _ => "shared".into(),
+                },
+                expandable: expandable_classes.contains(&volume.storage_class),
+                ..volume
+            })
+        })
+        .collect()
+}
+
+/// Combine fetched resources into a [`ClusterStatus`].
+fn build_cluster_status(
+    worker_statefulset: &str,
+    resources: &ClusterResources,
+    now: chrono::DateTime<chrono::Utc>,
+) -> ClusterStatus {
+    let ClusterResources {
+        workers,
+        megadb,
+        pods,
+        pvcs,
+        pod_usage,
+        volume_usage,
+        expandable_classes,
+    } = resources;
+    let (workers, megadb) = (workers.as_ref(), megadb.as_ref());
+    let volumes = cluster_volumes(pods, pvcs, volume_usage, expandable_classes);
+
+    let pod_infos = pods
+        .iter()
+        .map(|pod| {
+            let name = pod.metadata.name.clone().unwrap_or_default();
+            let usage = pod_usage.get(&name).copied().unwrap_or_default();
+            let cpu_limit = pod_resource(pod, "cpu");
+            let claims: Vec<&str> = pod_claims(pod).collect();
+            let disk_usage_percent = volumes
+                .iter()
+                .filter(|v| claims.contains(&v.name.as_str()) && v.capacity_bytes > 0)
+                .map(|v| v.used_bytes as f64 / v.capacity_bytes as f64 * 100.0)
+                .fold(0.0, f64::max);
+            let started = pod.metadata.creation_timestamp.as_ref().map(|t| t.0);
+            PodInfo {
+                role: pod_role(pod),
+                status: pod_status(pod),
+                cpu_usage_percent: if cpu_limit > 0.0 {
+                    usage.cpu_cores / cpu_limit * 100.0
+                } else {
+                    0.0
+                },
+                memory_bytes: usage.memory_bytes as u64,
+                memory_limit_bytes: pod_resource(pod, "memory") as u64,
+                disk_usage_percent,
+                restart_count: pod
+                    .status
+                    .as_ref()
+                    .and_then(|s| s.container_statuses.as_ref())
+                    .map(|cs| cs.iter().map(|c| c.restart_count).sum())
+                    .unwrap_or(0),
+                age_seconds: started
+                    .map(|t| (now - t).num_seconds().max(0) as u64)
+                    .unwrap_or(0),
+                name,
+            }
+        })
+        .collect();
+
+    let desired = workers.and_then(|s| s.spec.as_ref()?.replicas).unwrap_or(0);
+    let ready = workers
+        .and_then(|s| s.status.as_ref()?.ready_replicas)
+        .unwrap_or(0);
+    let (phase, message) = match workers {
+        None => (
+            "NotFound",
+            format!("StatefulSet {worker_statefulset} not found"),
+        ),
+        Some(_) if desired == 0 => ("Stopped", "Scaled to 0 workers".to_string()),
+        Some(_) if ready >= desired => ("Ready", "All pods healthy".to_string()),
+        Some(_) if ready == 0 => ("Unavailable", "No workers ready".to_string()),
+        Some(_) => ("Degraded", format!("{ready}/{desired} workers ready")),
+    };
+
+    // The operator's view, when there is one, wins over the StatefulSet's
+    let status = megadb.and_then(|m| m.status.as_ref());
+    let phase = status
+        .and_then(|s| s.phase.clone())
+        .unwrap_or_else(|| phase.into());
+    let conditions = status
+        .map(|s| s.conditions.iter().map(Into::into).collect())
+        .unwrap_or_default();
+
+    ClusterStatus {
+        phase,
+        ready_replicas: ready,
+        total_replicas: desired,
+        coordinator_endpoint: coordinator_endpoint(pods),
+        message,
+        pods: pod_infos,
+        volumes,
+        conditions,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Fake API server: answers each request with a canned JSON fixture,
+    /// keyed by `"METHOD /path"` or just `"/path"` for any method, and
+    /// records each request as `"METHOD /path?query"`.
+    fn fake_api(
+        fixtures: Vec<(&'static str, serde_json::Value)>,
+    ) -> (kube::Client, Arc<std::sync::Mutex<Vec<String>>>) {
+        let requests = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = requests.clone();
+        let service = tower::service_fn(move |req: http::Request<kube::client::Body>| {
+            let path = req.uri().path().to_string();
+            let route = format!("{} {path}", req.method());
+            log.lock()
+                .unwrap()
+                .push(format!("{} {}", req.method(), req.uri()));
+            let (status, body) = fixtures
+                .iter()
+                .find(|(p, _)| *p == route)
+                .or_else(|| fixtures.iter().find(|(p, _)| *p == path))
+                .map(|(_, body)| (200, body.clone()))
+                .unwrap_or_else(|| (404, not_found(&path)));
+            // String fixtures are sent as plain text (pod logs)
+            let body = match body {
+                serde_json::Value::String(text) => text,
+                other => other.to_string(),
+            };
+            let response = http::Response::builder()
+                .status(status)
+                .body(kube::client::Body::from(body.into_bytes()));
+            async move { response }
+        });
+        (kube::Client::new(service, "megadb"), requests)
+    }
+
+    fn fake_client(fixtures: Vec<(&'static str, serde_json::Value)>) -> kube::Client {
+        fake_api(fixtures).0
+    }
+
+    fn not_found(path: &str) -> serde_json::Value {
+        json!({
+            "kind": "Status",
+            "apiVersion": "v1",
+            "status": "Failure",
+            "message": format!("{path} not found"),
+            "reason": "NotFound",
+            "code": 404,
+        })
+    }
+
+    fn pod(name: &str, role: &str, node: &str, claims: &[&str]) -> serde_json::Value {
+        json!({
+            "metadata": {
+                "name": name,
+                "labels": { INSTANCE_LABEL: "megadb", COMPONENT_LABEL: role },
+                "creationTimestamp": "2026-01-01T00:00:00Z",
+            },
+            "spec": {
+                "nodeName": node,
+                "containers": [{
+                    "name": "megadb",
+                    "ports": [{ "containerPort": 5433 }],
+                    "resources": { "limits": { "cpu": "2", "memory": "8Gi" } },
+                }],
+                "volumes": claims.iter().map(|c| json!({
+                    "name": c, "persistentVolumeClaim": { "claimName": c },
+                })).collect::<Vec<_>>(),
+            },
+            "status": {
+                "phase": "Running",
+                "podIP": "10.0.0.5",
+                "containerStatuses": [{
+                    "name": "megadb", "ready": true, "restartCount": 2,
+                    "image": "megadb", "imageID": "",
+                }],
+            },
+        })
+    }
+
+    fn pvc(name: &str, mode: &str, class: &str, capacity: &str) -> serde_json::Value {
+        json!({
+            "metadata": { "name": name },
+            "spec": { "accessModes": [mode], "storageClassName": class },
+            "status": { "capacity": { "storage": capacity } },
+        })
+    }
+
+    fn fixtures() -> Vec<(&'static str, serde_json::Value)> {
+        vec![
+            (
+                "/apis/apps/v1/namespaces/megadb/statefulsets/megadb-worker",
+                json!({
+                    "metadata": { "name": "megadb-worker" },
+                    "spec": {
+                        "replicas": 2,
+                        "selector": {},
+                        "template": {},
+                        "serviceName": "megadb-worker",
+                    },
+                    "status": { "replicas": 2, "readyReplicas": 1 },
+                }),
+            ),
+            (
+                "/api/v1/namespaces/megadb/pods",
+                json!({
+                    "metadata": {},
+                    "items": [
+                        pod("megadb-coordinator-0", "coordinator", "node-a", &["wal"]),
+                        pod("megadb-worker-0", "worker", "node-a", &["wal", "cache-0"]),
+                    ],
+                }),
+            ),
+            (
+                "/api/v1/namespaces/megadb/persistentvolumeclaims",
+                json!({
+                    "metadata": {},
+                    "items": [
+                        pvc("wal", "ReadWriteMany", "nfs", "50Gi"),
+                        pvc("cache-0", "ReadWriteOnce", "gp3", "100Gi"),
+                        pvc("unrelated", "ReadWriteOnce", "gp3", "1Gi"),
+                    ],
+                }),
+            ),
+            (
+                "/apis/storage.k8s.io/v1/storageclasses",
+                json!({
+                    "metadata": {},
+                    "items": [
+                        { "metadata": { "name": "nfs" }, "provisioner": "nfs" },
+                        {
+                            "metadata": { "name": "gp3" },
+                            "provisioner": "ebs.csi.aws.com",
+                            "allowVolumeExpansion": true,
+                        },
+                    ],
+                }),
+            ),
+            (
+                "/apis/metrics.k8s.io/v1beta1/namespaces/megadb/pods",
+                json!({
+                    "items": [{
+                        "metadata": { "name": "megadb-worker-0" },
+                        "containers": [{ "usage": { "cpu": "500m", "memory": "2Gi" } }],
+                    }],
+                }),
+            ),
+            (
+                "/api/v1/nodes/node-a/proxy/stats/summary",
+                json!({
+                    "pods": [{
+                        "volume": [
+                            { "usedBytes": 75u64 << 30, "pvcRef": { "name": "cache-0", "namespace": "megadb" } },
+                            { "usedBytes": 1024, "pvcRef": { "name": "cache-0", "namespace": "other" } },
+                        ],
+                    }],
+                }),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_parse_quantity() {
+        assert_eq!(parse_quantity("250m"), Some(0.25));
+        assert_eq!(parse_quantity("2"), Some(2.0));
+        assert_eq!(parse_quantity("512Mi"), Some(512.0 * 1024.0 * 1024.0));
+        assert_eq!(parse_quantity("2G"), Some(2e9));
+        assert_eq!(parse_quantity("1e3"), Some(1000.0));
+        assert!((parse_quantity("123456789n").unwrap() - 0.123456789).abs() < 1e-12);
+        assert_eq!(parse_quantity("lots"), None);
+    }
+
+    #[tokio::test]
+    async fn test_cluster_status_from_fake_api() {
+        let k8s = K8sClient::with_client(fake_client(fixtures()), "megadb", "megadb");
+        let status = k8s.cluster_status().await.unwrap();
+
+        assert_eq!(status.phase, "Degraded");
+        assert_eq!((status.ready_replicas, status.total_replicas), (1, 2));
+        assert_eq!(status.coordinator_endpoint, "10.0.0.5:5433");
+
+        let worker = status
+            .pods
+            .iter()
+            .find(|p| p.name == "megadb-worker-0")
+            .unwrap();
+        assert_eq!(worker.role, "worker");
+        assert_eq!(worker.restart_count, 2);
+        assert_eq!(worker.cpu_usage_percent, 25.0);
+        assert_eq!(worker.memory_bytes, 2 << 30);
+        assert_eq!(worker.memory_limit_bytes, 8 << 30);
+        assert_eq!(worker.disk_usage_percent, 75.0);
+        assert!(worker.age_seconds > 0);
+
+        // Only claims mounted by cluster pods; the WAL is mounted by both
+        assert_eq!(status.volumes.len(), 2);
+        let wal = status.volumes.iter().find(|v| v.name == "wal").unwrap();
+        assert_eq!(wal.bound_pod, "shared");
+        assert_eq!(wal.capacity_bytes, 50 << 30);
+        assert!(!wal.expandable);
+        let cache = status.volumes.iter().find(|v| v.name == "cache-0").unwrap();
+        assert_eq!(cache.bound_pod, "megadb-worker-0");
+        assert_eq!(cache.used_bytes, 75 << 30);
+        assert_eq!(cache.storage_class, "gp3");
+        assert!(cache.expandable);
+    }
+
+    #[tokio::test]
+    async fn test_volumes() {
+        let (client, requests) = fake_api(fixtures());
+        let k8s = K8sClient::with_client(client, "megadb", "megadb");
+        let volumes = k8s.volumes().await.unwrap();
+
+        let mut names: Vec<&str> = volumes.iter().map(|v| v.name.as_str()).collect();
+        names.sort_unstable();
+        assert_eq!(names, ["cache-0", "wal"]);
+        let cache = volumes.iter().find(|v| v.name == "cache-0").unwrap();
+        assert_eq!(cache.bound_pod, "megadb-worker-0");
+        assert_eq!(cache.used_bytes, 75 << 30);
+        assert!(cache.expandable);
+
+        // Skips the workload and pod metrics lookups
+        let requests = requests.lock().unwrap();
+        assert!(requests
+            .iter()
+            .all(|r| !r.contains("statefulsets") && !r.contains("metrics.k8s.io")));
+    }
+
+    #[tokio::test]
+    async fn test_cluster_status_without_metrics() {
+        let fixtures = fixtures()
+            .into_iter()
+            .filter(|(path, _)| !path.contains("metrics.k8s.io") && !path.contains("/nodes/"))
+            .collect();
+        let k8s = K8sClient::with_client(fake_client(fixtures), "megadb", "megadb");
+        let status = k8s.cluster_status().await.unwrap();
+
+        assert_eq!(status.pods.len(), 2);
+        assert!(status.pods.iter().all(|p| p.cpu_usage_percent == 0.0));
+        assert!(status.volumes.iter().all(|v| v.used_bytes == 0));
+    }
+
+    #[tokio::test]
+    async fn test_cluster_status_missing_statefulset() {
+        let fixtures = fixtures()
+            .into_iter()
+            .filter(|(path, _)| !path.contains("statefulsets"))
+            .collect();
+        let k8s = K8sClient::with_client(fake_client(fixtures), "megadb", "megadb");
+        let status = k8s.cluster_status().await.unwrap();
+
+        assert_eq!(status.phase, "NotFound");
+        assert_eq!(status.total_replicas, 0);
+    }
+
+    fn scale(spec: i32, status: i32) -> serde_json::Value {
+        json!({
+            "metadata": { "name": "megadb-worker" },
+            "spec": { "replicas": spec },
+            "status": { "replicas": status },
+        })
+    }
+
+    #[tokio::test]
+    async fn test_scale_patches_statefulset_and_emits_event() {
+        let fixtures = vec![
+            (
+                "GET /apis/apps/v1/namespaces/megadb/statefulsets/megadb-worker/scale",
+                scale(2, 2),
+            ),
+            (
+                "PATCH /apis/apps/v1/namespaces/megadb/statefulsets/megadb-worker/scale",
+                scale(4, 2),
+            ),
+        ];
+        let k8s = K8sClient::with_client(fake_client(fixtures), "megadb", "megadb");
+        let mut events = k8s.subscribe_events();
+
+        let resp = k8s.scale(&ScaleRequest { replicas: 4 }).await.unwrap();
+        assert_eq!(
+            (resp.previous_replicas, resp.replicas, resp.current_replicas),
+            (2, 4, 2)
+        );
+
+        let event = events.try_recv().unwrap();
+        assert_eq!(event.event_type, "SCALED");
+        assert_eq!(event.pod_name, "megadb-worker");
+        assert_eq!(event.object_kind, "StatefulSet");
+        assert_eq!(k8s.events_since(0), [event]);
+    }
+
+    #[tokio::test]
+    async fn test_scale_rejects_out_of_bounds() {
+        let k8s = K8sClient::with_client(fake_client(vec![]), "megadb", "megadb").with_scaling(
+            KedaConfig {
+                enabled: false,
+                min_replicas: 1,
+                max_replicas: 4,
+            },
+        );
+        let err = k8s.scale(&ScaleRequest { replicas: 5 }).await.unwrap_err();
+        assert!(matches!(err, K8sError::Invalid(_)));
+        assert_eq!(err.status_code(), 400);
+    }
+
+    fn megadb_resource() -> serde_json::Value {
+        json!({
+            "apiVersion": "megadb.io/v1alpha1",
+            "kind": "MegaDB",
+            "metadata": { "name": "megadb", "namespace": "megadb" },
+            "spec": { "replicas": 2, "version": "0.9", "storage": { "storage_type": "s3" } },
+            "status": {
+                "phase": "Scaling",
+                "ready_replicas": 1,
+                "conditions": [{
+                    "type": "Ready",
+                    "status": "False",
+                    "reason": "WorkersStarting",
+                    "message": "1/2 workers ready",
+                    "lastTransitionTime": "2026-01-01T00:00:00Z",
+                }],
+            },
+        })
+    }
+
+    #[tokio::test]
+    async fn test_cluster_status_uses_megadb_conditions() {
+        let mut fixtures = fixtures();
+        fixtures.push((
+            "/apis/megadb.io/v1alpha1/namespaces/megadb/megadbs/megadb",
+            megadb_resource(),
+        ));
+        let k8s = K8sClient::with_client(fake_client(fixtures), "megadb", "megadb");
+        let status = k8s.cluster_status().await.unwrap();
+
+        assert_eq!(status.phase, "Scaling");
+        assert_eq!(status.conditions.len(), 1);
+        assert_eq!(status.conditions[0].condition_type, "Ready");
+        assert_eq!(status.conditions[0].reason, "WorkersStarting");
+    }
+
+    #[tokio::test]
+    async fn test_apply_megadb_dry_run() {
+        let (client, requests) = fake_api(vec![(
+            "PATCH /apis/megadb.io/v1alpha1/namespaces/megadb/megadbs/megadb",
+            megadb_resource(),
+        )]);
+        let k8s = K8sClient::with_client(client, "megadb", "megadb");
+        let mut events = k8s.subscribe_events();
+        let megadb: MegaDB = serde_json::from_value(megadb_resource()).unwrap();
+
+        let applied = k8s.apply_megadb(&megadb, true).await.unwrap();
+        assert_eq!(applied.summary().phase, "Scaling");
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].contains("dryRun=All"));
+        assert!(requests[0].contains("fieldManager=megafactory-sql"));
+        // Dry runs change nothing, so nothing is announced
+        assert!(events.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_megadb_config() {
+        let scaled_object = json!({
+            "apiVersion": "keda.sh/v1alpha1",
+            "kind": "ScaledObject",
+            "metadata": { "name": "megadb-worker" },
+            "spec": {
+                "scaleTargetRef": { "name": "megadb-worker" },
+                "minReplicaCount": 1,
+                "maxReplicaCount": 6,
+                "triggers": [],
+            },
+        });
+        let client = fake_client(vec![
+            (
+                "/apis/megadb.io/v1alpha1/namespaces/megadb/megadbs/megadb",
+                megadb_resource(),
+            ),
+            (
+                "/apis/keda.sh/v1alpha1/namespaces/megadb/scaledobjects/megadb-worker",
+                scaled_object,
+            ),
+        ]);
+        let k8s = K8sClient::with_client(client, "megadb", "megadb");
+
+        let config = k8s.megadb_config("megadb").await.unwrap();
+        assert_eq!(config.cluster_name, "megadb");
+        assert_eq!(config.cpu_worker_pool.replicas, 2);
+        assert!(config.keda_enabled);
+        assert_eq!(
+            (
+                config.cpu_worker_pool.min_replicas,
+                config.cpu_worker_pool.max_replicas
+            ),
+            (1, 6)
+        );
+        assert_eq!(config.preserved.version.as_deref(), Some("0.9"));
+
+        let err = k8s.megadb_config("other").await.unwrap_err();
+        assert!(matches!(err, K8sError::NotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn test_pod_logs_streams_lines() {
+        let (client, requests) = fake_api(vec![
+            (
+                "/api/v1/namespaces/megadb/pods/megadb-worker-0",
+                pod("megadb-worker-0", "worker", "node-a", &[]),
+            ),
+            (
+                "/api/v1/namespaces/megadb/pods/megadb-worker-0/log",
+                json!("INFO started\nWARN slow scan\n"),
+            ),
+        ]);
+        let k8s = K8sClient::with_client(client, "megadb", "megadb");
+        let query = LogQuery {
+            tail_lines: Some(100),
+            previous: true,
+            ..LogQuery::default()
+        };
+        let lines: Vec<String> = k8s
+            .pod_logs("megadb-worker-0", &query)
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(lines, ["INFO started", "WARN slow scan"]);
+
+        let requests = requests.lock().unwrap();
+        assert!(requests[1].contains("tailLines=100"));
+        assert!(requests[1].contains("previous=true"));
+    }
+
+    #[tokio::test]
+    async fn test_pod_logs_refuses_other_pods() {
+        let mut other = pod("grafana-0", "worker", "node-a", &[]);
+        other["metadata"]["labels"][INSTANCE_LABEL] = json!("grafana");
+        let client = fake_client(vec![("/api/v1/namespaces/megadb/pods/grafana-0", other)]);
+        let k8s = K8sClient::with_client(client, "megadb", "megadb");
+
+        let err = k8s
+            .pod_logs("grafana-0", &LogQuery::default())
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.status_code(), 404);
+    }
+
+    #[tokio::test]
+    async fn test_apply_megadb_refuses_other_clusters() {
+        let (client, requests) = fake_api(vec![]);
+        let k8s = K8sClient::with_client(client, "megadb", "megadb");
+        let mut megadb: MegaDB = serde_json::from_value(megadb_resource()).unwrap();
+        megadb.metadata.name = Some("analytics".into());
+
+        let err = k8s.apply_megadb(&megadb, false).await.err().unwrap();
+        assert_eq!(err.status_code(), 404);
+        assert!(requests.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_delete_megadb_refuses_other_clusters() {
+        let (client, requests) = fake_api(vec![]);
+        let k8s = K8sClient::with_client(client, "megadb", "megadb");
+
+        let err = k8s.delete_megadb("analytics").await.err().unwrap();
+        assert_eq!(err.status_code(), 404);
+        assert!(requests.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_delete_megadb_refuses_unlabelled() {
+        let (client, requests) = fake_api(vec![(
+            "GET /apis/megadb.io/v1alpha1/namespaces/megadb/megadbs/megadb",
+            megadb_resource(),
+        )]);
+        let k8s = K8sClient::with_client(client, "megadb", "megadb");
+
+        let err = k8s.delete_megadb("megadb").await.err().unwrap();
+        assert_eq!(err.status_code(), 404);
+        let requests = requests.lock().unwrap();
+        assert!(requests.iter().all(|r| !r.starts_with("DELETE")));
+    }
+
+    #[tokio::test]
+    async fn test_exec_refused_by_policy() {
+        let (client, requests) = fake_api(vec![]);
+        let k8s = K8sClient::with_client(client, "megadb", "megadb").with_exec(ExecPolicy {
+            enabled: true,
+            allowed_commands: vec!["megadb-admin".into()],
+        });
+
+        let err = k8s
+            .exec("megadb-worker-0", vec!["sh".into()], true)
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.status_code(), 403);
+        // Refused before any API call
+        assert!(requests.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rolling_restart_bumps_template_annotation() {
+        let (client, requests) = fake_api(vec![(
+            "PATCH /apis/apps/v1/namespaces/megadb/statefulsets/megadb-worker",
+            fixtures()[0].1.clone(),
+        )]);
+        let k8s = K8sClient::with_client(client, "megadb", "megadb");
+        let mut events = k8s.subscribe_events();
+
+        k8s.rolling_restart().await.unwrap();
+        assert_eq!(requests.lock().unwrap().len(), 1);
+        assert_eq!(events.try_recv().unwrap().event_type, "RESTARTED");
+    }
+
+    #[tokio::test]
+    async fn test_drain_refuses_coordinator() {
+        let client = fake_client(vec![(
+            "/api/v1/namespaces/megadb/pods/megadb-coordinator-0",
+            pod("megadb-coordinator-0", "coordinator", "node-a", &[]),
+        )]);
+        let k8s = K8sClient::with_client(client, "megadb", "megadb");
+        let megadb = MegaDbClient::new(reqwest::Client::new());
+
+        let err = k8s
+            .drain_worker(&megadb, "http://megadb.invalid", "megadb-coordinator-0")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, K8sError::Invalid(_)));
+    }
+
+    #[tokio::test]
+    async fn test_resize_volume() {
+        let mut fixtures = fixtures();
+        fixtures.extend([
+            (
+                "GET /api/v1/namespaces/megadb/persistentvolumeclaims/cache-0",
+                pvc("cache-0", "ReadWriteOnce", "gp3", "100Gi"),
+            ),
+            (
+                "PATCH /api/v1/namespaces/megadb/persistentvolumeclaims/cache-0",
+                pvc("cache-0", "ReadWriteOnce", "gp3", "150Gi"),
+            ),
+            (
+                "/api/v1/namespaces/megadb/persistentvolumeclaims/wal",
+                pvc("wal", "ReadWriteMany", "nfs", "50Gi"),
+            ),
+            (
+                "/apis/storage.k8s.io/v1/storageclasses/gp3",
+                json!({
+                    "metadata": { "name": "gp3" },
+                    "provisioner": "ebs.csi.aws.com",
+                    "allowVolumeExpansion": true,
+                }),
+            ),
+            (
+                "/apis/storage.k8s.io/v1/storageclasses/nfs",
+                json!({ "metadata": { "name": "nfs" }, "provisioner": "nfs" }),
+            ),
+        ]);
+        let (client, requests) = fake_api(fixtures);
+        let k8s = K8sClient::with_client(client, "megadb", "megadb");
+
+        let grow = VolumeResizeRequest { size_gib: 150 };
+        k8s.resize_volume("cache-0", &grow).await.unwrap();
+        {
+            let requests = requests.lock().unwrap();
+            assert!(requests.iter().any(
+                |r| r.starts_with("PATCH /api/v1/namespaces/megadb/persistentvolumeclaims/cache-0")
+            ));
+            // Only the claim and its class, not the whole cluster status
+            assert!(!requests.iter().any(|r| r.contains("/proxy/")
+                || r.contains("metrics.k8s.io")
+                || r.split('?').next().unwrap().ends_with("/storageclasses")));
+        }
+
+        // Not expandable, shrinking, and not the cluster's
+        let err = k8s.resize_volume("wal", &grow).await.unwrap_err();
+        assert!(matches!(err, K8sError::Invalid(_)));
+        let shrink = VolumeResizeRequest { size_gib: 50 };
+        let err = k8s.resize_volume("cache-0", &shrink).await.unwrap_err();
+        assert!(matches!(err, K8sError::Invalid(_)));
+        let err = k8s.resize_volume("unrelated", &grow).await.unwrap_err();
+        assert!(matches!(err, K8sError::NotFound(_)));
+    }
+
+    fn scaled_object(min: i32, max: i32) -> serde_json::Value {
+        json!({
+            "apiVersion": "keda.sh/v1alpha1",
+            "kind": "ScaledObject",
+            "metadata": { "name": "megadb-worker" },
+            "spec": {
+                "scaleTargetRef": { "name": "megadb-worker" },
+                "minReplicaCount": min,
+                "maxReplicaCount": max,
+                "triggers": [{
+                    "type": "prometheus",
+                    "name": "qps",
+                    "metadata": { "query": "sum(x)", "threshold": "100" },
+                }],
+            },
+        })
+    }
+
+    #[tokio::test]
+    async fn test_update_autoscaler() {
+        let (client, requests) = fake_api(vec![
+            (
+                "GET /apis/keda.sh/v1alpha1/namespaces/megadb/scaledobjects/megadb-worker",
+                scaled_object(1, 8),
+            ),
+            (
+                "PATCH /apis/keda.sh/v1alpha1/namespaces/megadb/scaledobjects/megadb-worker",
+                scaled_object(2, 16),
+            ),
+        ]);
+        let k8s = K8sClient::with_client(client, "megadb", "megadb");
+        let mut events = k8s.subscribe_events();
+
+        let status = k8s.autoscaler().await.unwrap().unwrap();
+        assert_eq!(status.triggers[0].trigger.name, "qps");
+        assert!(status.history.is_empty());
+
+        let update = AutoscalerUpdate {
+            min_replicas: 2,
+            max_replicas: 16,
+            thresholds: BTreeMap::from([("qps".to_string(), 150.0)]),
+        };
+        let status = k8s.update_autoscaler(&update).await.unwrap();
+        assert_eq!((status.min_replicas, status.max_replicas), (2, 16));
+        assert_eq!(events.try_recv().unwrap().event_type, "AUTOSCALER_UPDATED");
+        assert!(requests
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|r| r.starts_with("PATCH /apis/keda.sh")));
+
+        let unknown = AutoscalerUpdate {
+            thresholds: BTreeMap::from([("latency".to_string(), 5.0)]),
+            ..update
+        };
+        let err = k8s.update_autoscaler(&unknown).await.unwrap_err();
+        assert!(matches!(err, K8sError::Invalid(_)));
+    }
+
+    #[tokio::test]
+    async fn test_autoscaler_absent() {
+        let k8s = K8sClient::with_client(fake_client(vec![]), "megadb", "megadb");
+        assert!(k8s.autoscaler().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cluster_hardware() {
+        let (client, requests) = fake_api(vec![
+            (
+                "/api/v1/nodes",
+                json!({
+                    "metadata": {},
+                    "items": [{
+                        "metadata": { "name": "node-a" },
+                        "spec": { "providerID": "gce://proj/us-central1-a/node-a" },
+                        "status": { "allocatable": { "cpu": "8", "google.com/tpu": "4" } },
+                    }],
+                }),
+            ),
+            (
+                "/api/v1/pods",
+                json!({ "metadata": {}, "items": [pod("megadb-worker-0", "worker", "node-a", &[])] }),
+            ),
+        ]);
+        let k8s = K8sClient::with_client(client, "megadb", "megadb");
+
+        let status = k8s.cluster_hardware().await.unwrap();
+        assert_eq!(
+            status.detected_provider,
+            Some(megafactory_sql_types::configurator::CloudProvider::Gcp)
+        );
+        assert_eq!((status.total_tpus, status.tpu_available), (4, 4));
+        // Finished pods are filtered out server-side
+        let requests = requests.lock().unwrap();
+        assert!(
+            requests[1].contains("fieldSelector=status.phase"),
+            "{requests:?}"
+        );
+    }
+}
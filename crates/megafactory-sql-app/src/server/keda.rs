@@ -0,0 +1,360 @@
+//! Typed `keda.sh/v1alpha1` `ScaledObject`, limited to the fields the
+//! dashboard reads and writes.
+//!
+//! Prometheus triggers only: their `metadata` is the usual
+//! `serverAddress` / `query` / `threshold` string map.
+
+use std::collections::BTreeMap;
+
+use k8s_openapi::api::autoscaling::v2::HorizontalPodAutoscaler;
+use k8s_openapi::api::core::v1::Event;
+use kube::CustomResource;
+use megafactory_sql_types::configurator::DeploymentConfig;
+use megafactory_sql_types::k8s::{
+    AutoscalerStatus, ScalingEvent, ScalingTrigger, TriggerStatus, KEDA_PROMETHEUS_ADDRESS,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::crd::MegaDBCondition;
+use super::k8s::parse_quantity;
+
+/// Events on the HPA or ScaledObject kept as scaling history.
+const HISTORY_REASONS: [&str; 3] = [
+    "SuccessfulRescale",
+    "KEDAScaleTargetActivated",
+    "KEDAScaleTargetDeactivated",
+];
+
+#[derive(CustomResource, Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[kube(
+    group = "keda.sh",
+    version = "v1alpha1",
+    kind = "ScaledObject",
+    plural = "scaledobjects",
+    namespaced,
+    status = "ScaledObjectStatus"
+)]
+#[serde(rename_all = "camelCase")]
+pub struct ScaledObjectSpec {
+    pub scale_target_ref: ScaleTargetRef,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub polling_interval: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cooldown_period: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_replica_count: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_replica_count: Option<i32>,
+    pub triggers: Vec<ScaleTrigger>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScaleTargetRef {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ScaleTrigger {
+    #[serde(rename = "type")]
+    pub type_: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+}
+
+/// Status written by KEDA.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScaledObjectStatus {
+    /// The HPA KEDA manages for this object, usually `keda-hpa-{name}`.
+    #[serde(default)]
+    pub hpa_name: Option<String>,
+    #[serde(default)]
+    pub conditions: Vec<MegaDBCondition>,
+}
+
+impl From<&ScalingTrigger> for ScaleTrigger {
+    fn from(t: &ScalingTrigger) -> Self {
+        Self {
+            type_: "prometheus".into(),
+            name: Some(t.name.clone()),
+            metadata: BTreeMap::from([
+                ("serverAddress".into(), KEDA_PROMETHEUS_ADDRESS.into()),
+                ("query".into(), t.query.clone()),
+                ("threshold".into(), t.threshold.to_string()),
+            ]),
+        }
+    }
+}
+
+impl ScaleTrigger {
+    /// The trigger as the dashboard shows it; `None` for non-Prometheus
+    /// triggers. Unnamed triggers are named after their position.
+    pub fn to_scaling_trigger(&self, index: usize) -> Option<ScalingTrigger> {
+        if self.type_ != "prometheus" {
+            return None;
+        }
+        Some(ScalingTrigger {
+            name: self.name.clone().unwrap_or_else(|| format!("s{index}")),
+            query: self.metadata.get("query").cloned().unwrap_or_default(),
+            threshold: self
+                .metadata
+                .get("threshold")
+                .and_then(|t| t.parse().ok())
+                .unwrap_or(0.0),
+        })
+    }
+}
+
+impl ScaledObject {
+    /// The ScaledObject the configurator deploys next to the MegaDB resource,
+    /// or `None` when KEDA is disabled.
+    pub fn from_config(config: &DeploymentConfig) -> Option<Self> {
+        if !config.keda_enabled {
+            return None;
+        }
+        let name = format!("{}-worker", config.cluster_name);
+        let spec = ScaledObjectSpec {
+            scale_target_ref: ScaleTargetRef {
+                api_version: Some("apps/v1".into()),
+                kind: Some("StatefulSet".into()),
+                name: name.clone(),
+            },
+            polling_interval: None,
+            cooldown_period: None,
+            min_replica_count: Some(config.cpu_worker_pool.min_replicas),
+            max_replica_count: Some(config.cpu_worker_pool.max_replicas),
+            triggers: ScalingTrigger::defaults().iter().map(Into::into).collect(),
+        };
+        Some(Self::new(&name, spec))
+    }
+
+    /// Name of the HPA KEDA manages for this object.
+    pub fn hpa_name(&self) -> String {
+        self.status
+            .as_ref()
+            .and_then(|s| s.hpa_name.clone())
+            .unwrap_or_else(|| format!("keda-hpa-{}", self.metadata.name.as_deref().unwrap_or("")))
+    }
+
+    /// Combine the object with its HPA (current values, replicas) and the
+    /// events of both (history).
+    pub fn autoscaler_status(
+        &self,
+        hpa: Option<&HorizontalPodAutoscaler>,
+        events: &[Event],
+    ) -> AutoscalerStatus {
+        let hpa_status = hpa.and_then(|h| h.status.as_ref());
+        let metrics = hpa_status.and_then(|s| s.current_metrics.as_ref());
+        // KEDA names each trigger's external metric `s{index}-{type}`
+        let current_value = |index: usize| {
+            let prefix = format!("s{index}-");
+            metrics?
+                .iter()
+                .filter_map(|m| m.external.as_ref())
+                .find(|m| m.metric.name.starts_with(&prefix))
+                .and_then(|m| {
+                    m.current
+                        .average_value
+                        .as_ref()
+                        .or(m.current.value.as_ref())
+                })
+                .and_then(|q| parse_quantity(&q.0))
+        };
+        let triggers = self
+            .spec
+            .triggers
+            .iter()
+            .enumerate()
+            .filter_map(|(i, t)| {
+                Some(TriggerStatus {
+                    trigger: t.to_scaling_trigger(i)?,
+                    current_value: current_value(i),
+                })
+            })
+            .collect();
+
+        let name = self.metadata.name.clone().unwrap_or_default();
+        let hpa_name = self.hpa_name();
+        let mut history: Vec<ScalingEvent> = events
+            .iter()
+            .filter(|e| {
+                let object = e.involved_object.name.as_deref();
+                object == Some(name.as_str()) || object == Some(hpa_name.as_str())
+            })
+            .filter(|e| HISTORY_REASONS.contains(&e.reason.as_deref().unwrap_or("")))
+            .map(|e| {
+                let message = e.message.clone().unwrap_or_default();
+                ScalingEvent {
+                    time: e
+                        .last_timestamp
+                        .as_ref()
+                        .map(|t| t.0)
+                        .or(e.event_time.as_ref().map(|t| t.0)),
+                    replicas: rescale_size(&message),
+                    reason: e.reason.clone().unwrap_or_default(),
+                    message,
+                }
+            })
+            .collect();
+        history.sort_by_key(|e| std::cmp::Reverse(e.time));
+
+        let active = self.status.as_ref().is_some_and(|s| {
+            s.conditions
+                .iter()
+                .any(|c| c.type_ == "Active" && c.status == "True")
+        });
+
+        AutoscalerStatus {
+            name,
+            min_replicas: self.spec.min_replica_count.unwrap_or(0),
+            max_replicas: self.spec.max_replica_count.unwrap_or(100),
+            current_replicas: hpa_status.and_then(|s| s.current_replicas).unwrap_or(0),
+            desired_replicas: hpa_status.map(|s| s.desired_replicas).unwrap_or(0),
+            active,
+            triggers,
+            history,
+        }
+    }
+}
+
+/// Replica count from an HPA rescale message ("New size: 4; reason: ...").
+fn rescale_size(message: &str) -> Option<i32> {
+    let rest = message.strip_prefix("New size: ")?;
+    rest.split(';').next()?.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use megafactory_sql_types::configurator::{CloudProvider, StorageBackend, WorkerPoolConfig};
+    use serde_json::json;
+
+    #[test]
+    fn test_from_config() {
+        let config = DeploymentConfig {
+            cluster_name: "analytics".into(),
+            provider: CloudProvider::Aws,
+            cpu_worker_pool: WorkerPoolConfig {
+                instance_type: "m6i.4xlarge".into(),
+                replicas: 3,
+                min_replicas: 2,
+                max_replicas: 12,
+                accelerator: None,
+                node_selector: vec![],
+                tolerations: vec![],
+            },
+            gpu_worker_pool: None,
+            fpga_worker_pool: None,
+            npu_worker_pool: None,
+            storage_backend: StorageBackend::S3,
+            keda_enabled: true,
+            estimated_monthly_cost_usd: 0.0,
+            preserved: Default::default(),
+        };
+        let scaled_object = ScaledObject::from_config(&config).unwrap();
+        let json = serde_json::to_value(&scaled_object).unwrap();
+        assert_eq!(json["apiVersion"], "keda.sh/v1alpha1");
+        assert_eq!(json["metadata"]["name"], "analytics-worker");
+        assert_eq!(json["spec"]["scaleTargetRef"]["kind"], "StatefulSet");
+        assert_eq!(json["spec"]["minReplicaCount"], 2);
+        assert_eq!(json["spec"]["triggers"][0]["metadata"]["threshold"], "100");
+
+        assert_eq!(json["spec"]["maxReplicaCount"], 12);
+
+        let disabled = DeploymentConfig {
+            keda_enabled: false,
+            ..config
+        };
+        assert!(ScaledObject::from_config(&disabled).is_none());
+    }
+
+    #[test]
+    fn test_autoscaler_status() {
+        let scaled_object: ScaledObject = serde_json::from_value(json!({
+            "apiVersion": "keda.sh/v1alpha1",
+            "kind": "ScaledObject",
+            "metadata": { "name": "megadb-worker" },
+            "spec": {
+                "scaleTargetRef": { "name": "megadb-worker" },
+                "minReplicaCount": 1,
+                "maxReplicaCount": 8,
+                "triggers": [
+                    { "type": "prometheus", "name": "qps",
+                      "metadata": { "query": "sum(x)", "threshold": "100" } },
+                    { "type": "cpu", "metadata": { "value": "80" } },
+                ],
+            },
+            "status": {
+                "hpaName": "keda-hpa-megadb-worker",
+                "conditions": [{ "type": "Active", "status": "True" }],
+            },
+        }))
+        .unwrap();
+        let hpa: HorizontalPodAutoscaler = serde_json::from_value(json!({
+            "metadata": { "name": "keda-hpa-megadb-worker" },
+            "spec": { "maxReplicas": 8, "scaleTargetRef": { "kind": "StatefulSet", "name": "megadb-worker" } },
+            "status": {
+                "currentReplicas": 3,
+                "desiredReplicas": 4,
+                "currentMetrics": [{
+                    "type": "External",
+                    "external": {
+                        "metric": { "name": "s0-prometheus" },
+                        "current": { "averageValue": "125500m" },
+                    },
+                }],
+            },
+        }))
+        .unwrap();
+        let event = |name: &str, reason: &str, message: &str, time: &str| -> Event {
+            serde_json::from_value(json!({
+                "metadata": { "name": format!("{name}.1") },
+                "involvedObject": { "name": name },
+                "reason": reason,
+                "message": message,
+                "lastTimestamp": time,
+            }))
+            .unwrap()
+        };
+        let events = [
+            event(
+                "keda-hpa-megadb-worker",
+                "SuccessfulRescale",
+                "New size: 4; reason: external metric s0-prometheus above target",
+                "2026-01-01T00:05:00Z",
+            ),
+            event(
+                "megadb-worker",
+                "KEDAScaleTargetActivated",
+                "Scaled statefulset megadb/megadb-worker from 0 to 1",
+                "2026-01-01T00:00:00Z",
+            ),
+            event(
+                "megadb-worker-0",
+                "Pulled",
+                "Image pulled",
+                "2026-01-01T00:06:00Z",
+            ),
+        ];
+
+        let status = scaled_object.autoscaler_status(Some(&hpa), &events);
+        assert_eq!((status.min_replicas, status.max_replicas), (1, 8));
+        assert_eq!((status.current_replicas, status.desired_replicas), (3, 4));
+        assert!(status.active);
+        // The CPU trigger isn't a Prometheus trigger
+        assert_eq!(status.triggers.len(), 1);
+        assert_eq!(status.triggers[0].trigger.threshold, 100.0);
+        assert_eq!(status.triggers[0].current_value, Some(125.5));
+        assert_eq!(status.history.len(), 2);
+        assert_eq!(status.history[0].replicas, Some(4));
+        assert_eq!(status.history[1].reason, "KEDAScaleTargetActivated");
+    }
+}
@@ -0,0 +1,299 @@
+//! YAML manifests of a deployment config: the `MegaDB` resource and, with
+//! KEDA enabled, the `ScaledObject` for its workers, as one multi-document
+//! YAML. Both are serialized from the typed resources the dashboard
+//! deploys, and parse back into the same `DeploymentConfig`.
+
+use megafactory_sql_types::configurator::DeploymentConfig;
+use serde::Deserialize;
+
+use super::crd::MegaDB;
+use super::keda::ScaledObject;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ManifestError {
+    #[error("Invalid YAML: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+    #[error("No MegaDB resource in the manifest")]
+    MissingMegaDb,
+    #[error("More than one MegaDB resource in the manifest")]
+    MultipleMegaDb,
+    /// The resource parsed but can't be turned into a config.
+    #[error("{0}")]
+    Invalid(String),
+}
+
+/// Render `config` as the YAML the configurator shows and deploys.
+pub fn to_yaml(config: &DeploymentConfig) -> Result<String, ManifestError> {
+    let mut yaml = serde_yaml::to_string(&MegaDB::from_config(config))?;
+    if let Some(scaled_object) = ScaledObject::from_config(config) {
+        yaml.push_str("---\n");
+        yaml.push_str(&serde_yaml::to_string(&scaled_object)?);
+    }
+    Ok(yaml)
+}
+
+/// Parse a manifest holding one MegaDB resource, and optionally the
+/// ScaledObject for its workers, into a deployment config. Documents of
+/// other kinds are ignored.
+pub fn from_yaml(yaml: &str) -> Result<DeploymentConfig, ManifestError> {
+    let mut megadb = None;
+    let mut scaled_objects = vec![];
+    for document in serde_yaml::Deserializer::from_str(yaml) {
+        let value = serde_yaml::Value::deserialize(document)?;
+        match value.get("kind").and_then(serde_yaml::Value::as_str) {
+            Some("MegaDB") if megadb.is_some() => return Err(ManifestError::MultipleMegaDb),
+            Some("MegaDB") => megadb = Some(serde_yaml::from_value::<MegaDB>(value)?),
+            Some("ScaledObject") => scaled_objects.push(serde_yaml::from_value(value)?),
+            _ => {}
+        }
+    }
+    let megadb = megadb.ok_or(ManifestError::MissingMegaDb)?;
+    let worker = format!("{}-worker", megadb.metadata.name.as_deref().unwrap_or(""));
+    let scaled_object = scaled_objects
+        .iter()
+        .find(|so: &&ScaledObject| so.spec.scale_target_ref.name == worker);
+    megadb.to_config(scaled_object)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use megafactory_sql_types::configurator::{
+        AcceleratorType, CloudProvider, PoolAccelerator, StorageBackend, TolerationConfig,
+        WorkerPoolConfig,
+    };
+
+    fn pool(
+        instance_type: &str,
+        replicas: i32,
+        accelerator: Option<PoolAccelerator>,
+    ) -> WorkerPoolConfig {
+        let tolerations = accelerator
+            .iter()
+            .map(|a| TolerationConfig {
+                key: a.k8s_resource_name.clone(),
+                operator: "Exists".into(),
+                value: None,
+                effect: "NoSchedule".into(),
+            })
+            .collect();
+        WorkerPoolConfig {
+            instance_type: instance_type.into(),
+            replicas,
+            min_replicas: 1,
+            max_replicas: 8,
+            accelerator,
+            node_selector: vec![("megadb.io/node-pool".into(), "pool".into())],
+            tolerations,
+        }
+    }
+
+    fn accelerator(
+        accelerator_type: AcceleratorType,
+        resource: &str,
+        count: u32,
+        device: &str,
+    ) -> Option<PoolAccelerator> {
+        Some(PoolAccelerator {
+            accelerator_type,
+            k8s_resource_name: resource.into(),
+            count_per_pod: count,
+            device_name: device.into(),
+            mig_profile: None,
+        })
+    }
+
+    fn config() -> DeploymentConfig {
+        DeploymentConfig {
+            cluster_name: "megadb-prod".into(),
+            provider: CloudProvider::Aws,
+            cpu_worker_pool: WorkerPoolConfig {
+                min_replicas: 2,
+                max_replicas: 10,
+                node_selector: vec![],
+                ..pool("m6i.4xlarge", 3, None)
+            },
+            gpu_worker_pool: Some(pool(
+                "g6.12xlarge",
+                2,
+                accelerator(AcceleratorType::Gpu, "nvidia.com/gpu", 4, "NVIDIA L4 24GB"),
+            )),
+            fpga_worker_pool: Some(pool(
+                "f1.4xlarge",
+                1,
+                accelerator(
+                    AcceleratorType::Fpga,
+                    "xilinx.com/fpga-u250",
+                    2,
+                    "Xilinx U250",
+                ),
+            )),
+            npu_worker_pool: Some(pool(
+                "inf2.8xlarge",
+                2,
+                accelerator(
+                    AcceleratorType::Npu,
+                    "aws.amazon.com/neuron",
+                    1,
+                    "AWS Neuron",
+                ),
+            )),
+            storage_backend: StorageBackend::S3,
+            keda_enabled: true,
+            estimated_monthly_cost_usd: 0.0,
+            preserved: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_manifest_yaml() {
+        let yaml = to_yaml(&config()).unwrap();
+        assert!(yaml.starts_with("apiVersion: megadb.io/v1alpha1\nkind: MegaDB\n"));
+        assert!(yaml.contains("  name: megadb-prod\n"));
+        assert!(yaml.contains("  replicas: 3\n"));
+        assert!(yaml.contains("    storage_type: s3\n"));
+        assert!(yaml.contains("  gpu_workers:\n"));
+        assert!(yaml.contains("product: NVIDIA L4 24GB"));
+        assert!(yaml.contains("  fpga_workers:\n"));
+        assert!(yaml.contains("resource_name: xilinx.com/fpga-u250"));
+        assert!(yaml.contains("  npu_workers:\n"));
+        assert!(yaml.contains("resource_name: aws.amazon.com/neuron"));
+
+        // KEDA settings become a ScaledObject document
+        assert!(yaml.contains("---\napiVersion: keda.sh/v1alpha1\nkind: ScaledObject\n"));
+        assert!(yaml.contains("    name: megadb-prod-worker\n"));
+        assert!(yaml.contains("  minReplicaCount: 2\n"));
+        assert!(yaml.contains("  maxReplicaCount: 10\n"));
+        assert!(yaml.contains("type: prometheus"));
+        assert!(yaml.contains("query: sum(rate(megadb_queries_total[1m]))"));
+        assert!(yaml.contains("threshold: '100'"));
+
+        let disabled = DeploymentConfig {
+            keda_enabled: false,
+            ..config()
+        };
+        assert!(!to_yaml(&disabled).unwrap().contains("ScaledObject"));
+    }
+
+    #[test]
+    fn test_manifest_roundtrip() {
+        let mut config = config();
+        // Values that would break hand-built YAML
+        config.cluster_name = "prod: \"a\"\n- b".into();
+        config.npu_worker_pool.as_mut().unwrap().tolerations[0].value = Some("x: 'y'".into());
+
+        let yaml = to_yaml(&config).unwrap();
+        let parsed = from_yaml(&yaml).unwrap();
+        assert_eq!(
+            serde_json::to_value(&parsed).unwrap(),
+            serde_json::to_value(&config).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_manifest_without_annotation() {
+        let yaml = r#"
+apiVersion: v1
+kind: Namespace
+metadata:
+  name: analytics
+---
+apiVersion: megadb.io/v1alpha1
+kind: MegaDB
+metadata:
+  name: analytics
+spec:
+  replicas: 4
+  storage:
+    storage_type: gcs
+  accelerators:
+    fpga:
+      resource_name: xilinx.com/fpga-u250
+      count: 1
+"#;
+        let config = from_yaml(yaml).unwrap();
+        assert_eq!(config.cluster_name, "analytics");
+        assert_eq!(config.provider, CloudProvider::Gcp);
+        assert_eq!(config.storage_backend, StorageBackend::Gcs);
+        assert_eq!(config.cpu_worker_pool.replicas, 4);
+        assert_eq!(config.cpu_worker_pool.instance_type, "");
+        assert!(!config.keda_enabled);
+        let fpga = config.fpga_worker_pool.unwrap();
+        assert_eq!(fpga.replicas, 4);
+        assert_eq!(
+            fpga.accelerator.unwrap().k8s_resource_name,
+            "xilinx.com/fpga-u250"
+        );
+    }
+
+    #[test]
+    fn test_manifest_preserves_unknown_fields() {
+        let yaml = r#"
+apiVersion: megadb.io/v1alpha1
+kind: MegaDB
+metadata:
+  name: analytics
+  labels:
+    team: data
+  annotations:
+    argocd.argoproj.io/sync-wave: "2"
+spec:
+  replicas: 3
+  version: 1.4.0
+  backup:
+    schedule: "0 3 * * *"
+  storage:
+    storage_type: s3
+  gpu_workers:
+    replicas: 2
+    priority_class: high
+    accelerators:
+      gpu:
+        count: 4
+        product: NVIDIA L4 24GB
+"#;
+        let config = from_yaml(yaml).unwrap();
+        assert_eq!(config.preserved.version.as_deref(), Some("1.4.0"));
+        assert_eq!(config.preserved.labels["team"], "data");
+        assert_eq!(config.preserved.spec["backup"]["schedule"], "0 3 * * *");
+        assert_eq!(
+            config.preserved.worker_groups["gpu_workers"]["priority_class"],
+            "high"
+        );
+
+        let mut edited = config.clone();
+        edited.gpu_worker_pool.as_mut().unwrap().replicas = 4;
+        let yaml = to_yaml(&edited).unwrap();
+        assert!(yaml.contains("  version: 1.4.0\n"));
+        assert!(yaml.contains("    team: data\n"));
+        assert!(yaml.contains("argocd.argoproj.io/sync-wave: '2'"));
+        assert!(yaml.contains("    schedule: 0 3 * * *\n"));
+        assert!(yaml.contains("    priority_class: high\n"));
+        assert!(yaml.contains("    replicas: 4\n"));
+
+        let reparsed = from_yaml(&yaml).unwrap();
+        assert_eq!(reparsed.preserved, config.preserved);
+    }
+
+    #[test]
+    fn test_manifest_errors() {
+        assert!(matches!(
+            from_yaml("apiVersion: v1\nkind: Namespace\nmetadata:\n  name: x\n"),
+            Err(ManifestError::MissingMegaDb)
+        ));
+        assert!(matches!(
+            from_yaml("kind: MegaDB\nspec: [\n"),
+            Err(ManifestError::Yaml(_))
+        ));
+        let yaml = to_yaml(&config()).unwrap();
+        assert!(matches!(
+            from_yaml(&format!("{yaml}---\n{yaml}")),
+            Err(ManifestError::MultipleMegaDb)
+        ));
+        let bad_storage = yaml.replace("storage_type: s3", "storage_type: ftp");
+        assert!(matches!(
+            from_yaml(&bad_storage),
+            Err(ManifestError::Invalid(_))
+        ));
+    }
+}
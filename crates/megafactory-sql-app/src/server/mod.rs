@@ -0,0 +1,34 @@
+//! Server-side services (SSR only).
+//!
+//! Shared by the `#[server]` functions in `pages/` and the axum proxy routes
+//! in `megafactory-sql-server`, so both talk to MegaDB and Kubernetes the
+//! same way.
+
+pub mod catalog;
+pub mod crd;
+pub mod events;
+pub mod export;
+pub mod hardware;
+pub mod k8s;
+pub mod keda;
+pub mod manifest;
+pub mod megadb;
+
+use leptos::prelude::*;
+
+pub use catalog::CatalogStore;
+pub use k8s::{K8sClient, K8sError};
+pub use megadb::{MegaDbClient, MegaDbError, ProgressWatch};
+
+/// Server-side state provided to server functions through Leptos context.
+#[derive(Debug, Clone)]
+pub struct ServerContext {
+    pub megadb: MegaDbClient,
+    pub k8s: K8sClient,
+    pub catalog: CatalogStore,
+}
+
+/// Get the [`ServerContext`] from inside a server function.
+pub fn server_context() -> Result<ServerContext, ServerFnError> {
+    use_context::<ServerContext>().ok_or_else(|| ServerFnError::new("Server context not provided"))
+}
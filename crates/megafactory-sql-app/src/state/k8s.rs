@@ -1,5 +1,9 @@
 use leptos::prelude::*;
 use megafactory_sql_types::k8s::ClusterStatus;
+use megafactory_sql_types::ws::K8sEvent;
+
+/// Live events kept for the dashboard; older ones are dropped.
+const MAX_RECENT_EVENTS: usize = 500;
 
 /// Global Kubernetes cluster state.
 #[derive(Debug, Clone)]
@@ -8,6 +12,9 @@ pub struct K8sState {
     pub is_connected: bool,
     pub auto_refresh: bool,
     pub refresh_interval_secs: u32,
+    /// Most recent `K8sEvent`s from the WebSocket and the server's event
+    /// log, newest first.
+    pub recent_events: Vec<K8sEvent>,
 }
 
 impl Default for K8sState {
@@ -17,10 +24,40 @@ impl Default for K8sState {
             is_connected: false,
             auto_refresh: true,
             refresh_interval_secs: 5,
+            recent_events: Vec::new(),
         }
     }
 }
 
+impl K8sState {
+    /// Add an event in id order, replacing an earlier copy of it (same id,
+    /// or same UID for a core/v1 Event that Kubernetes has updated).
+    pub fn push_event(&mut self, event: K8sEvent) {
+        self.recent_events.retain(|e| {
+            let same_id = event.id != 0 && e.id == event.id;
+            let same_uid = event.uid.is_some() && e.uid == event.uid;
+            !same_id && !same_uid
+        });
+        // Events from servers without an event log have no id; treat as newest
+        let index = if event.id == 0 {
+            0
+        } else {
+            self.recent_events
+                .iter()
+                .position(|e| e.id < event.id)
+                .unwrap_or(self.recent_events.len())
+        };
+        self.recent_events.insert(index, event);
+        self.recent_events.truncate(MAX_RECENT_EVENTS);
+    }
+
+    /// Id of the newest event received, for catching up from the server's
+    /// event log after a reconnect.
+    pub fn last_event_id(&self) -> u64 {
+        self.recent_events.iter().map(|e| e.id).max().unwrap_or(0)
+    }
+}
+
 pub fn provide_k8s_state() {
     let state = signal(K8sState::default());
     provide_context(state);
@@ -1,5 +1,6 @@
 pub mod connection;
 pub mod k8s;
+pub mod monitoring;
 pub mod query;
 pub mod schema;
 pub mod settings;
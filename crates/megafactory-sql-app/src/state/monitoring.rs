@@ -0,0 +1,18 @@
+use leptos::prelude::*;
+use megafactory_sql_types::metrics::QueryMetrics;
+
+/// Live monitoring state, fed by `MetricsUpdate` messages on the WebSocket.
+#[derive(Debug, Clone, Default)]
+pub struct MonitoringState {
+    /// Most recent metrics pushed by the server; `None` until the first update.
+    pub live_metrics: Option<QueryMetrics>,
+}
+
+pub fn provide_monitoring_state() {
+    let state = signal(MonitoringState::default());
+    provide_context(state);
+}
+
+pub fn use_monitoring_state() -> (ReadSignal<MonitoringState>, WriteSignal<MonitoringState>) {
+    expect_context::<(ReadSignal<MonitoringState>, WriteSignal<MonitoringState>)>()
+}
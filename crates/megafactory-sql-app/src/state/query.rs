@@ -1,5 +1,6 @@
 use leptos::prelude::*;
 use megafactory_sql_types::query::{QueryHistoryEntry, QueryResult};
+use megafactory_sql_types::ws::QueryProgressEvent;
 
 const MAX_HISTORY: usize = 100;
 
@@ -11,6 +12,10 @@ pub struct QueryTab {
     pub sql: String,
     pub result: Option<QueryResult>,
     pub is_running: bool,
+    /// Id of the execution in flight; results from any other execution are stale.
+    pub query_id: Option<uuid::Uuid>,
+    /// Latest server-reported progress of the running execution.
+    pub progress: Option<QueryProgressEvent>,
 }
 
 impl QueryTab {
@@ -21,6 +26,8 @@ impl QueryTab {
             sql: String::new(),
             result: None,
             is_running: false,
+            query_id: None,
+            progress: None,
         }
     }
 
@@ -65,6 +72,22 @@ impl QueryState {
         &mut self.tabs[self.active_tab_index]
     }
 
+    /// Tab running execution `query_id`; `None` once it finished, was
+    /// cancelled, or the tab was closed.
+    pub fn running_tab(&self, query_id: uuid::Uuid) -> Option<&QueryTab> {
+        self.tabs.iter().find(|t| t.query_id == Some(query_id))
+    }
+
+    /// Ids of all executions in flight across tabs.
+    pub fn running_query_ids(&self) -> Vec<uuid::Uuid> {
+        self.tabs.iter().filter_map(|t| t.query_id).collect()
+    }
+
+    /// Mutable variant of [`Self::running_tab`].
+    pub fn running_tab_mut(&mut self, query_id: uuid::Uuid) -> Option<&mut QueryTab> {
+        self.tabs.iter_mut().find(|t| t.query_id == Some(query_id))
+    }
+
     pub fn add_tab(&mut self) {
         let num = self.tabs.len() + 1;
         self.tabs
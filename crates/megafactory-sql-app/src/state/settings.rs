@@ -8,6 +8,16 @@ pub struct AppSettings {
     pub font_size: u32,
     pub row_limit: u64,
     pub autocomplete: bool,
+    /// Serve mock query results instead of contacting MegaDB.
+    #[serde(default)]
+    pub demo_mode: bool,
+    /// Volume usage (%) above which the dashboard offers to expand a PVC.
+    #[serde(default = "default_volume_warn_percent")]
+    pub volume_warn_percent: u32,
+}
+
+fn default_volume_warn_percent() -> u32 {
+    70
 }
 
 impl Default for AppSettings {
@@ -17,6 +27,8 @@ impl Default for AppSettings {
             font_size: 14,
             row_limit: 1000,
             autocomplete: true,
+            demo_mode: false,
+            volume_warn_percent: default_volume_warn_percent(),
         }
     }
 }
@@ -12,85 +12,88 @@ pub enum WsConnectionStatus {
     },
 }
 
-/// Provide WebSocket manager context. On WASM, connects to the server's /ws endpoint.
+/// Provide WebSocket manager context. On WASM, connects to the server's /ws
+/// endpoint and dispatches server messages into app state: `MetricsUpdate` to
+/// `MonitoringState`, `K8sEvent` to `K8sState` and `QueryProgress` to the tab
+/// running that query. Each (re)connect also loads the cluster events logged
+/// since the last one received.
+///
+/// Must be called after the query, k8s and monitoring states are provided.
 pub fn provide_websocket() {
     let status = signal(WsConnectionStatus::default());
     provide_context(status);
 
     #[cfg(target_arch = "wasm32")]
     {
-        use wasm_bindgen::prelude::*;
-        use wasm_bindgen::JsCast;
+        use crate::ws_client::{WsClient, WsEvent};
+        use megafactory_sql_types::ws::{query_topic, WsServerMessage, K8S_TOPIC, METRICS_TOPIC};
+        use std::collections::HashSet;
 
         let (_, set_status) = status;
+        let (query_state, set_query_state) = crate::state::query::use_query_state();
+        let (k8s_state, set_k8s_state) = crate::state::k8s::use_k8s_state();
+        let (_, set_monitoring_state) = crate::state::monitoring::use_monitoring_state();
 
-        Effect::new(move || {
-            let window = match web_sys::window() {
-                Some(w) => w,
-                None => return,
-            };
-            let location = window.location();
-            let protocol = location.protocol().unwrap_or_else(|_| "http:".into());
-            let host = location.host().unwrap_or_else(|_| "localhost:3000".into());
-            let ws_protocol = if protocol == "https:" { "wss:" } else { "ws:" };
-            let url = format!("{ws_protocol}//{host}/ws");
-
-            set_status.set(WsConnectionStatus::Connecting);
-
-            let ws = match web_sys::WebSocket::new(&url) {
-                Ok(ws) => ws,
-                Err(_) => {
-                    set_status.set(WsConnectionStatus::Disconnected);
+        let on_event = move |event: WsEvent| match event {
+            WsEvent::Connecting => set_status.set(WsConnectionStatus::Connecting),
+            WsEvent::Connected => {
+                set_status.set(WsConnectionStatus::Connected);
+                // Fetch the cluster events missed while disconnected
+                let since = k8s_state.with_untracked(|s| s.last_event_id());
+                leptos::task::spawn_local(async move {
+                    match crate::pages::k8s_dashboard::get_k8s_events(since).await {
+                        Ok(events) => set_k8s_state.update(|s| {
+                            for event in events {
+                                s.push_event(event);
+                            }
+                        }),
+                        Err(e) => web_sys::console::warn_1(
+                            &format!("Failed to load cluster events: {e}").into(),
+                        ),
+                    }
+                });
+            }
+            WsEvent::Reconnecting { attempt } => {
+                set_status.set(WsConnectionStatus::Reconnecting { attempt })
+            }
+            WsEvent::Message(WsServerMessage::MetricsUpdate(metrics)) => {
+                set_monitoring_state.update(|s| s.live_metrics = Some(metrics));
+            }
+            WsEvent::Message(WsServerMessage::K8sEvent(event)) => {
+                set_k8s_state.update(|s| s.push_event(event));
+            }
+            WsEvent::Message(WsServerMessage::QueryProgress(progress)) => {
+                let Ok(query_id) = uuid::Uuid::parse_str(&progress.query_id) else {
                     return;
-                }
-            };
-
-            // On open
-            let set_status_open = set_status;
-            let on_open = Closure::<dyn Fn()>::new(move || {
-                set_status_open.set(WsConnectionStatus::Connected);
-            });
-            ws.set_onopen(Some(on_open.as_ref().unchecked_ref()));
-            on_open.forget();
-
-            // On close — schedule reconnect
-            let set_status_close = set_status;
-            let on_close = Closure::<dyn Fn()>::new(move || {
-                set_status_close.set(WsConnectionStatus::Disconnected);
-            });
-            ws.set_onclose(Some(on_close.as_ref().unchecked_ref()));
-            on_close.forget();
-
-            // On error
-            let on_error = Closure::<dyn Fn()>::new(move || {
-                // Errors are followed by close events
-            });
-            ws.set_onerror(Some(on_error.as_ref().unchecked_ref()));
-            on_error.forget();
-
-            // On message — parse and dispatch
-            let on_message =
-                Closure::<dyn Fn(web_sys::MessageEvent)>::new(move |ev: web_sys::MessageEvent| {
-                    if let Some(text) = ev.data().as_string() {
-                        // Parse WsServerMessage and dispatch to state
-                        if let Ok(_msg) = serde_json::from_str::<
-                            megafactory_sql_types::ws::WsServerMessage,
-                        >(&text)
-                        {
-                            // Future: dispatch metrics/k8s/query updates to state
-                        }
+                };
+                set_query_state.update(|s| {
+                    if let Some(tab) = s.running_tab_mut(query_id) {
+                        tab.progress = Some(progress);
                     }
                 });
-            ws.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
-            on_message.forget();
+            }
+            WsEvent::Message(WsServerMessage::Error { message }) => {
+                web_sys::console::warn_1(&format!("WebSocket error: {message}").into());
+            }
+            WsEvent::Message(WsServerMessage::Pong) => {}
+        };
 
-            // Send subscribe message
-            let subscribe =
-                serde_json::to_string(&megafactory_sql_types::ws::WsClientMessage::Subscribe {
-                    topics: vec!["metrics".into(), "k8s".into()],
-                })
-                .unwrap_or_default();
-            let _ = ws.send_with_str(&subscribe);
+        Effect::new(move || {
+            let Some(client) = WsClient::connect(on_event) else {
+                return;
+            };
+            client.subscribe([METRICS_TOPIC.to_string(), K8S_TOPIC.to_string()]);
+
+            // Follow running queries: subscribe to each one's progress topic
+            // while it runs. The client resubscribes them after a reconnect.
+            Effect::new(move |prev: Option<HashSet<uuid::Uuid>>| {
+                let running: HashSet<uuid::Uuid> =
+                    query_state.with(|s| s.running_query_ids().into_iter().collect());
+                let prev = prev.unwrap_or_default();
+                client.subscribe(running.difference(&prev).map(query_topic));
+                client.unsubscribe(prev.difference(&running).map(query_topic));
+                running
+            });
         });
     }
 }
@@ -0,0 +1,229 @@
+//! Reconnecting client for the server's `/ws` endpoint.
+//!
+//! Keeps the set of subscribed topics, reconnects with exponential backoff,
+//! sends `Ping` heartbeats and resubscribes every topic after a reconnect.
+//! `state::websocket` wires its events into app state.
+
+use megafactory_sql_types::ws::WsServerMessage;
+
+pub const WS_PATH: &str = "/ws";
+
+/// Lifecycle and message events reported to the client's handler.
+#[derive(Debug, Clone)]
+pub enum WsEvent {
+    /// Opening the first socket.
+    Connecting,
+    Connected,
+    /// The socket closed; reconnect `attempt` (1-based) is waiting out its backoff.
+    Reconnecting {
+        attempt: u32,
+    },
+    /// A server message other than `Pong`.
+    Message(WsServerMessage),
+}
+
+/// Delay before reconnect attempt `attempt` (1-based): doubles from 1s, capped at 30s.
+pub fn backoff_delay_ms(attempt: u32) -> u32 {
+    const INITIAL_MS: u32 = 1_000;
+    const MAX_MS: u32 = 30_000;
+    INITIAL_MS
+        .saturating_mul(1 << attempt.saturating_sub(1).min(16))
+        .min(MAX_MS)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub use client::WsClient;
+
+#[cfg(target_arch = "wasm32")]
+mod client {
+    use std::cell::{Cell, RefCell};
+    use std::collections::BTreeSet;
+    use std::rc::Rc;
+
+    use gloo_timers::callback::{Interval, Timeout};
+    use megafactory_sql_types::ws::{WsClientMessage, WsServerMessage};
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen::JsCast;
+    use web_sys::{MessageEvent, WebSocket};
+
+    use super::{backoff_delay_ms, WsEvent, WS_PATH};
+
+    /// How often `Ping` is sent on an open socket.
+    const HEARTBEAT_INTERVAL_MS: u32 = 15_000;
+    /// A socket with no `Pong` for this long is closed and reopened.
+    const HEARTBEAT_TIMEOUT_MS: f64 = 45_000.0;
+
+    /// Handle to the shared connection; clones refer to the same socket.
+    #[derive(Clone)]
+    pub struct WsClient {
+        inner: Rc<Inner>,
+    }
+
+    struct Inner {
+        url: String,
+        socket: RefCell<Option<WebSocket>>,
+        /// Reconnect attempts since the last successful open.
+        attempt: Cell<u32>,
+        last_pong: Cell<f64>,
+        topics: RefCell<BTreeSet<String>>,
+        on_event: Box<dyn Fn(WsEvent)>,
+    }
+
+    impl WsClient {
+        /// Connect to `/ws` on the page's host and start the heartbeat.
+        /// Returns `None` outside a browser window.
+        pub fn connect(on_event: impl Fn(WsEvent) + 'static) -> Option<Self> {
+            let location = web_sys::window()?.location();
+            let protocol = location.protocol().unwrap_or_else(|_| "http:".into());
+            let host = location.host().unwrap_or_else(|_| "localhost:3000".into());
+            let ws_protocol = if protocol == "https:" { "wss:" } else { "ws:" };
+
+            let client = Self {
+                inner: Rc::new(Inner {
+                    url: format!("{ws_protocol}//{host}{WS_PATH}"),
+                    socket: RefCell::new(None),
+                    attempt: Cell::new(0),
+                    last_pong: Cell::new(0.0),
+                    topics: RefCell::new(BTreeSet::new()),
+                    on_event: Box::new(on_event),
+                }),
+            };
+            client.open();
+
+            let heartbeat = client.clone();
+            Interval::new(HEARTBEAT_INTERVAL_MS, move || heartbeat.heartbeat()).forget();
+            Some(client)
+        }
+
+        /// Add topics; they are sent now if connected and again after every reconnect.
+        pub fn subscribe(&self, topics: impl IntoIterator<Item = String>) {
+            let added: Vec<String> = {
+                let mut current = self.inner.topics.borrow_mut();
+                topics
+                    .into_iter()
+                    .filter(|t| current.insert(t.clone()))
+                    .collect()
+            };
+            if !added.is_empty() {
+                self.send(&WsClientMessage::Subscribe { topics: added });
+            }
+        }
+
+        pub fn unsubscribe(&self, topics: impl IntoIterator<Item = String>) {
+            let removed: Vec<String> = {
+                let mut current = self.inner.topics.borrow_mut();
+                topics.into_iter().filter(|t| current.remove(t)).collect()
+            };
+            if !removed.is_empty() {
+                self.send(&WsClientMessage::Unsubscribe { topics: removed });
+            }
+        }
+
+        /// Send on the current socket; dropped while not connected.
+        fn send(&self, msg: &WsClientMessage) {
+            let socket = self.inner.socket.borrow();
+            let Some(ws) = socket
+                .as_ref()
+                .filter(|ws| ws.ready_state() == WebSocket::OPEN)
+            else {
+                return;
+            };
+            if let Ok(json) = serde_json::to_string(msg) {
+                let _ = ws.send_with_str(&json);
+            }
+        }
+
+        fn open(&self) {
+            if self.inner.attempt.get() == 0 {
+                (self.inner.on_event)(WsEvent::Connecting);
+            }
+            let ws = match WebSocket::new(&self.inner.url) {
+                Ok(ws) => ws,
+                Err(_) => {
+                    self.schedule_reconnect();
+                    return;
+                }
+            };
+
+            let client = self.clone();
+            let on_open = Closure::<dyn Fn()>::new(move || {
+                client.inner.attempt.set(0);
+                client.inner.last_pong.set(js_sys::Date::now());
+                (client.inner.on_event)(WsEvent::Connected);
+                let topics: Vec<String> = client.inner.topics.borrow().iter().cloned().collect();
+                if !topics.is_empty() {
+                    client.send(&WsClientMessage::Subscribe { topics });
+                }
+            });
+            ws.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+            on_open.forget();
+
+            // Errors are followed by close events, which drive the reconnect.
+            let client = self.clone();
+            let on_close = Closure::<dyn Fn()>::new(move || {
+                client.inner.socket.borrow_mut().take();
+                client.schedule_reconnect();
+            });
+            ws.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+            on_close.forget();
+
+            let client = self.clone();
+            let on_message = Closure::<dyn Fn(MessageEvent)>::new(move |ev: MessageEvent| {
+                let Some(text) = ev.data().as_string() else {
+                    return;
+                };
+                match serde_json::from_str::<WsServerMessage>(&text) {
+                    Ok(WsServerMessage::Pong) => client.inner.last_pong.set(js_sys::Date::now()),
+                    Ok(msg) => (client.inner.on_event)(WsEvent::Message(msg)),
+                    Err(e) => web_sys::console::warn_1(
+                        &format!("Unrecognised WebSocket message: {e}").into(),
+                    ),
+                }
+            });
+            ws.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+            on_message.forget();
+
+            self.inner.socket.replace(Some(ws));
+        }
+
+        fn schedule_reconnect(&self) {
+            let attempt = self.inner.attempt.get() + 1;
+            self.inner.attempt.set(attempt);
+            (self.inner.on_event)(WsEvent::Reconnecting { attempt });
+            let client = self.clone();
+            Timeout::new(backoff_delay_ms(attempt), move || client.open()).forget();
+        }
+
+        /// Ping an open socket, or close it if the server stopped answering.
+        fn heartbeat(&self) {
+            let stale = js_sys::Date::now() - self.inner.last_pong.get() > HEARTBEAT_TIMEOUT_MS;
+            if stale {
+                if let Some(ws) = self.inner.socket.borrow().as_ref() {
+                    if ws.ready_state() == WebSocket::OPEN {
+                        let _ = ws.close();
+                    }
+                }
+                return;
+            }
+            self.send(&WsClientMessage::Ping);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_delay_doubles_to_cap() {
+        let delays: Vec<u32> = (1..=7).map(backoff_delay_ms).collect();
+        assert_eq!(delays, [1_000, 2_000, 4_000, 8_000, 16_000, 30_000, 30_000]);
+    }
+
+    #[test]
+    fn test_backoff_delay_large_attempt() {
+        assert_eq!(backoff_delay_ms(0), 1_000);
+        assert_eq!(backoff_delay_ms(33), 30_000);
+        assert_eq!(backoff_delay_ms(u32::MAX), 30_000);
+    }
+}
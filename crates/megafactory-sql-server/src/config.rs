@@ -12,8 +12,65 @@ pub struct AppConfig {
     pub prometheus_url: String,
     /// Kubernetes namespace (default: default)
     pub k8s_namespace: String,
+    /// Serve mock live feeds instead of Prometheus/Kubernetes (MEGAFACTORY_DEMO=1)
+    pub demo: bool,
+    /// Browser origins besides the dashboard's own that may open exec
+    /// sessions (MEGAFACTORY_ALLOWED_ORIGINS, comma-separated)
+    pub allowed_origins: Vec<String>,
     /// Shared HTTP client for proxy requests
     pub client: reqwest::Client,
+    /// MegaDB client shared with server functions (wraps `client`)
+    pub megadb: megafactory_sql_app::server::MegaDbClient,
+    /// Kubernetes client for the MegaDB cluster, shared with server functions
+    pub k8s: megafactory_sql_app::server::K8sClient,
+    /// Instance catalogs, with overrides from MEGAFACTORY_CATALOG_DIR
+    pub catalog: megafactory_sql_app::server::CatalogStore,
+}
+
+/// Worker replica bounds for manual scaling (MEGADB_MIN_REPLICAS,
+/// MEGADB_MAX_REPLICAS, MEGADB_KEDA_ENABLED).
+fn scaling_from_env() -> megafactory_sql_types::k8s::KedaConfig {
+    let defaults = megafactory_sql_types::k8s::KedaConfig::default();
+    let replicas = |var: &str, default: i32| {
+        std::env::var(var)
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(default)
+    };
+    megafactory_sql_types::k8s::KedaConfig {
+        enabled: std::env::var("MEGADB_KEDA_ENABLED")
+            .map(|v| matches!(v.as_str(), "1" | "true" | "yes"))
+            .unwrap_or(defaults.enabled),
+        min_replicas: replicas("MEGADB_MIN_REPLICAS", defaults.min_replicas),
+        max_replicas: replicas("MEGADB_MAX_REPLICAS", defaults.max_replicas),
+    }
+}
+
+/// Pod exec from the dashboard (MEGAFACTORY_EXEC_ENABLED, and
+/// MEGAFACTORY_EXEC_COMMANDS as a comma-separated allowlist; `*` allows any).
+fn exec_from_env() -> megafactory_sql_types::exec::ExecPolicy {
+    megafactory_sql_types::exec::ExecPolicy {
+        enabled: std::env::var("MEGAFACTORY_EXEC_ENABLED")
+            .map(|v| matches!(v.as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false),
+        allowed_commands: std::env::var("MEGAFACTORY_EXEC_COMMANDS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .map(String::from)
+            .collect(),
+    }
+}
+
+/// MegaDB endpoints the dashboard's connections may point at: `MEGADB_URL`
+/// plus MEGADB_ALLOWED_URLS, a comma-separated list of base URLs.
+fn megadb_urls_from_env(megadb_url: &str) -> Vec<String> {
+    let extra = std::env::var("MEGADB_ALLOWED_URLS").unwrap_or_default();
+    std::iter::once(megadb_url)
+        .chain(extra.split(',').map(str::trim).filter(|u| !u.is_empty()))
+        .map(String::from)
+        .collect()
 }
 
 impl AppConfig {
@@ -23,18 +80,53 @@ impl AppConfig {
             .pool_max_idle_per_host(10)
             .build()
             .expect("Failed to build HTTP client");
+        let megadb_url =
+            std::env::var("MEGADB_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
+        let k8s_namespace =
+            std::env::var("K8S_NAMESPACE").unwrap_or_else(|_| "default".to_string());
+        // MegaDB cluster name, matched against `app.kubernetes.io/instance`
+        let megadb_cluster =
+            std::env::var("MEGADB_CLUSTER").unwrap_or_else(|_| "megadb".to_string());
 
         Self {
             bind_address: std::env::var("MEGAFACTORY_BIND")
                 .unwrap_or_else(|_| "0.0.0.0:3000".to_string()),
-            megadb_url: std::env::var("MEGADB_URL")
-                .unwrap_or_else(|_| "http://localhost:8080".to_string()),
+            megadb_url: megadb_url.clone(),
             megadb_ws_url: std::env::var("MEGADB_WS_URL")
                 .unwrap_or_else(|_| "ws://localhost:8080/ws".to_string()),
             prometheus_url: std::env::var("PROMETHEUS_URL")
                 .unwrap_or_else(|_| "http://localhost:9090".to_string()),
-            k8s_namespace: std::env::var("K8S_NAMESPACE").unwrap_or_else(|_| "default".to_string()),
+            k8s_namespace: k8s_namespace.clone(),
+            demo: std::env::var("MEGAFACTORY_DEMO")
+                .map(|v| matches!(v.as_str(), "1" | "true" | "yes"))
+                .unwrap_or(false),
+            allowed_origins: std::env::var("MEGAFACTORY_ALLOWED_ORIGINS")
+                .unwrap_or_default()
+                .split(',')
+                .map(|o| o.trim().trim_end_matches('/'))
+                .filter(|o| !o.is_empty())
+                .map(String::from)
+                .collect(),
+            megadb: megafactory_sql_app::server::MegaDbClient::new(client.clone())
+                .with_allowed_urls(megadb_urls_from_env(&megadb_url)),
+            k8s: megafactory_sql_app::server::K8sClient::new(k8s_namespace, megadb_cluster)
+                .with_scaling(scaling_from_env())
+                .with_exec(exec_from_env()),
+            catalog: megafactory_sql_app::server::CatalogStore::new(
+                std::env::var_os("MEGAFACTORY_CATALOG_DIR")
+                    .filter(|dir| !dir.is_empty())
+                    .map(Into::into),
+            ),
             client,
         }
     }
+
+    /// Server-side state handed to Leptos server functions.
+    pub fn server_context(&self) -> megafactory_sql_app::server::ServerContext {
+        megafactory_sql_app::server::ServerContext {
+            megadb: self.megadb.clone(),
+            k8s: self.k8s.clone(),
+            catalog: self.catalog.clone(),
+        }
+    }
 }
@@ -0,0 +1,388 @@
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Extension, Path,
+    },
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
+use futures::SinkExt;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Status;
+use kube::api::TerminalSize;
+use megafactory_sql_app::server::K8sClient;
+use megafactory_sql_types::exec::{ExecClientMessage, ExecServerMessage};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+
+use crate::config::AppConfig;
+
+/// Bytes read from the process per output message.
+const OUTPUT_BUFFER: usize = 4096;
+
+/// How long a new session may take to send its `Start` message.
+const START_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// `GET /proxy/k8s/pods/{name}/exec`: upgrade to a WebSocket bridged to a
+/// process in the pod. The first client message is `Start` with the command;
+/// the policy is checked again then, against the actual command.
+pub async fn exec_upgrade_handler(
+    ws: WebSocketUpgrade,
+    headers: HeaderMap,
+    Extension(config): Extension<AppConfig>,
+    Path(pod): Path<String>,
+) -> Response {
+    // Refuse before upgrading so a disabled server answers with a plain 403.
+    if !config.k8s.exec_policy().enabled {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({"error": "Exec into pods is disabled on this server"})),
+        )
+            .into_response();
+    }
+    // CORS doesn't apply to WebSocket upgrades, so without this any page the
+    // user has open could start a session.
+    if !origin_allowed(&headers, &config.allowed_origins) {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({"error": "Exec is only available from the dashboard"})),
+        )
+            .into_response();
+    }
+    let k8s = config.k8s.clone();
+    ws.on_upgrade(move |socket| exec_session(socket, k8s, pod))
+}
+
+/// Whether the upgrade comes from the dashboard: its `Origin` names the host
+/// the request was sent to, or one of `allowed`. Browsers always send an
+/// `Origin` on WebSocket upgrades, so requests without one are refused too.
+fn origin_allowed(headers: &HeaderMap, allowed: &[String]) -> bool {
+    let Some(origin) = headers.get(header::ORIGIN).and_then(|o| o.to_str().ok()) else {
+        return false;
+    };
+    if allowed.iter().any(|a| a.eq_ignore_ascii_case(origin)) {
+        return true;
+    }
+    // `null` and other opaque origins have no host to compare
+    let Some((_, authority)) = origin.split_once("://") else {
+        return false;
+    };
+    headers
+        .get(header::HOST)
+        .and_then(|h| h.to_str().ok())
+        .is_some_and(|host| host.eq_ignore_ascii_case(authority))
+}
+
+async fn exec_session(mut socket: WebSocket, k8s: K8sClient, pod: String) {
+    let start = tokio::time::timeout(START_TIMEOUT, recv(&mut socket)).await;
+    let (command, tty, size) = match start {
+        Ok(Some(ExecClientMessage::Start {
+            command,
+            tty,
+            cols,
+            rows,
+        })) => (command, tty, (cols, rows)),
+        _ => {
+            let message = "Expected a Start message".to_string();
+            send(&mut socket, &ExecServerMessage::Error { message }).await;
+            return;
+        }
+    };
+
+    let mut process = match k8s.exec(&pod, command, tty).await {
+        Ok(process) => process,
+        Err(e) => {
+            let message = e.to_string();
+            send(&mut socket, &ExecServerMessage::Error { message }).await;
+            return;
+        }
+    };
+    if !send(&mut socket, &ExecServerMessage::Started).await {
+        process.abort();
+        return;
+    }
+
+    let mut stdin = process.stdin();
+    let mut stdout = process.stdout();
+    let mut stderr = process.stderr();
+    let mut resize = process.terminal_size();
+    if let Some(tx) = resize.as_mut() {
+        let _ = tx.send(terminal_size(size)).await;
+    }
+    let Some(status) = process.take_status() else {
+        process.abort();
+        return;
+    };
+    let mut status = std::pin::pin!(status);
+    let mut exit: Option<Option<Status>> = None;
+    let (mut out_buf, mut err_buf) = ([0u8; OUTPUT_BUFFER], [0u8; OUTPUT_BUFFER]);
+    let (mut out_text, mut err_text) = (Utf8Decoder::default(), Utf8Decoder::default());
+
+    // Run until the process has exited and its output is drained, or the
+    // browser goes away.
+    while exit.is_none() || stdout.is_some() || stderr.is_some() {
+        let msg = tokio::select! {
+            n = read_some(&mut stdout, &mut out_buf) => {
+                let data = match n {
+                    Some(n) => out_text.decode(&out_buf[..n]),
+                    None => {
+                        stdout = None;
+                        out_text.finish()
+                    }
+                };
+                if data.is_empty() {
+                    continue;
+                }
+                ExecServerMessage::Stdout { data }
+            }
+            n = read_some(&mut stderr, &mut err_buf) => {
+                let data = match n {
+                    Some(n) => err_text.decode(&err_buf[..n]),
+                    None => {
+                        stderr = None;
+                        err_text.finish()
+                    }
+                };
+                if data.is_empty() {
+                    continue;
+                }
+                ExecServerMessage::Stderr { data }
+            }
+            s = &mut status, if exit.is_none() => {
+                exit = Some(s);
+                continue;
+            }
+            incoming = recv(&mut socket) => {
+                match incoming {
+                    Some(ExecClientMessage::Stdin { data }) => {
+                        if let Some(w) = stdin.as_mut() {
+                            if w.write_all(data.as_bytes()).await.is_err() {
+                                stdin = None;
+                            }
+                        }
+                    }
+                    Some(ExecClientMessage::Resize { cols, rows }) => {
+                        if let Some(tx) = resize.as_mut() {
+                            let _ = tx.send(terminal_size((cols, rows))).await;
+                        }
+                    }
+                    Some(ExecClientMessage::Start { .. }) => {}
+                    None => {
+                        process.abort();
+                        return;
+                    }
+                }
+                continue;
+            }
+        };
+        if !send(&mut socket, &msg).await {
+            process.abort();
+            return;
+        }
+    }
+
+    let (code, message) = exit_status(exit.flatten());
+    send(&mut socket, &ExecServerMessage::Exit { code, message }).await;
+    let _ = socket.send(Message::Close(None)).await;
+}
+
+/// Read from an output stream; `None` at EOF or on error. Never resolves
+/// once the stream is gone, so `select!` stops polling it.
+async fn read_some(stream: &mut Option<impl AsyncRead + Unpin>, buf: &mut [u8]) -> Option<usize> {
+    match stream {
+        Some(stream) => match stream.read(buf).await {
+            Ok(0) | Err(_) => None,
+            Ok(n) => Some(n),
+        },
+        None => std::future::pending().await,
+    }
+}
+
+/// Decodes an output stream as UTF-8 across reads: a character split
+/// between two reads is held back until the rest of it arrives.
+#[derive(Default)]
+struct Utf8Decoder {
+    pending: Vec<u8>,
+}
+
+impl Utf8Decoder {
+    /// Text of `bytes` and whatever was held back before them.
+    fn decode(&mut self, bytes: &[u8]) -> String {
+        self.pending.extend_from_slice(bytes);
+        let complete = self.pending.len() - incomplete_tail(&self.pending);
+        let text = String::from_utf8_lossy(&self.pending[..complete]).into_owned();
+        self.pending.drain(..complete);
+        text
+    }
+
+    /// What is still held back at EOF; invalid, so replaced.
+    fn finish(&mut self) -> String {
+        let text = String::from_utf8_lossy(&self.pending).into_owned();
+        self.pending.clear();
+        text
+    }
+}
+
+/// Length of a multi-byte character cut off at the end of `bytes`.
+fn incomplete_tail(bytes: &[u8]) -> usize {
+    for len in 1..=bytes.len().min(3) {
+        let byte = bytes[bytes.len() - len];
+        // Continuation bytes are 0b10xx_xxxx; look back to the lead byte
+        if byte & 0xC0 == 0x80 {
+            continue;
+        }
+        let width = match byte {
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF7 => 4,
+            _ => 1,
+        };
+        return if width > len { len } else { 0 };
+    }
+    0
+}
+
+/// Next client message; `None` once the socket closes. Frames that don't
+/// parse are skipped.
+async fn recv(socket: &mut WebSocket) -> Option<ExecClientMessage> {
+    loop {
+        match socket.recv().await? {
+            Ok(Message::Text(text)) => match serde_json::from_str(&text) {
+                Ok(msg) => return Some(msg),
+                Err(e) => tracing::debug!("Invalid exec message: {e}"),
+            },
+            Ok(Message::Close(_)) | Err(_) => return None,
+            Ok(_) => {}
+        }
+    }
+}
+
+/// Send a message; false once the socket is gone.
+async fn send(socket: &mut WebSocket, msg: &ExecServerMessage) -> bool {
+    let json = serde_json::to_string(msg).unwrap_or_default();
+    socket.send(Message::Text(json.into())).await.is_ok()
+}
+
+fn terminal_size((cols, rows): (u16, u16)) -> TerminalSize {
+    TerminalSize {
+        width: cols,
+        height: rows,
+    }
+}
+
+/// Exit code and message from the exec status channel. Kubernetes reports
+/// non-zero exit codes as an `ExitCode` cause on a failed status.
+fn exit_status(status: Option<Status>) -> (Option<i32>, String) {
+    let Some(status) = status else {
+        return (None, "Process ended without a status".to_string());
+    };
+    if status.status.as_deref() == Some("Success") {
+        return (Some(0), "Process exited".to_string());
+    }
+    let code = status
+        .details
+        .as_ref()
+        .and_then(|d| d.causes.as_ref())
+        .into_iter()
+        .flatten()
+        .find(|c| c.reason.as_deref() == Some("ExitCode"))
+        .and_then(|c| c.message.as_deref()?.parse().ok());
+    (code, status.message.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(origin: Option<&str>, host: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::HOST, host.parse().unwrap());
+        if let Some(origin) = origin {
+            headers.insert(header::ORIGIN, origin.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_origin_same_host() {
+        let allowed = |origin| origin_allowed(&headers(origin, "dashboard.local:3000"), &[]);
+        assert!(allowed(Some("http://dashboard.local:3000")));
+        assert!(allowed(Some("https://Dashboard.Local:3000")));
+        assert!(!allowed(Some("http://evil.example.com")));
+        assert!(!allowed(Some("http://dashboard.local:3001")));
+        assert!(!allowed(Some("null")));
+        assert!(!allowed(None));
+    }
+
+    #[test]
+    fn test_origin_allowlist() {
+        // Behind a proxy the Host header names the upstream, not the dashboard
+        let allowed = ["https://megafactory.example.com".to_string()];
+        let host = "megafactory-sql:3000";
+        assert!(origin_allowed(
+            &headers(Some("https://megafactory.example.com"), host),
+            &allowed
+        ));
+        assert!(!origin_allowed(
+            &headers(Some("https://megafactory.example.com.evil.io"), host),
+            &allowed
+        ));
+    }
+
+    /// Feed `bytes` to a decoder split at `at`, returning each call's text.
+    fn decode_split(bytes: &[u8], at: usize) -> (String, String) {
+        let mut decoder = Utf8Decoder::default();
+        let first = decoder.decode(&bytes[..at]);
+        let second = decoder.decode(&bytes[at..]);
+        assert!(decoder.finish().is_empty());
+        (first, second)
+    }
+
+    #[test]
+    fn test_utf8_decoder_two_byte_split() {
+        let bytes = "café!".as_bytes();
+        assert_eq!(decode_split(bytes, 4), ("caf".into(), "é!".into()));
+    }
+
+    #[test]
+    fn test_utf8_decoder_three_byte_split() {
+        let bytes = "1€".as_bytes();
+        for at in 2..4 {
+            assert_eq!(
+                decode_split(bytes, at),
+                ("1".into(), "€".into()),
+                "split at {at}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_utf8_decoder_four_byte_split() {
+        let bytes = "ok 😀".as_bytes();
+        for at in 4..7 {
+            assert_eq!(
+                decode_split(bytes, at),
+                ("ok ".into(), "😀".into()),
+                "split at {at}"
+            );
+        }
+        // A complete character at the end is not held back
+        assert_eq!(incomplete_tail(bytes), 0);
+    }
+
+    #[test]
+    fn test_utf8_decoder_invalid_lead_byte() {
+        let mut decoder = Utf8Decoder::default();
+        assert_eq!(decoder.decode(b"a\xFFb"), "a\u{FFFD}b");
+        // Not a lead byte, so there is nothing to wait for
+        assert_eq!(decoder.decode(b"c\xFF"), "c\u{FFFD}");
+        assert_eq!(decoder.decode(b"\x80"), "\u{FFFD}");
+        assert!(decoder.finish().is_empty());
+    }
+
+    #[test]
+    fn test_utf8_decoder_finish_truncated() {
+        let mut decoder = Utf8Decoder::default();
+        assert_eq!(decoder.decode(b"ab\xE2\x82"), "ab");
+        assert_eq!(decoder.finish(), "\u{FFFD}");
+        assert!(decoder.finish().is_empty());
+    }
+}
@@ -0,0 +1,476 @@
+//! Live producers for the WebSocket hub: Prometheus metrics and Kubernetes
+//! pod/event/MegaDB watches. Mock producers in `websocket.rs` replace these in demo mode.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use futures::StreamExt;
+use k8s_openapi::api::core::v1::{Event, Pod};
+use kube::runtime::{watcher, WatchStreamExt};
+use megafactory_sql_app::server::crd::MegaDB;
+use megafactory_sql_app::server::K8sClient;
+use megafactory_sql_types::metrics::QueryMetrics;
+use megafactory_sql_types::ws::{EventSeverity, K8sEvent, WsServerMessage, METRICS_TOPIC};
+
+use crate::config::AppConfig;
+use crate::websocket::WsHub;
+
+/// How often PromQL is evaluated for `MetricsUpdate`.
+const METRICS_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// PromQL for each `QueryMetrics` field, in field order.
+const METRICS_QUERIES: [&str; 7] = [
+    "sum(rate(megadb_queries_total[1m]))",
+    "1000 * sum(rate(megadb_query_duration_seconds_sum[1m])) / sum(rate(megadb_query_duration_seconds_count[1m]))",
+    "1000 * histogram_quantile(0.50, sum(rate(megadb_query_duration_seconds_bucket[1m])) by (le))",
+    "1000 * histogram_quantile(0.95, sum(rate(megadb_query_duration_seconds_bucket[1m])) by (le))",
+    "1000 * histogram_quantile(0.99, sum(rate(megadb_query_duration_seconds_bucket[1m])) by (le))",
+    "sum(megadb_active_sessions)",
+    "sum(megadb_open_connections)",
+];
+
+/// Response of Prometheus `GET /api/v1/query`.
+#[derive(Debug, serde::Deserialize)]
+struct PromResponse {
+    data: PromData,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct PromData {
+    result: Vec<PromSample>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct PromSample {
+    /// `[unix_time, "value"]`
+    value: (f64, String),
+}
+
+impl PromResponse {
+    /// The first sample's value. An empty result (no series yet) or NaN reads as 0.
+    fn scalar(&self) -> f64 {
+        let value = self
+            .data
+            .result
+            .first()
+            .map(|s| s.value.1.parse::<f64>().unwrap_or(f64::NAN))
+            .unwrap_or(0.0);
+        if value.is_finite() {
+            value
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Evaluate an instant PromQL query expected to return at most one sample.
+async fn prom_scalar(config: &AppConfig, query: &str) -> Result<f64, String> {
+    let resp: PromResponse = config
+        .client
+        .get(format!("{}/api/v1/query", config.prometheus_url))
+        .query(&[("query", query)])
+        .timeout(std::time::Duration::from_secs(10))
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Prometheus unreachable: {e}"))?
+        .json()
+        .await
+        .map_err(|e| format!("Parse error: {e}"))?;
+    Ok(resp.scalar())
+}
+
+/// Evaluate the MegaDB PromQL set on an interval and publish `MetricsUpdate`.
+/// Nothing is published while Prometheus is failing.
+pub fn spawn_prometheus_metrics(hub: Arc<WsHub>, config: AppConfig) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(METRICS_INTERVAL);
+        let mut healthy = true;
+        loop {
+            interval.tick().await;
+            let values =
+                futures::future::join_all(METRICS_QUERIES.iter().map(|q| prom_scalar(&config, q)))
+                    .await
+                    .into_iter()
+                    .collect::<Result<Vec<f64>, String>>();
+
+            let values = match values {
+                Ok(values) => {
+                    if !healthy {
+                        tracing::info!("Prometheus metrics feed recovered");
+                        healthy = true;
+                    }
+                    values
+                }
+                Err(e) => {
+                    if healthy {
+                        tracing::warn!("Prometheus metrics feed failing: {e}");
+                        healthy = false;
+                    }
+                    continue;
+                }
+            };
+
+            let metrics = QueryMetrics {
+                queries_per_second: values[0],
+                avg_latency_ms: values[1],
+                p50_latency_ms: values[2],
+                p95_latency_ms: values[3],
+                p99_latency_ms: values[4],
+                active_sessions: values[5] as u32,
+                open_connections: values[6] as u32,
+            };
+            hub.publish(
+                METRICS_TOPIC,
+                None,
+                &WsServerMessage::MetricsUpdate(metrics),
+            );
+        }
+    });
+}
+
+/// Watch pods, core/v1 events and MegaDB resources in the cluster's
+/// namespace and record them in the `K8sClient` event log, which the hub
+/// relays to the `k8s` topic. Pod updates are only recorded when phase,
+/// readiness or restarts change, and MegaDB updates when the phase changes;
+/// existing core/v1 events are loaded into the log at startup.
+pub fn spawn_k8s_watch(k8s: K8sClient) {
+    tokio::spawn(async move {
+        let client = match k8s.client().await {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::warn!("Kubernetes not configured, K8s event feed disabled: {e}");
+                return;
+            }
+        };
+        let namespace = k8s.namespace();
+        let pods: kube::Api<Pod> = kube::Api::namespaced(client.clone(), namespace);
+        let events: kube::Api<Event> = kube::Api::namespaced(client.clone(), namespace);
+        let megadbs: kube::Api<MegaDB> = kube::Api::namespaced(client, namespace);
+
+        tokio::spawn(watch_pods(k8s.clone(), pods));
+        tokio::spawn(watch_events(k8s.clone(), events));
+        tokio::spawn(watch_megadbs(k8s, megadbs));
+    });
+}
+
+/// One-line summary of the pod fields worth announcing.
+fn pod_summary(pod: &Pod) -> String {
+    let status = pod.status.as_ref();
+    let phase = status
+        .and_then(|s| s.phase.clone())
+        .unwrap_or_else(|| "Unknown".into());
+    let containers = status
+        .and_then(|s| s.container_statuses.as_ref())
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let ready = containers.iter().filter(|c| c.ready).count();
+    let restarts: i32 = containers.iter().map(|c| c.restart_count).sum();
+    format!(
+        "{phase}, {ready}/{} ready, {restarts} restarts",
+        containers.len()
+    )
+}
+
+async fn watch_pods(k8s: K8sClient, pods: kube::Api<Pod>) {
+    let mut last_seen: HashMap<String, String> = HashMap::new();
+    // Pods of the (re)list in progress; replaces `last_seen` once it completes
+    let mut listed: HashMap<String, String> = HashMap::new();
+    let mut stream = watcher(pods, watcher::Config::default())
+        .default_backoff()
+        .boxed();
+
+    while let Some(event) = stream.next().await {
+        let (event_type, pod_name, message) = match event {
+            Ok(watcher::Event::Init) => {
+                listed.clear();
+                continue;
+            }
+            Ok(watcher::Event::InitApply(pod)) => {
+                if let Some(name) = pod.metadata.name.clone() {
+                    listed.insert(name, pod_summary(&pod));
+                }
+                continue;
+            }
+            // Pods deleted while the watch was down are missing from the relist
+            Ok(watcher::Event::InitDone) => {
+                let previous = std::mem::replace(&mut last_seen, std::mem::take(&mut listed));
+                for name in previous.into_keys() {
+                    if !last_seen.contains_key(&name) {
+                        k8s.record_event(K8sEvent::new("DELETED", "Pod", name, "Pod deleted"));
+                    }
+                }
+                continue;
+            }
+            Ok(watcher::Event::Apply(pod)) => {
+                let Some(name) = pod.metadata.name.clone() else {
+                    continue;
+                };
+                let summary = pod_summary(&pod);
+                match last_seen.insert(name.clone(), summary.clone()) {
+                    Some(prev) if prev == summary => continue,
+                    Some(_) => ("MODIFIED", name, summary),
+                    None => ("ADDED", name, summary),
+                }
+            }
+            Ok(watcher::Event::Delete(pod)) => {
+                let Some(name) = pod.metadata.name.clone() else {
+                    continue;
+                };
+                last_seen.remove(&name);
+                ("DELETED", name, "Pod deleted".to_string())
+            }
+            Err(e) => {
+                tracing::warn!("Pod watch error: {e}");
+                continue;
+            }
+        };
+
+        k8s.record_event(K8sEvent::new(event_type, "Pod", pod_name, message));
+    }
+}
+
+/// A core/v1 Event as a `K8sEvent`.
+fn core_event(ev: Event) -> K8sEvent {
+    let event_type = ev.type_.unwrap_or_else(|| "Normal".into());
+    let reason = ev.reason.unwrap_or_default();
+    let event_time = ev.event_time.map(|t| t.0);
+    let first_timestamp = ev.first_timestamp.map(|t| t.0).or(event_time);
+    K8sEvent {
+        id: 0,
+        severity: EventSeverity::classify(&event_type, &reason),
+        event_type,
+        pod_name: ev.involved_object.name.unwrap_or_default(),
+        message: ev.message.unwrap_or_default(),
+        reason,
+        object_kind: ev.involved_object.kind.unwrap_or_default(),
+        count: ev.count.unwrap_or(1),
+        first_timestamp,
+        last_timestamp: ev.last_timestamp.map(|t| t.0).or(first_timestamp),
+        uid: ev.metadata.uid,
+    }
+}
+
+async fn watch_events(k8s: K8sClient, events: kube::Api<Event>) {
+    // resourceVersion of each event already logged, by UID
+    let mut logged: HashMap<String, String> = HashMap::new();
+    // Events of the (re)list in progress; replaces `logged` once it completes
+    let mut listed: HashMap<String, String> = HashMap::new();
+    let mut stream = watcher(events, watcher::Config::default())
+        .default_backoff()
+        .boxed();
+
+    while let Some(event) = stream.next().await {
+        match event {
+            Ok(watcher::Event::Init) => listed.clear(),
+            // A relist after a watch restart returns every event again; only
+            // log those that are new or changed since.
+            Ok(watcher::Event::InitApply(ev)) => {
+                let (Some(uid), Some(version)) = (
+                    ev.metadata.uid.clone(),
+                    ev.metadata.resource_version.clone(),
+                ) else {
+                    continue;
+                };
+                if logged.get(&uid) != Some(&version) {
+                    k8s.record_event(core_event(ev));
+                }
+                listed.insert(uid, version);
+            }
+            Ok(watcher::Event::InitDone) => logged = std::mem::take(&mut listed),
+            // Updates share the UID of the event they bump, so the log
+            // keeps one entry per event.
+            Ok(watcher::Event::Apply(ev)) => {
+                if let (Some(uid), Some(version)) = (
+                    ev.metadata.uid.clone(),
+                    ev.metadata.resource_version.clone(),
+                ) {
+                    logged.insert(uid, version);
+                }
+                k8s.record_event(core_event(ev));
+            }
+            Ok(watcher::Event::Delete(ev)) => {
+                if let Some(uid) = &ev.metadata.uid {
+                    logged.remove(uid);
+                }
+            }
+            Err(e) => tracing::warn!("Event watch error: {e}"),
+        }
+    }
+}
+
+async fn watch_megadbs(k8s: K8sClient, megadbs: kube::Api<MegaDB>) {
+    let mut phases: HashMap<String, String> = HashMap::new();
+    let mut stream = watcher(megadbs, watcher::Config::default())
+        .default_backoff()
+        .boxed();
+
+    while let Some(event) = stream.next().await {
+        let db = match event {
+            Ok(watcher::Event::InitApply(db) | watcher::Event::Apply(db)) => db,
+            Ok(watcher::Event::Delete(db)) => {
+                let Some(name) = db.metadata.name else {
+                    continue;
+                };
+                phases.remove(&name);
+                k8s.record_event(K8sEvent::new("DELETED", "MegaDB", name, "MegaDB deleted"));
+                continue;
+            }
+            Ok(watcher::Event::Init | watcher::Event::InitDone) => continue,
+            // Without the MegaDB CRD installed this fails on every retry.
+            Err(e) => {
+                tracing::debug!("MegaDB watch error: {e}");
+                continue;
+            }
+        };
+        let Some(name) = db.metadata.name.clone() else {
+            continue;
+        };
+        let phase = db
+            .status
+            .and_then(|s| s.phase)
+            .unwrap_or_else(|| "Pending".into());
+        let Some(previous) = phases.insert(name.clone(), phase.clone()) else {
+            continue;
+        };
+        if previous == phase {
+            continue;
+        }
+        let severity = match phase.as_str() {
+            "Failed" => EventSeverity::Error,
+            "Degraded" => EventSeverity::Warning,
+            _ => EventSeverity::Info,
+        };
+        k8s.record_event(K8sEvent {
+            reason: "PhaseChanged".into(),
+            severity,
+            ..K8sEvent::new(
+                "MODIFIED",
+                "MegaDB",
+                name,
+                format!("Phase {previous} -> {phase}"),
+            )
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pod(status: serde_json::Value) -> Pod {
+        serde_json::from_value(serde_json::json!({
+            "metadata": { "name": "megadb-worker-0" },
+            "status": status,
+        }))
+        .unwrap()
+    }
+
+    fn container(ready: bool, restart_count: i32) -> serde_json::Value {
+        serde_json::json!({
+            "name": "megadb",
+            "image": "megadb:latest",
+            "imageID": "",
+            "ready": ready,
+            "restartCount": restart_count,
+        })
+    }
+
+    fn event(value: serde_json::Value) -> Event {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn prom(body: &str) -> f64 {
+        serde_json::from_str::<PromResponse>(body).unwrap().scalar()
+    }
+
+    #[test]
+    fn test_pod_summary() {
+        let pod = pod(serde_json::json!({
+            "phase": "Running",
+            "containerStatuses": [container(true, 2), container(false, 1)],
+        }));
+        assert_eq!(pod_summary(&pod), "Running, 1/2 ready, 3 restarts");
+    }
+
+    #[test]
+    fn test_pod_summary_without_status() {
+        let pod = pod(serde_json::json!({}));
+        assert_eq!(pod_summary(&pod), "Unknown, 0/0 ready, 0 restarts");
+    }
+
+    #[test]
+    fn test_core_event() {
+        let ev = core_event(event(serde_json::json!({
+            "metadata": { "name": "megadb-worker-0.1", "uid": "abc" },
+            "involvedObject": { "kind": "Pod", "name": "megadb-worker-0" },
+            "type": "Warning",
+            "reason": "BackOff",
+            "message": "Back-off restarting failed container",
+            "count": 4,
+            "firstTimestamp": "2026-02-01T10:00:00Z",
+            "lastTimestamp": "2026-02-01T10:05:00Z",
+        })));
+        assert_eq!(ev.severity, EventSeverity::Error);
+        assert_eq!(ev.event_type, "Warning");
+        assert_eq!(ev.reason, "BackOff");
+        assert_eq!(ev.object_kind, "Pod");
+        assert_eq!(ev.pod_name, "megadb-worker-0");
+        assert_eq!(ev.uid.as_deref(), Some("abc"));
+        assert_eq!(ev.count, 4);
+        assert_eq!(
+            ev.first_timestamp.unwrap().to_rfc3339(),
+            "2026-02-01T10:00:00+00:00"
+        );
+        assert_eq!(
+            ev.last_timestamp.unwrap().to_rfc3339(),
+            "2026-02-01T10:05:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_core_event_severity() {
+        let severity = |type_: &str, reason: &str| {
+            core_event(event(serde_json::json!({
+                "metadata": {},
+                "involvedObject": {},
+                "type": type_,
+                "reason": reason,
+            })))
+            .severity
+        };
+        assert_eq!(severity("Normal", "Scheduled"), EventSeverity::Info);
+        assert_eq!(severity("Warning", "Unhealthy"), EventSeverity::Warning);
+        assert_eq!(severity("Warning", "FailedMount"), EventSeverity::Error);
+    }
+
+    #[test]
+    fn test_core_event_defaults() {
+        // Events from the events.k8s.io API only set eventTime
+        let ev = core_event(event(serde_json::json!({
+            "metadata": {},
+            "involvedObject": { "name": "megadb-worker-1" },
+            "eventTime": "2026-02-01T10:00:00.000000Z",
+        })));
+        assert_eq!(ev.event_type, "Normal");
+        assert_eq!(ev.severity, EventSeverity::Info);
+        assert_eq!(ev.count, 1);
+        assert_eq!(ev.uid, None);
+        assert!(ev.first_timestamp.is_some());
+        assert_eq!(ev.last_timestamp, ev.first_timestamp);
+    }
+
+    #[test]
+    fn test_prom_scalar() {
+        let body = r#"{"status":"success","data":{"resultType":"vector","result":[{"metric":{},"value":[1767225600.0,"42.5"]}]}}"#;
+        assert_eq!(prom(body), 42.5);
+    }
+
+    #[test]
+    fn test_prom_scalar_empty_or_nan() {
+        let empty = r#"{"status":"success","data":{"resultType":"vector","result":[]}}"#;
+        assert_eq!(prom(empty), 0.0);
+        let nan = r#"{"status":"success","data":{"resultType":"vector","result":[{"metric":{},"value":[1767225600.0,"NaN"]}]}}"#;
+        assert_eq!(prom(nan), 0.0);
+    }
+}
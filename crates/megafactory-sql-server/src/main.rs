@@ -8,6 +8,8 @@ use tower_http::services::ServeDir;
 use tracing_subscriber::EnvFilter;
 
 mod config;
+mod exec;
+mod feeds;
 mod proxy;
 mod websocket;
 
@@ -55,6 +57,15 @@ async fn main() {
     let app_config = config::AppConfig::from_env();
     tracing::info!("Starting MegaFactory SQL on {}", app_config.bind_address);
     tracing::info!("MegaDB backend: {}", app_config.megadb_url);
+    if app_config.demo {
+        tracing::info!("Demo mode: serving mock metrics and K8s events");
+    }
+    if let Some(dir) = app_config.catalog.dir() {
+        tracing::info!("Instance catalog overrides: {}", dir.display());
+        for e in app_config.catalog.check_all().await {
+            tracing::warn!("{e}");
+        }
+    }
 
     let leptos_options = LeptosOptions::builder()
         .output_name("megafactory-sql")
@@ -76,7 +87,7 @@ async fn main() {
         .allow_headers(Any);
 
     // Create WebSocket hub for real-time broadcast
-    let ws_hub = websocket::WsHub::new();
+    let ws_hub = websocket::WsHub::new(&app_config);
 
     // Build proxy + WS routes as a standalone Router (no state needed).
     let api_routes: Router<()> = proxy::router()
@@ -88,10 +99,18 @@ async fn main() {
     let app: Router<LeptosOptions> = Router::default();
     let app = app
         .nest_service("/js", ServeDir::new("js"))
-        .leptos_routes(&leptos_options, routes, {
-            let options = leptos_options.clone();
-            move || shell(options.clone())
-        })
+        .leptos_routes_with_context(
+            &leptos_options,
+            routes,
+            {
+                let server_context = app_config.server_context();
+                move || provide_context(server_context.clone())
+            },
+            {
+                let options = leptos_options.clone();
+                move || shell(options.clone())
+            },
+        )
         .fallback(leptos_axum::file_and_error_handler(shell))
         .layer(cors)
         .with_state(leptos_options)
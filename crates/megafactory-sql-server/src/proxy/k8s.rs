@@ -1,10 +1,21 @@
 use axum::{
-    extract::Extension,
-    http::StatusCode,
-    response::Json,
-    routing::{get, post},
+    body::Body,
+    extract::{Extension, Path, Query},
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
+    routing::{delete, get, patch, post},
     Router,
 };
+use futures::StreamExt;
+
+use megafactory_sql_app::server::crd::{MegaDB, MegaDBSpec};
+use megafactory_sql_app::server::K8sError;
+use megafactory_sql_types::k8s::{
+    AutoscalerStatus, AutoscalerUpdate, ClusterStatus, MegaDbSummary, ScaleRequest, ScaleResponse,
+    VolumeInfo, VolumeResizeRequest,
+};
+use megafactory_sql_types::logs::{LogChunk, LogQuery};
+use megafactory_sql_types::ws::K8sEvent;
 
 use crate::config::AppConfig;
 
@@ -41,107 +52,234 @@ async fn list_pods(Extension(config): Extension<AppConfig>) -> Json<serde_json::
     }
 }
 
-/// Get cluster status via StatefulSet.
-async fn cluster_status(Extension(config): Extension<AppConfig>) -> Json<serde_json::Value> {
-    match kube::Client::try_default().await {
-        Ok(client) => {
-            let sts: kube::Api<k8s_openapi::api::apps::v1::StatefulSet> =
-                kube::Api::namespaced(client, &config.k8s_namespace);
-            match sts.list(&Default::default()).await {
-                Ok(list) => {
-                    let statefulsets: Vec<serde_json::Value> = list
-                        .items
-                        .iter()
-                        .map(|s| {
-                            let name = s.metadata.name.clone().unwrap_or_default();
-                            let replicas = s.spec.as_ref().and_then(|sp| sp.replicas).unwrap_or(0);
-                            let ready = s
-                                .status
-                                .as_ref()
-                                .and_then(|st| st.ready_replicas)
-                                .unwrap_or(0);
-                            serde_json::json!({
-                                "name": name,
-                                "replicas": replicas,
-                                "ready_replicas": ready,
-                            })
-                        })
-                        .collect();
-                    Json(serde_json::json!({
-                        "available": true,
-                        "statefulsets": statefulsets,
-                    }))
-                }
-                Err(e) => Json(serde_json::json!({
-                    "available": true,
-                    "error": format!("Failed to list StatefulSets: {e}"),
-                })),
-            }
-        }
-        Err(e) => Json(serde_json::json!({
-            "available": false,
-            "error": format!("Kubernetes not configured: {e}"),
+fn k8s_error(e: K8sError) -> (StatusCode, Json<serde_json::Value>) {
+    (
+        StatusCode::from_u16(e.status_code()).unwrap_or(StatusCode::BAD_GATEWAY),
+        Json(serde_json::json!({
+            "available": !matches!(e, K8sError::Unavailable(_)),
+            "error": e.to_string(),
         })),
-    }
+    )
 }
 
-/// Scale a StatefulSet.
+/// Full cluster status: worker StatefulSet, pods with usage, and volumes.
+async fn cluster_status(
+    Extension(config): Extension<AppConfig>,
+) -> Result<Json<ClusterStatus>, (StatusCode, Json<serde_json::Value>)> {
+    config
+        .k8s
+        .cluster_status()
+        .await
+        .map(Json)
+        .map_err(k8s_error)
+}
+
+/// Scale the MegaDB worker StatefulSet within the configured replica bounds.
 async fn scale(
     Extension(config): Extension<AppConfig>,
-    Json(req): Json<serde_json::Value>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
-    let replicas = req.get("replicas").and_then(|v| v.as_i64()).unwrap_or(1) as i32;
+    Json(req): Json<ScaleRequest>,
+) -> Result<Json<ScaleResponse>, (StatusCode, Json<serde_json::Value>)> {
+    config.k8s.scale(&req).await.map(Json).map_err(k8s_error)
+}
 
-    match kube::Client::try_default().await {
-        Ok(_client) => Ok(Json(serde_json::json!({
-            "success": true,
-            "message": format!("Scale request to {} replicas accepted (namespace: {})", replicas, config.k8s_namespace),
-        }))),
-        Err(e) => Err((
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(serde_json::json!({
-                "available": false,
-                "error": format!("Kubernetes not configured: {e}"),
-            })),
+type ProxyResult<T> = Result<Json<T>, (StatusCode, Json<serde_json::Value>)>;
+
+/// The worker ScaledObject with current trigger values and scaling history.
+async fn get_autoscaler(Extension(config): Extension<AppConfig>) -> ProxyResult<AutoscalerStatus> {
+    match config.k8s.autoscaler().await.map_err(k8s_error)? {
+        Some(status) => Ok(Json(status)),
+        None => Err((
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Workers are not autoscaled by KEDA"})),
         )),
     }
 }
 
-/// List PVCs in namespace.
-async fn list_volumes(Extension(config): Extension<AppConfig>) -> Json<serde_json::Value> {
-    match kube::Client::try_default().await {
-        Ok(client) => {
-            let pvcs: kube::Api<k8s_openapi::api::core::v1::PersistentVolumeClaim> =
-                kube::Api::namespaced(client, &config.k8s_namespace);
-            match pvcs.list(&Default::default()).await {
-                Ok(list) => {
-                    let volumes: Vec<String> = list
-                        .items
-                        .iter()
-                        .filter_map(|p| p.metadata.name.clone())
-                        .collect();
-                    Json(serde_json::json!({
-                        "available": true,
-                        "volumes": volumes,
-                    }))
-                }
-                Err(e) => Json(serde_json::json!({
-                    "available": true,
-                    "error": format!("Failed to list PVCs: {e}"),
-                })),
-            }
-        }
-        Err(e) => Json(serde_json::json!({
-            "available": false,
-            "error": format!("Kubernetes not configured: {e}"),
-        })),
+/// Update the worker ScaledObject's replica bounds and trigger thresholds.
+async fn update_autoscaler(
+    Extension(config): Extension<AppConfig>,
+    Json(update): Json<AutoscalerUpdate>,
+) -> ProxyResult<AutoscalerStatus> {
+    config
+        .k8s
+        .update_autoscaler(&update)
+        .await
+        .map(Json)
+        .map_err(k8s_error)
+}
+
+/// List MegaDB custom resources in the namespace.
+async fn list_megadbs(Extension(config): Extension<AppConfig>) -> ProxyResult<Vec<MegaDbSummary>> {
+    config.k8s.list_megadbs().await.map(Json).map_err(k8s_error)
+}
+
+async fn get_megadb(
+    Extension(config): Extension<AppConfig>,
+    Path(name): Path<String>,
+) -> ProxyResult<MegaDB> {
+    match config.k8s.get_megadb(&name).await.map_err(k8s_error)? {
+        Some(megadb) => Ok(Json(megadb)),
+        None => Err((
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": format!("MegaDB {name} not found")})),
+        )),
     }
 }
 
+#[derive(Debug, serde::Deserialize)]
+struct ApplyParams {
+    #[serde(default)]
+    dry_run: bool,
+}
+
+/// Server-side apply a MegaDB spec under `name`; `?dry_run=true` validates only.
+async fn apply_megadb(
+    Extension(config): Extension<AppConfig>,
+    Path(name): Path<String>,
+    Query(params): Query<ApplyParams>,
+    Json(spec): Json<MegaDBSpec>,
+) -> ProxyResult<MegaDB> {
+    config
+        .k8s
+        .apply_megadb(&MegaDB::new(&name, spec), params.dry_run)
+        .await
+        .map(Json)
+        .map_err(k8s_error)
+}
+
+async fn delete_megadb(
+    Extension(config): Extension<AppConfig>,
+    Path(name): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
+    config
+        .k8s
+        .delete_megadb(&name)
+        .await
+        .map(|()| StatusCode::ACCEPTED)
+        .map_err(k8s_error)
+}
+
+/// Rolling-restart the worker StatefulSet.
+async fn rolling_restart(
+    Extension(config): Extension<AppConfig>,
+) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
+    config
+        .k8s
+        .rolling_restart()
+        .await
+        .map(|()| StatusCode::ACCEPTED)
+        .map_err(k8s_error)
+}
+
+async fn delete_pod(
+    Extension(config): Extension<AppConfig>,
+    Path(name): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
+    config
+        .k8s
+        .delete_pod(&name)
+        .await
+        .map(|()| StatusCode::ACCEPTED)
+        .map_err(k8s_error)
+}
+
+/// Drain a worker through the configured MegaDB, then delete its pod.
+async fn drain_worker(
+    Extension(config): Extension<AppConfig>,
+    Path(name): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
+    config
+        .k8s
+        .drain_worker(&config.megadb, &config.megadb_url, &name)
+        .await
+        .map(|()| StatusCode::ACCEPTED)
+        .map_err(k8s_error)
+}
+
+/// Stream a cluster pod's logs as NDJSON `LogChunk`s; with `?follow=true`
+/// the response stays open and new lines are sent as they are written.
+async fn pod_logs(
+    Extension(config): Extension<AppConfig>,
+    Path(name): Path<String>,
+    Query(query): Query<LogQuery>,
+) -> Result<Response, (StatusCode, Json<serde_json::Value>)> {
+    let lines = config
+        .k8s
+        .pod_logs(&name, &query)
+        .await
+        .map_err(k8s_error)?;
+
+    // Errors after the headers are sent travel in-band as an `error` chunk.
+    let chunks = lines.map(|line| {
+        let chunk = match line {
+            Ok(text) => LogChunk::Line { text },
+            Err(e) => LogChunk::Error {
+                message: e.to_string(),
+            },
+        };
+        let mut line = serde_json::to_vec(&chunk).unwrap_or_default();
+        line.push(b'\n');
+        Ok::<_, std::convert::Infallible>(line)
+    });
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        Body::from_stream(chunks),
+    )
+        .into_response())
+}
+
+/// The cluster's PVCs with usage, storage class and whether they can grow.
+async fn list_volumes(Extension(config): Extension<AppConfig>) -> ProxyResult<Vec<VolumeInfo>> {
+    config.k8s.volumes().await.map(Json).map_err(k8s_error)
+}
+
+/// Expand a PVC whose storage class allows it.
+async fn resize_volume(
+    Extension(config): Extension<AppConfig>,
+    Path(name): Path<String>,
+    Json(req): Json<VolumeResizeRequest>,
+) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
+    config
+        .k8s
+        .resize_volume(&name, &req)
+        .await
+        .map(|()| StatusCode::ACCEPTED)
+        .map_err(k8s_error)
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct EventsQuery {
+    #[serde(default)]
+    since: u64,
+}
+
+/// Logged cluster events newer than `?since=<id>`, oldest first, for
+/// dashboards catching up after missing WebSocket events.
+async fn list_events(
+    Extension(config): Extension<AppConfig>,
+    Query(query): Query<EventsQuery>,
+) -> Json<Vec<K8sEvent>> {
+    Json(config.k8s.events_since(query.since))
+}
+
 pub fn router() -> Router {
     Router::new()
         .route("/pods", get(list_pods))
+        .route("/pods/{name}", delete(delete_pod))
+        .route("/pods/{name}/drain", post(drain_worker))
+        .route("/pods/{name}/logs", get(pod_logs))
+        .route("/pods/{name}/exec", get(crate::exec::exec_upgrade_handler))
         .route("/status", get(cluster_status))
+        .route("/events", get(list_events))
         .route("/scale", post(scale))
+        .route("/restart", post(rolling_restart))
+        .route("/autoscaler", get(get_autoscaler).put(update_autoscaler))
         .route("/volumes", get(list_volumes))
+        .route("/volumes/{name}", patch(resize_volume))
+        .route("/megadbs", get(list_megadbs))
+        .route(
+            "/megadbs/{name}",
+            get(get_megadb).put(apply_megadb).delete(delete_megadb),
+        )
 }
@@ -1,13 +1,24 @@
 use axum::{
-    extract::Extension,
-    http::StatusCode,
-    response::{IntoResponse, Json},
+    body::Body,
+    extract::{Extension, Path},
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
     routing::{get, post},
     Router,
 };
+use futures::StreamExt;
+use megafactory_sql_app::server::MegaDbError;
+use megafactory_sql_types::query::{QueryResultChunk, StreamQueryRequest};
 
 use crate::config::AppConfig;
 
+fn megadb_error(e: MegaDbError) -> (StatusCode, Json<serde_json::Value>) {
+    (
+        StatusCode::from_u16(e.status_code()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+        Json(serde_json::json!({"error": e.to_string()})),
+    )
+}
+
 /// Health check: probes MegaDB at config.megadb_url/health with 3s timeout.
 async fn health_check(Extension(config): Extension<AppConfig>) -> impl IntoResponse {
     let start = std::time::Instant::now();
@@ -33,37 +44,62 @@ async fn proxy_query(
     Json(req): Json<megafactory_sql_types::query::QueryRequest>,
 ) -> Result<Json<megafactory_sql_types::query::QueryResult>, (StatusCode, Json<serde_json::Value>)>
 {
-    let resp = config
-        .client
-        .post(format!("{}/query", config.megadb_url))
-        .json(&req)
-        .timeout(std::time::Duration::from_secs(60))
-        .send()
+    config
+        .megadb
+        .query(&config.megadb_url, &req)
         .await
-        .map_err(|e| {
-            (
-                StatusCode::BAD_GATEWAY,
-                Json(serde_json::json!({"error": format!("MegaDB unreachable: {e}")})),
-            )
-        })?;
+        .map(Json)
+        .map_err(megadb_error)
+}
 
-    if !resp.status().is_success() {
-        let status = resp.status();
-        let body = resp.text().await.unwrap_or_default();
-        return Err((
-            StatusCode::from_u16(status.as_u16()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
-            Json(serde_json::json!({"error": body})),
-        ));
-    }
+/// Streaming query proxy: forwards SQL to MegaDB POST /query/stream and relays
+/// NDJSON `QueryResultChunk`s without buffering the result set. A connection
+/// outside the client's allowed endpoints is refused with 403.
+async fn proxy_query_stream(
+    Extension(config): Extension<AppConfig>,
+    Json(body): Json<StreamQueryRequest>,
+) -> Result<Response, (StatusCode, Json<serde_json::Value>)> {
+    let base_url = body
+        .connection
+        .map(|c| c.http_base_url())
+        .unwrap_or_else(|| config.megadb_url.clone());
+    let chunks = config
+        .megadb
+        .query_stream(&base_url, &body.request)
+        .await
+        .map_err(megadb_error)?;
 
-    let result = resp.json().await.map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({"error": format!("Failed to parse response: {e}")})),
-        )
-    })?;
+    // Errors after the headers are sent travel in-band as an `error` chunk.
+    let lines = chunks.map(|chunk| {
+        let chunk = chunk.unwrap_or_else(|e| QueryResultChunk::Error {
+            message: e.to_string(),
+        });
+        let mut line = serde_json::to_vec(&chunk).unwrap_or_default();
+        line.push(b'\n');
+        Ok::<_, std::convert::Infallible>(line)
+    });
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        Body::from_stream(lines),
+    )
+        .into_response())
+}
 
-    Ok(Json(result))
+/// Cancel a query started through this server (either proxy route or server
+/// function) by its client-assigned id.
+async fn cancel_query(
+    Extension(config): Extension<AppConfig>,
+    Path(query_id): Path<uuid::Uuid>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match config.megadb.cancel(query_id).await {
+        Ok(true) => Ok(Json(serde_json::json!({"cancelled": true}))),
+        Ok(false) => Err((
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": format!("No running query with id {query_id}")})),
+        )),
+        Err(e) => Err(megadb_error(e)),
+    }
 }
 
 /// Tables proxy: GET /proxy/megadb/tables.
@@ -118,6 +154,8 @@ pub fn router() -> Router {
     Router::new()
         .route("/health", get(health_check))
         .route("/query", post(proxy_query))
+        .route("/query/stream", post(proxy_query_stream))
+        .route("/query/{id}/cancel", post(cancel_query))
         .route("/tables", get(proxy_tables))
         .route("/metrics", get(proxy_metrics))
 }
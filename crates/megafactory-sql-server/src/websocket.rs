@@ -5,36 +5,160 @@ use axum::{
     },
     response::IntoResponse,
 };
+use megafactory_sql_app::server::{K8sClient, MegaDbClient, ProgressWatch};
+use megafactory_sql_types::ws::{
+    K8sEvent, WsClientMessage, WsServerMessage, K8S_TOPIC, METRICS_TOPIC, QUERY_TOPIC,
+};
+use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, mpsc};
+use tokio::task::JoinHandle;
+
+use crate::config::AppConfig;
+use crate::feeds;
+
+/// Outgoing messages buffered per connection before the socket applies backpressure.
+const CONNECTION_BUFFER: usize = 256;
+
+/// A message published on a topic, already serialized as `WsServerMessage` JSON.
+#[derive(Debug, Clone)]
+struct TopicMessage {
+    /// Sub-topic key (e.g. the query id); `None` on unkeyed topics.
+    key: Option<String>,
+    json: String,
+}
 
-/// WebSocket hub managing topic-based broadcast channels.
+/// A registered topic and its broadcast channel.
+struct Topic {
+    tx: broadcast::Sender<TopicMessage>,
+    /// Subscribers must name a key (`topic:<key>`) and only receive messages for it.
+    keyed: bool,
+}
+
+/// WebSocket hub: a registry of topics, each backed by a broadcast channel.
+///
+/// Sockets start with no subscriptions and receive only the topics they
+/// `Subscribe` to. Adding a topic is one `register` call in `new`.
 pub struct WsHub {
-    metrics_tx: broadcast::Sender<String>,
-    k8s_tx: broadcast::Sender<String>,
-    #[allow(dead_code)]
-    query_tx: broadcast::Sender<String>,
+    topics: HashMap<&'static str, Topic>,
+    /// Polls query progress for `query:<id>` subscribers.
+    megadb: MegaDbClient,
+}
+
+/// A socket's subscription to one topic.
+struct Subscription {
+    rx: broadcast::Receiver<TopicMessage>,
+    key: Option<String>,
+    /// Held on `query:<id>` so the query's progress is polled.
+    watch: Option<ProgressWatch>,
 }
 
 impl WsHub {
-    pub fn new() -> Arc<Self> {
-        let (metrics_tx, _) = broadcast::channel(64);
-        let (k8s_tx, _) = broadcast::channel(64);
-        let (query_tx, _) = broadcast::channel(64);
-
-        let hub = Arc::new(Self {
-            metrics_tx,
-            k8s_tx,
-            query_tx,
-        });
+    /// Create the hub and start its producers: live Prometheus/Kubernetes
+    /// feeds, or mocks when `config.demo` is set.
+    pub fn new(config: &AppConfig) -> Arc<Self> {
+        let mut hub = Self {
+            topics: HashMap::new(),
+            megadb: config.megadb.clone(),
+        };
+        hub.register(METRICS_TOPIC, false);
+        hub.register(K8S_TOPIC, false);
+        hub.register(QUERY_TOPIC, true);
+        let hub = Arc::new(hub);
 
-        Self::spawn_mock_metrics(hub.clone());
-        Self::spawn_mock_k8s_events(hub.clone());
+        if config.demo {
+            Self::spawn_mock_metrics(hub.clone());
+            Self::spawn_mock_k8s_events(config.k8s.clone());
+        } else {
+            feeds::spawn_prometheus_metrics(hub.clone(), config.clone());
+            feeds::spawn_k8s_watch(config.k8s.clone());
+        }
+        Self::spawn_relay(
+            hub.clone(),
+            config.megadb.subscribe_progress(),
+            QUERY_TOPIC,
+            |event| {
+                let key = Some(event.query_id.clone());
+                (key, WsServerMessage::QueryProgress(event))
+            },
+        );
+        Self::spawn_relay(
+            hub.clone(),
+            config.k8s.subscribe_events(),
+            K8S_TOPIC,
+            |event| (None, WsServerMessage::K8sEvent(event)),
+        );
 
         hub
     }
 
-    /// Send mock metrics updates every 5 seconds.
+    fn register(&mut self, name: &'static str, keyed: bool) {
+        let (tx, _) = broadcast::channel(64);
+        self.topics.insert(name, Topic { tx, keyed });
+    }
+
+    /// Publish `msg` on `topic`; `key` selects subscribers of `topic:<key>`.
+    pub fn publish(&self, topic: &str, key: Option<String>, msg: &WsServerMessage) {
+        let Some(topic) = self.topics.get(topic) else {
+            return;
+        };
+        if let Ok(json) = serde_json::to_string(msg) {
+            // No receivers just means nobody is subscribed right now.
+            let _ = topic.tx.send(TopicMessage { key, json });
+        }
+    }
+
+    /// Resolve a client topic name (`metrics`, `query:<id>`) to a receiver and
+    /// key filter. Returns `None` for unknown topics, a missing/unexpected key,
+    /// or a query id that isn't a UUID.
+    fn subscribe(&self, name: &str) -> Option<Subscription> {
+        let (base, key) = match name.split_once(':') {
+            Some((base, key)) if !key.is_empty() => (base, Some(key.to_string())),
+            Some(_) => return None,
+            None => (name, None),
+        };
+        let topic = self.topics.get(base)?;
+        if topic.keyed != key.is_some() {
+            return None;
+        }
+        // Query ids are matched in the form the progress relay publishes them
+        let (key, watch) = match (base, key) {
+            (QUERY_TOPIC, Some(key)) => {
+                let id: uuid::Uuid = key.parse().ok()?;
+                (Some(id.to_string()), Some(self.megadb.watch_progress(id)))
+            }
+            (_, key) => (key, None),
+        };
+        Some(Subscription {
+            rx: topic.tx.subscribe(),
+            key,
+            watch,
+        })
+    }
+
+    /// Relay events from a shared server-side client (MegaDB progress, K8s
+    /// changes) onto `topic`; `to_message` picks the key and message.
+    fn spawn_relay<T: Clone + Send + 'static>(
+        hub: Arc<Self>,
+        mut rx: broadcast::Receiver<T>,
+        topic: &'static str,
+        to_message: fn(T) -> (Option<String>, WsServerMessage),
+    ) {
+        tokio::spawn(async move {
+            loop {
+                match rx.recv().await {
+                    Ok(event) => {
+                        let (key, msg) = to_message(event);
+                        hub.publish(topic, key, &msg);
+                    }
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+
+    /// Send mock metrics updates every 5 seconds (demo mode).
     fn spawn_mock_metrics(hub: Arc<Self>) {
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(std::time::Duration::from_secs(5));
@@ -49,16 +173,17 @@ impl WsHub {
                     active_sessions: (8.0 + rand_f64() * 10.0) as u32,
                     open_connections: (5.0 + rand_f64() * 8.0) as u32,
                 };
-                let msg = megafactory_sql_types::ws::WsServerMessage::MetricsUpdate(metrics);
-                if let Ok(json) = serde_json::to_string(&msg) {
-                    let _ = hub.metrics_tx.send(json);
-                }
+                hub.publish(
+                    METRICS_TOPIC,
+                    None,
+                    &WsServerMessage::MetricsUpdate(metrics),
+                );
             }
         });
     }
 
-    /// Send mock K8s events every 10 seconds.
-    fn spawn_mock_k8s_events(hub: Arc<Self>) {
+    /// Send mock K8s events every 10 seconds (demo mode).
+    fn spawn_mock_k8s_events(k8s: K8sClient) {
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(std::time::Duration::from_secs(10));
             let events = [
@@ -74,15 +199,7 @@ impl WsHub {
             loop {
                 interval.tick().await;
                 let (event_type, pod_name, message) = events[idx % events.len()];
-                let event = megafactory_sql_types::ws::K8sEvent {
-                    event_type: event_type.into(),
-                    pod_name: pod_name.into(),
-                    message: message.into(),
-                };
-                let msg = megafactory_sql_types::ws::WsServerMessage::K8sEvent(event);
-                if let Ok(json) = serde_json::to_string(&msg) {
-                    let _ = hub.k8s_tx.send(json);
-                }
+                k8s.record_event(K8sEvent::new(event_type, "Pod", pod_name, message));
                 idx += 1;
             }
         });
@@ -98,38 +215,98 @@ pub async fn ws_upgrade_handler(
 }
 
 async fn handle_socket(mut socket: WebSocket, hub: Arc<WsHub>) {
-    let mut metrics_rx = hub.metrics_tx.subscribe();
-    let mut k8s_rx = hub.k8s_tx.subscribe();
+    // Subscriptions forward into `out_tx`; this loop is the socket's only writer.
+    let (out_tx, mut out_rx) = mpsc::channel::<String>(CONNECTION_BUFFER);
+    let mut subscriptions: HashMap<String, JoinHandle<()>> = HashMap::new();
 
     loop {
         tokio::select! {
-            Ok(msg) = metrics_rx.recv() => {
-                if socket.send(Message::Text(msg.into())).await.is_err() {
+            Some(json) = out_rx.recv() => {
+                if socket.send(Message::Text(json.into())).await.is_err() {
                     break;
                 }
             }
-            Ok(msg) = k8s_rx.recv() => {
-                if socket.send(Message::Text(msg.into())).await.is_err() {
-                    break;
-                }
-            }
-            Some(msg) = socket.recv() => {
-                match msg {
-                    Ok(Message::Text(text)) => {
-                        if let Ok(megafactory_sql_types::ws::WsClientMessage::Ping) = serde_json::from_str::<megafactory_sql_types::ws::WsClientMessage>(&text) {
-                            let pong = serde_json::to_string(&megafactory_sql_types::ws::WsServerMessage::Pong).unwrap_or_default();
-                            if socket.send(Message::Text(pong.into())).await.is_err() {
-                                break;
+            // `out_rx` never closes while `out_tx` is held here, so the
+            // socket ending has to end the loop.
+            msg = socket.recv() => {
+                let text = match msg {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+                    Some(Ok(_)) => continue,
+                };
+                let reply = match serde_json::from_str::<WsClientMessage>(&text) {
+                    Ok(WsClientMessage::Ping) => Some(WsServerMessage::Pong),
+                    Ok(WsClientMessage::Subscribe { topics }) => {
+                        let mut unknown = Vec::new();
+                        for name in topics {
+                            if subscriptions.contains_key(&name) {
+                                continue;
+                            }
+                            match hub.subscribe(&name) {
+                                Some(subscription) => {
+                                    let task = forward_topic(subscription, out_tx.clone());
+                                    subscriptions.insert(name, task);
+                                }
+                                None => unknown.push(name),
+                            }
+                        }
+                        (!unknown.is_empty()).then(|| WsServerMessage::Error {
+                            message: format!("Unknown topic(s): {}", unknown.join(", ")),
+                        })
+                    }
+                    Ok(WsClientMessage::Unsubscribe { topics }) => {
+                        for name in topics {
+                            if let Some(task) = subscriptions.remove(&name) {
+                                task.abort();
                             }
                         }
+                        None
+                    }
+                    Err(e) => Some(WsServerMessage::Error {
+                        message: format!("Invalid message: {e}"),
+                    }),
+                };
+                if let Some(reply) = reply {
+                    let json = serde_json::to_string(&reply).unwrap_or_default();
+                    if socket.send(Message::Text(json.into())).await.is_err() {
+                        break;
                     }
-                    Ok(Message::Close(_)) | Err(_) => break,
-                    _ => {}
                 }
             }
-            else => break,
         }
     }
+
+    // The forwarding tasks would otherwise run until their topic closes
+    for task in subscriptions.into_values() {
+        task.abort();
+    }
+}
+
+/// Forward one subscription's messages to its connection, keeping only those
+/// matching its key on keyed topics. A client that lags skips what it missed.
+fn forward_topic(subscription: Subscription, out_tx: mpsc::Sender<String>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        // The progress watch lasts as long as this task
+        let Subscription {
+            mut rx,
+            key,
+            watch: _watch,
+        } = subscription;
+        loop {
+            match rx.recv().await {
+                Ok(msg) => {
+                    if key.is_some() && msg.key != key {
+                        continue;
+                    }
+                    if out_tx.send(msg.json).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    })
 }
 
 /// Simple pseudo-random f64 in [0, 1) using system time.
@@ -140,3 +317,116 @@ fn rand_f64() -> f64 {
         .subsec_nanos();
     (nanos % 1000) as f64 / 1000.0
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{SinkExt, StreamExt};
+    use std::time::Duration;
+
+    /// A hub with the live topics registered but no producers running.
+    fn hub() -> WsHub {
+        let mut hub = WsHub {
+            topics: HashMap::new(),
+            megadb: MegaDbClient::new(reqwest::Client::new()),
+        };
+        hub.register(METRICS_TOPIC, false);
+        hub.register(K8S_TOPIC, false);
+        hub.register(QUERY_TOPIC, true);
+        hub
+    }
+
+    fn error(message: &str) -> WsServerMessage {
+        WsServerMessage::Error {
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_subscribe_unknown_topic() {
+        assert!(hub().subscribe("logs").is_none());
+    }
+
+    #[test]
+    fn test_subscribe_keyed_topic_without_key() {
+        let hub = hub();
+        assert!(hub.subscribe("query:").is_none());
+        assert!(hub.subscribe("query").is_none());
+    }
+
+    #[test]
+    fn test_subscribe_unkeyed_topic_with_key() {
+        assert!(hub().subscribe("metrics:x").is_none());
+    }
+
+    #[test]
+    fn test_subscribe_query_requires_uuid() {
+        assert!(hub().subscribe("query:not-a-uuid").is_none());
+    }
+
+    #[test]
+    fn test_subscribe_query_key_matches_published_id() {
+        let id = uuid::Uuid::new_v4();
+        let subscription = hub()
+            .subscribe(&format!("query:{}", id.to_string().to_uppercase()))
+            .unwrap();
+        assert_eq!(subscription.key, Some(id.to_string()));
+        assert!(subscription.watch.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_forward_topic_filters_by_key() {
+        let hub = hub();
+        let (a, b) = (uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+        let subscription = hub.subscribe(&format!("query:{a}")).unwrap();
+        let (out_tx, mut out_rx) = mpsc::channel(8);
+        let task = forward_topic(subscription, out_tx);
+
+        hub.publish(QUERY_TOPIC, Some(b.to_string()), &error("b"));
+        hub.publish(QUERY_TOPIC, Some(a.to_string()), &error("a"));
+
+        let json = tokio::time::timeout(Duration::from_secs(1), out_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(json, serde_json::to_string(&error("a")).unwrap());
+        assert!(out_rx.try_recv().is_err());
+        task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_handle_socket_reports_rejected_topics() {
+        let app = axum::Router::new()
+            .route("/ws", axum::routing::get(ws_upgrade_handler))
+            .layer(Extension(Arc::new(hub())));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{addr}/ws"))
+            .await
+            .unwrap();
+        let subscribe = WsClientMessage::Subscribe {
+            topics: vec!["metrics".into(), "logs".into(), "query:nope".into()],
+        };
+        socket
+            .send(tokio_tungstenite::tungstenite::Message::Text(
+                serde_json::to_string(&subscribe).unwrap().into(),
+            ))
+            .await
+            .unwrap();
+
+        let reply = tokio::time::timeout(Duration::from_secs(5), socket.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let reply: WsServerMessage = serde_json::from_str(reply.to_text().unwrap()).unwrap();
+        match reply {
+            WsServerMessage::Error { message } => {
+                assert_eq!(message, "Unknown topic(s): logs, query:nope")
+            }
+            other => panic!("expected an error, got {other:?}"),
+        }
+    }
+}
@@ -37,6 +37,35 @@ impl AccelerableOp {
             Self::RuleEngine => "Rule Engine",
         }
     }
+
+    pub fn all() -> &'static [AccelerableOp] {
+        &[
+            Self::HashAggregate,
+            Self::Filter,
+            Self::Sort,
+            Self::HashJoin,
+            Self::GraphTraversal,
+            Self::VectorDistance,
+            Self::CostAnalytics,
+            Self::Decompression,
+            Self::RuleEngine,
+        ]
+    }
+
+    /// Speedup of `backend` over CPU/SIMD for this operator on a large
+    /// input, as quoted by the workbench. `None` when `backend` has no kernel
+    /// for the operator.
+    pub fn speedup(&self, backend: AcceleratorBackend) -> Option<f64> {
+        match (self, backend) {
+            (_, AcceleratorBackend::Cpu) => Some(1.0),
+            (Self::HashAggregate, AcceleratorBackend::Gpu) => Some(9.3),
+            (Self::GraphTraversal, AcceleratorBackend::Gpu) => Some(45.0),
+            (Self::VectorDistance, AcceleratorBackend::Gpu) => Some(50.0),
+            (Self::CostAnalytics, AcceleratorBackend::Npu) => Some(8.0),
+            (Self::Decompression, AcceleratorBackend::Fpga) => Some(5.0),
+            _ => None,
+        }
+    }
 }
 
 /// Execution backend for an operator.
@@ -227,6 +256,20 @@ mod tests {
         assert_eq!(AccelerableOp::Decompression.label(), "Decompression");
     }
 
+    #[test]
+    fn accelerable_op_speedups() {
+        assert_eq!(AccelerableOp::all().len(), 9);
+        assert_eq!(
+            AccelerableOp::VectorDistance.speedup(AcceleratorBackend::Gpu),
+            Some(50.0)
+        );
+        assert_eq!(
+            AccelerableOp::Filter.speedup(AcceleratorBackend::Cpu),
+            Some(1.0)
+        );
+        assert_eq!(AccelerableOp::Sort.speedup(AcceleratorBackend::Fpga), None);
+    }
+
     #[test]
     fn backend_labels() {
         assert_eq!(AcceleratorBackend::Gpu.label(), "GPU (CUDA)");
@@ -10,8 +10,12 @@
 //! - `megadb-k8s/src/crd.rs` — MegaDBSpec, AcceleratorSpec, GpuResourceSpec, WorkerGroupSpec
 //! - `megadb-core/src/config.rs` — AcceleratorConfig, GpuConfig, FpgaConfig, NpuConfig
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
+use crate::advisor::{AccelerableOp, AcceleratorBackend};
+
 // ─── Cloud Provider & Instance Catalog ──────────────────────────────────────
 
 /// Cloud provider for the K8s cluster.
@@ -36,6 +40,16 @@ impl CloudProvider {
     pub fn all() -> &'static [CloudProvider] {
         &[Self::Aws, Self::Gcp, Self::Azure, Self::OnPrem]
     }
+
+    /// Short name used for catalog files, e.g. `on_prem.json`.
+    pub fn slug(&self) -> &'static str {
+        match self {
+            Self::Aws => "aws",
+            Self::Gcp => "gcp",
+            Self::Azure => "azure",
+            Self::OnPrem => "on_prem",
+        }
+    }
 }
 
 /// Type of hardware accelerator available on an instance.
@@ -68,6 +82,17 @@ impl AcceleratorType {
             Self::None => "CPU",
         }
     }
+
+    /// The MegaDB execution backend for this accelerator; TPUs have none.
+    pub fn backend(&self) -> Option<AcceleratorBackend> {
+        match self {
+            Self::Gpu => Some(AcceleratorBackend::Gpu),
+            Self::Fpga => Some(AcceleratorBackend::Fpga),
+            Self::Npu => Some(AcceleratorBackend::Npu),
+            Self::Tpu => None,
+            Self::None => Some(AcceleratorBackend::Cpu),
+        }
+    }
 }
 
 /// An instance family from a cloud provider (e.g., AWS p5, GCP a3).
@@ -100,7 +125,9 @@ pub struct AcceleratorDetail {
     pub count: u32,
     pub memory_gb: Option<u32>,
     pub compute_capability: Option<String>,
+    #[serde(default)]
     pub supports_mig: bool,
+    #[serde(default)]
     pub mig_profiles: Vec<String>,
 }
 
@@ -117,6 +144,18 @@ pub struct NodeHardware {
     pub labels: Vec<(String, String)>,
 }
 
+impl NodeHardware {
+    /// Allocatable CPU in cores; `cpu_allocatable` is a quantity such as
+    /// "16" or "15890m".
+    pub fn cpu_cores(&self) -> f64 {
+        let cpu = self.cpu_allocatable.trim();
+        match cpu.strip_suffix('m') {
+            Some(millis) => millis.parse::<f64>().map_or(0.0, |m| m / 1000.0),
+            None => cpu.parse().unwrap_or(0.0),
+        }
+    }
+}
+
 /// A hardware accelerator detected on a K8s node.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DetectedAccelerator {
@@ -142,6 +181,9 @@ pub struct ClusterHardwareStatus {
     pub tpu_available: u32,
     pub k8s_connected: bool,
     pub error: Option<String>,
+    /// Cost of the detected nodes, filled in by the server from its catalog.
+    #[serde(default)]
+    pub cost: Option<ClusterCostSummary>,
 }
 
 // ─── Deployment Configuration ───────────────────────────────────────────────
@@ -160,6 +202,23 @@ pub struct DeploymentConfig {
     pub storage_backend: StorageBackend,
     pub keda_enabled: bool,
     pub estimated_monthly_cost_usd: f64,
+    /// What an imported manifest carried beyond these settings.
+    #[serde(default)]
+    pub preserved: PreservedFields,
+}
+
+/// Parts of an imported MegaDB resource the configurator doesn't edit,
+/// written back unchanged when the config is deployed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PreservedFields {
+    /// `spec.version`, when set.
+    pub version: Option<String>,
+    pub labels: BTreeMap<String, String>,
+    pub annotations: BTreeMap<String, String>,
+    /// Spec fields the configurator doesn't know, by name.
+    pub spec: BTreeMap<String, serde_json::Value>,
+    /// Unknown fields of each worker group, by group ("gpu_workers", ...).
+    pub worker_groups: BTreeMap<String, BTreeMap<String, serde_json::Value>>,
 }
 
 /// Worker pool configuration for a specific node type.
@@ -222,926 +281,1049 @@ impl StorageBackend {
             CloudProvider::OnPrem => Self::Local,
         }
     }
+
+    pub fn all() -> &'static [StorageBackend] {
+        &[Self::S3, Self::Gcs, Self::AzureBlob, Self::Local]
+    }
+
+    /// Standard-tier list price per GB-month; local storage is owned
+    /// hardware and costs nothing extra.
+    pub fn price_per_gb_month_usd(&self) -> f64 {
+        match self {
+            Self::S3 => 0.023,
+            Self::Gcs => 0.020,
+            Self::AzureBlob => 0.018,
+            Self::Local => 0.0,
+        }
+    }
 }
 
-// ─── Instance Catalog Builder ───────────────────────────────────────────────
+impl PoolAccelerator {
+    /// All of an instance type's accelerators for one pod per node, or
+    /// `None` for CPU-only instances.
+    pub fn for_instance(instance: &InstanceType) -> Option<Self> {
+        let detail = instance.accelerator.as_ref()?;
+        let k8s_resource_name = instance.k8s_resource_name.clone().unwrap_or_else(|| {
+            match detail.accelerator_type {
+                AcceleratorType::Gpu => "nvidia.com/gpu",
+                AcceleratorType::Npu => "aws.amazon.com/neuron",
+                AcceleratorType::Tpu => "google.com/tpu",
+                AcceleratorType::Fpga | AcceleratorType::None => "",
+            }
+            .to_string()
+        });
+        Some(Self {
+            accelerator_type: detail.accelerator_type,
+            k8s_resource_name,
+            count_per_pod: detail.count,
+            device_name: detail.device_name.clone(),
+            mig_profile: None,
+        })
+    }
+}
 
-/// Returns the full instance catalog for a cloud provider.
-pub fn instance_catalog(provider: CloudProvider) -> Vec<InstanceFamily> {
-    match provider {
-        CloudProvider::Aws => aws_catalog(),
-        CloudProvider::Gcp => gcp_catalog(),
-        CloudProvider::Azure => azure_catalog(),
-        CloudProvider::OnPrem => on_prem_catalog(),
+impl WorkerPoolConfig {
+    /// A pool of `replicas` pods on `instance`. Accelerated pools use all
+    /// of the instance's accelerators, and are pinned to a
+    /// `megadb.io/node-pool: <type>-pool` node pool whose accelerator taint
+    /// they tolerate.
+    pub fn for_instance(instance: &InstanceType, replicas: i32) -> Self {
+        let accelerator = PoolAccelerator::for_instance(instance);
+        let (node_selector, tolerations) = match &accelerator {
+            Some(accel) => (
+                vec![(
+                    "megadb.io/node-pool".to_string(),
+                    format!("{}-pool", accel.accelerator_type.badge().to_lowercase()),
+                )],
+                vec![TolerationConfig {
+                    key: accel.k8s_resource_name.clone(),
+                    operator: "Exists".to_string(),
+                    value: None,
+                    effect: "NoSchedule".to_string(),
+                }],
+            ),
+            None => (vec![], vec![]),
+        };
+        Self {
+            instance_type: instance.name.clone(),
+            replicas,
+            min_replicas: replicas,
+            max_replicas: replicas,
+            accelerator,
+            node_selector,
+            tolerations,
+        }
     }
 }
 
-fn aws_catalog() -> Vec<InstanceFamily> {
-    vec![
-        InstanceFamily {
-            provider: CloudProvider::Aws,
-            family: "p5".to_string(),
-            description: "NVIDIA H100 Tensor Core — highest GPU performance".to_string(),
-            accelerator_type: AcceleratorType::Gpu,
-            instance_types: vec![InstanceType {
-                name: "p5.48xlarge".to_string(),
-                vcpus: 192,
-                memory_gb: 2048,
-                accelerator: Some(AcceleratorDetail {
-                    accelerator_type: AcceleratorType::Gpu,
-                    device_name: "NVIDIA H100 80GB".to_string(),
-                    count: 8,
-                    memory_gb: Some(80),
-                    compute_capability: Some("9.0".to_string()),
-                    supports_mig: true,
-                    mig_profiles: vec![
-                        "1g.10gb".to_string(),
-                        "2g.20gb".to_string(),
-                        "3g.40gb".to_string(),
-                        "7g.80gb".to_string(),
-                    ],
-                }),
-                network_gbps: Some(3200.0),
-                price_per_hour_usd: Some(98.32),
-                k8s_resource_name: Some("nvidia.com/gpu".to_string()),
-            }],
-        },
-        InstanceFamily {
-            provider: CloudProvider::Aws,
-            family: "p4d".to_string(),
-            description: "NVIDIA A100 Tensor Core — large-scale GPU compute".to_string(),
-            accelerator_type: AcceleratorType::Gpu,
-            instance_types: vec![InstanceType {
-                name: "p4d.24xlarge".to_string(),
-                vcpus: 96,
-                memory_gb: 1152,
-                accelerator: Some(AcceleratorDetail {
-                    accelerator_type: AcceleratorType::Gpu,
-                    device_name: "NVIDIA A100 40GB".to_string(),
-                    count: 8,
-                    memory_gb: Some(40),
-                    compute_capability: Some("8.0".to_string()),
-                    supports_mig: true,
-                    mig_profiles: vec![
-                        "1g.5gb".to_string(),
-                        "2g.10gb".to_string(),
-                        "3g.20gb".to_string(),
-                        "7g.40gb".to_string(),
-                    ],
-                }),
-                network_gbps: Some(400.0),
-                price_per_hour_usd: Some(32.77),
-                k8s_resource_name: Some("nvidia.com/gpu".to_string()),
-            }],
-        },
-        InstanceFamily {
-            provider: CloudProvider::Aws,
-            family: "g6".to_string(),
-            description: "NVIDIA L4 — cost-effective GPU inference".to_string(),
-            accelerator_type: AcceleratorType::Gpu,
-            instance_types: vec![
-                InstanceType {
-                    name: "g6.xlarge".to_string(),
-                    vcpus: 4,
-                    memory_gb: 16,
-                    accelerator: Some(AcceleratorDetail {
-                        accelerator_type: AcceleratorType::Gpu,
-                        device_name: "NVIDIA L4 24GB".to_string(),
-                        count: 1,
-                        memory_gb: Some(24),
-                        compute_capability: Some("8.9".to_string()),
-                        supports_mig: false,
-                        mig_profiles: vec![],
-                    }),
-                    network_gbps: Some(10.0),
-                    price_per_hour_usd: Some(0.805),
-                    k8s_resource_name: Some("nvidia.com/gpu".to_string()),
-                },
-                InstanceType {
-                    name: "g6.12xlarge".to_string(),
-                    vcpus: 48,
-                    memory_gb: 192,
-                    accelerator: Some(AcceleratorDetail {
-                        accelerator_type: AcceleratorType::Gpu,
-                        device_name: "NVIDIA L4 24GB".to_string(),
-                        count: 4,
-                        memory_gb: Some(24),
-                        compute_capability: Some("8.9".to_string()),
-                        supports_mig: false,
-                        mig_profiles: vec![],
-                    }),
-                    network_gbps: Some(40.0),
-                    price_per_hour_usd: Some(4.602),
-                    k8s_resource_name: Some("nvidia.com/gpu".to_string()),
-                },
-            ],
-        },
-        InstanceFamily {
-            provider: CloudProvider::Aws,
-            family: "g5".to_string(),
-            description: "NVIDIA A10G — mixed GPU workloads".to_string(),
-            accelerator_type: AcceleratorType::Gpu,
-            instance_types: vec![
-                InstanceType {
-                    name: "g5.xlarge".to_string(),
-                    vcpus: 4,
-                    memory_gb: 16,
-                    accelerator: Some(AcceleratorDetail {
-                        accelerator_type: AcceleratorType::Gpu,
-                        device_name: "NVIDIA A10G 24GB".to_string(),
-                        count: 1,
-                        memory_gb: Some(24),
-                        compute_capability: Some("8.6".to_string()),
-                        supports_mig: false,
-                        mig_profiles: vec![],
-                    }),
-                    network_gbps: Some(10.0),
-                    price_per_hour_usd: Some(1.006),
-                    k8s_resource_name: Some("nvidia.com/gpu".to_string()),
-                },
-                InstanceType {
-                    name: "g5.12xlarge".to_string(),
-                    vcpus: 48,
-                    memory_gb: 192,
-                    accelerator: Some(AcceleratorDetail {
-                        accelerator_type: AcceleratorType::Gpu,
-                        device_name: "NVIDIA A10G 24GB".to_string(),
-                        count: 4,
-                        memory_gb: Some(24),
-                        compute_capability: Some("8.6".to_string()),
-                        supports_mig: false,
-                        mig_profiles: vec![],
-                    }),
-                    network_gbps: Some(40.0),
-                    price_per_hour_usd: Some(5.672),
-                    k8s_resource_name: Some("nvidia.com/gpu".to_string()),
-                },
-            ],
-        },
-        InstanceFamily {
-            provider: CloudProvider::Aws,
-            family: "f2".to_string(),
-            description: "Xilinx FPGA — custom hardware acceleration".to_string(),
-            accelerator_type: AcceleratorType::Fpga,
-            instance_types: vec![InstanceType {
-                name: "f2.6xlarge".to_string(),
-                vcpus: 24,
-                memory_gb: 192,
-                accelerator: Some(AcceleratorDetail {
-                    accelerator_type: AcceleratorType::Fpga,
-                    device_name: "Xilinx Alveo U250".to_string(),
-                    count: 2,
-                    memory_gb: Some(64),
-                    compute_capability: None,
-                    supports_mig: false,
-                    mig_profiles: vec![],
-                }),
-                network_gbps: Some(25.0),
-                price_per_hour_usd: Some(9.90),
-                k8s_resource_name: Some("xilinx.com/fpga-u250".to_string()),
-            }],
-        },
-        InstanceFamily {
-            provider: CloudProvider::Aws,
-            family: "inf1".to_string(),
-            description: "AWS Inferentia (1st gen) — budget NPU inference".to_string(),
-            accelerator_type: AcceleratorType::Npu,
-            instance_types: vec![
-                InstanceType {
-                    name: "inf1.xlarge".to_string(),
-                    vcpus: 4,
-                    memory_gb: 8,
-                    accelerator: Some(AcceleratorDetail {
-                        accelerator_type: AcceleratorType::Npu,
-                        device_name: "AWS Inferentia".to_string(),
-                        count: 1,
-                        memory_gb: Some(8),
-                        compute_capability: None,
-                        supports_mig: false,
-                        mig_profiles: vec![],
-                    }),
-                    network_gbps: Some(25.0),
-                    price_per_hour_usd: Some(0.228),
-                    k8s_resource_name: Some("aws.amazon.com/neuron".to_string()),
-                },
-                InstanceType {
-                    name: "inf1.6xlarge".to_string(),
-                    vcpus: 24,
-                    memory_gb: 48,
-                    accelerator: Some(AcceleratorDetail {
-                        accelerator_type: AcceleratorType::Npu,
-                        device_name: "AWS Inferentia".to_string(),
-                        count: 4,
-                        memory_gb: Some(8),
-                        compute_capability: None,
-                        supports_mig: false,
-                        mig_profiles: vec![],
-                    }),
-                    network_gbps: Some(25.0),
-                    price_per_hour_usd: Some(1.18),
-                    k8s_resource_name: Some("aws.amazon.com/neuron".to_string()),
-                },
-                InstanceType {
-                    name: "inf1.24xlarge".to_string(),
-                    vcpus: 96,
-                    memory_gb: 192,
-                    accelerator: Some(AcceleratorDetail {
-                        accelerator_type: AcceleratorType::Npu,
-                        device_name: "AWS Inferentia".to_string(),
-                        count: 16,
-                        memory_gb: Some(8),
-                        compute_capability: None,
-                        supports_mig: false,
-                        mig_profiles: vec![],
-                    }),
-                    network_gbps: Some(100.0),
-                    price_per_hour_usd: Some(4.72),
-                    k8s_resource_name: Some("aws.amazon.com/neuron".to_string()),
-                },
-            ],
-        },
-        InstanceFamily {
-            provider: CloudProvider::Aws,
-            family: "inf2".to_string(),
-            description: "AWS Inferentia2 — NPU for ML inference (NeuronCore-v2)".to_string(),
-            accelerator_type: AcceleratorType::Npu,
-            instance_types: vec![
-                InstanceType {
-                    name: "inf2.xlarge".to_string(),
-                    vcpus: 4,
-                    memory_gb: 16,
-                    accelerator: Some(AcceleratorDetail {
-                        accelerator_type: AcceleratorType::Npu,
-                        device_name: "AWS Inferentia2".to_string(),
-                        count: 1,
-                        memory_gb: Some(32),
-                        compute_capability: None,
-                        supports_mig: false,
-                        mig_profiles: vec![],
-                    }),
-                    network_gbps: Some(15.0),
-                    price_per_hour_usd: Some(0.758),
-                    k8s_resource_name: Some("aws.amazon.com/neuron".to_string()),
-                },
-                InstanceType {
-                    name: "inf2.8xlarge".to_string(),
-                    vcpus: 32,
-                    memory_gb: 128,
-                    accelerator: Some(AcceleratorDetail {
-                        accelerator_type: AcceleratorType::Npu,
-                        device_name: "AWS Inferentia2".to_string(),
-                        count: 1,
-                        memory_gb: Some(32),
-                        compute_capability: None,
-                        supports_mig: false,
-                        mig_profiles: vec![],
-                    }),
-                    network_gbps: Some(25.0),
-                    price_per_hour_usd: Some(1.97),
-                    k8s_resource_name: Some("aws.amazon.com/neuron".to_string()),
-                },
-                InstanceType {
-                    name: "inf2.24xlarge".to_string(),
-                    vcpus: 96,
-                    memory_gb: 384,
-                    accelerator: Some(AcceleratorDetail {
-                        accelerator_type: AcceleratorType::Npu,
-                        device_name: "AWS Inferentia2".to_string(),
-                        count: 6,
-                        memory_gb: Some(32),
-                        compute_capability: None,
-                        supports_mig: false,
-                        mig_profiles: vec![],
-                    }),
-                    network_gbps: Some(50.0),
-                    price_per_hour_usd: Some(6.49),
-                    k8s_resource_name: Some("aws.amazon.com/neuron".to_string()),
-                },
-                InstanceType {
-                    name: "inf2.48xlarge".to_string(),
-                    vcpus: 192,
-                    memory_gb: 768,
-                    accelerator: Some(AcceleratorDetail {
-                        accelerator_type: AcceleratorType::Npu,
-                        device_name: "AWS Inferentia2".to_string(),
-                        count: 12,
-                        memory_gb: Some(32),
-                        compute_capability: None,
-                        supports_mig: false,
-                        mig_profiles: vec![],
-                    }),
-                    network_gbps: Some(100.0),
-                    price_per_hour_usd: Some(12.98),
-                    k8s_resource_name: Some("aws.amazon.com/neuron".to_string()),
-                },
-            ],
-        },
-        InstanceFamily {
-            provider: CloudProvider::Aws,
-            family: "trn1".to_string(),
-            description: "AWS Trainium (1st gen) — ML training acceleration".to_string(),
-            accelerator_type: AcceleratorType::Npu,
-            instance_types: vec![
-                InstanceType {
-                    name: "trn1.2xlarge".to_string(),
-                    vcpus: 8,
-                    memory_gb: 32,
-                    accelerator: Some(AcceleratorDetail {
-                        accelerator_type: AcceleratorType::Npu,
-                        device_name: "AWS Trainium".to_string(),
-                        count: 1,
-                        memory_gb: Some(32),
-                        compute_capability: None,
-                        supports_mig: false,
-                        mig_profiles: vec![],
-                    }),
-                    network_gbps: Some(25.0),
-                    price_per_hour_usd: Some(1.34),
-                    k8s_resource_name: Some("aws.amazon.com/neuron".to_string()),
-                },
-                InstanceType {
-                    name: "trn1.32xlarge".to_string(),
-                    vcpus: 128,
-                    memory_gb: 512,
-                    accelerator: Some(AcceleratorDetail {
-                        accelerator_type: AcceleratorType::Npu,
-                        device_name: "AWS Trainium".to_string(),
-                        count: 16,
-                        memory_gb: Some(32),
-                        compute_capability: None,
-                        supports_mig: false,
-                        mig_profiles: vec![],
-                    }),
-                    network_gbps: Some(800.0),
-                    price_per_hour_usd: Some(21.50),
-                    k8s_resource_name: Some("aws.amazon.com/neuron".to_string()),
-                },
-                InstanceType {
-                    name: "trn1n.32xlarge".to_string(),
-                    vcpus: 128,
-                    memory_gb: 512,
-                    accelerator: Some(AcceleratorDetail {
-                        accelerator_type: AcceleratorType::Npu,
-                        device_name: "AWS Trainium".to_string(),
-                        count: 16,
-                        memory_gb: Some(32),
-                        compute_capability: None,
-                        supports_mig: false,
-                        mig_profiles: vec![],
-                    }),
-                    network_gbps: Some(1600.0),
-                    price_per_hour_usd: Some(24.78),
-                    k8s_resource_name: Some("aws.amazon.com/neuron".to_string()),
-                },
-            ],
-        },
-        InstanceFamily {
-            provider: CloudProvider::Aws,
-            family: "trn2".to_string(),
-            description: "AWS Trainium2 — next-gen ML training (NeuronCore-v3, HBM3)".to_string(),
-            accelerator_type: AcceleratorType::Npu,
-            instance_types: vec![InstanceType {
-                name: "trn2.48xlarge".to_string(),
-                vcpus: 192,
-                memory_gb: 2048,
-                accelerator: Some(AcceleratorDetail {
-                    accelerator_type: AcceleratorType::Npu,
-                    device_name: "AWS Trainium2".to_string(),
-                    count: 16,
-                    memory_gb: Some(96),
-                    compute_capability: None,
-                    supports_mig: false,
-                    mig_profiles: vec![],
-                }),
-                network_gbps: Some(3200.0),
-                price_per_hour_usd: Some(21.50),
-                k8s_resource_name: Some("aws.amazon.com/neuron".to_string()),
-            }],
-        },
-    ]
+impl DeploymentConfig {
+    /// A starting point for `provider`: three CPU workers on the catalog's
+    /// first CPU-only instance type, autoscaled by KEDA, and no accelerator
+    /// pools.
+    pub fn default_for(provider: CloudProvider, catalog: &[InstanceFamily]) -> Self {
+        let instance_type = catalog
+            .iter()
+            .filter(|f| f.accelerator_type == AcceleratorType::None)
+            .flat_map(|f| &f.instance_types)
+            .map(|t| t.name.clone())
+            .next()
+            .unwrap_or_default();
+        Self {
+            cluster_name: "megadb-cluster".to_string(),
+            provider,
+            cpu_worker_pool: WorkerPoolConfig {
+                instance_type,
+                replicas: 3,
+                min_replicas: 2,
+                max_replicas: 10,
+                accelerator: None,
+                node_selector: vec![],
+                tolerations: vec![],
+            },
+            gpu_worker_pool: None,
+            fpga_worker_pool: None,
+            npu_worker_pool: None,
+            storage_backend: StorageBackend::default_for(provider),
+            keda_enabled: true,
+            estimated_monthly_cost_usd: 0.0,
+            preserved: PreservedFields::default(),
+        }
+    }
+
+    /// The configured worker pools with display labels, CPU workers first.
+    pub fn pools(&self) -> Vec<(&'static str, &WorkerPoolConfig)> {
+        let mut pools = vec![("CPU workers", &self.cpu_worker_pool)];
+        let optional = [
+            ("GPU workers", &self.gpu_worker_pool),
+            ("FPGA workers", &self.fpga_worker_pool),
+            ("NPU workers", &self.npu_worker_pool),
+        ];
+        for (label, pool) in optional {
+            if let Some(pool) = pool {
+                pools.push((label, pool));
+            }
+        }
+        pools
+    }
 }
 
-fn gcp_catalog() -> Vec<InstanceFamily> {
-    vec![
-        InstanceFamily {
-            provider: CloudProvider::Gcp,
-            family: "a3-highgpu".to_string(),
-            description: "NVIDIA H100 — highest GPU compute on GCP".to_string(),
-            accelerator_type: AcceleratorType::Gpu,
-            instance_types: vec![InstanceType {
-                name: "a3-highgpu-8g".to_string(),
-                vcpus: 208,
-                memory_gb: 1872,
-                accelerator: Some(AcceleratorDetail {
-                    accelerator_type: AcceleratorType::Gpu,
-                    device_name: "NVIDIA H100 80GB".to_string(),
-                    count: 8,
-                    memory_gb: Some(80),
-                    compute_capability: Some("9.0".to_string()),
-                    supports_mig: true,
-                    mig_profiles: vec![
-                        "1g.10gb".to_string(),
-                        "2g.20gb".to_string(),
-                        "3g.40gb".to_string(),
-                        "7g.80gb".to_string(),
-                    ],
-                }),
-                network_gbps: Some(1600.0),
-                price_per_hour_usd: Some(98.82),
-                k8s_resource_name: Some("nvidia.com/gpu".to_string()),
-            }],
-        },
-        InstanceFamily {
-            provider: CloudProvider::Gcp,
-            family: "a2-highgpu".to_string(),
-            description: "NVIDIA A100 — general-purpose GPU compute".to_string(),
-            accelerator_type: AcceleratorType::Gpu,
-            instance_types: vec![
-                InstanceType {
-                    name: "a2-highgpu-1g".to_string(),
-                    vcpus: 12,
-                    memory_gb: 85,
-                    accelerator: Some(AcceleratorDetail {
-                        accelerator_type: AcceleratorType::Gpu,
-                        device_name: "NVIDIA A100 40GB".to_string(),
-                        count: 1,
-                        memory_gb: Some(40),
-                        compute_capability: Some("8.0".to_string()),
-                        supports_mig: true,
-                        mig_profiles: vec![
-                            "1g.5gb".to_string(),
-                            "2g.10gb".to_string(),
-                            "3g.20gb".to_string(),
-                        ],
-                    }),
-                    network_gbps: Some(24.0),
-                    price_per_hour_usd: Some(3.67),
-                    k8s_resource_name: Some("nvidia.com/gpu".to_string()),
-                },
-                InstanceType {
-                    name: "a2-highgpu-4g".to_string(),
-                    vcpus: 48,
-                    memory_gb: 340,
-                    accelerator: Some(AcceleratorDetail {
-                        accelerator_type: AcceleratorType::Gpu,
-                        device_name: "NVIDIA A100 40GB".to_string(),
-                        count: 4,
-                        memory_gb: Some(40),
-                        compute_capability: Some("8.0".to_string()),
-                        supports_mig: true,
-                        mig_profiles: vec![
-                            "1g.5gb".to_string(),
-                            "2g.10gb".to_string(),
-                            "3g.20gb".to_string(),
-                        ],
-                    }),
-                    network_gbps: Some(50.0),
-                    price_per_hour_usd: Some(14.69),
-                    k8s_resource_name: Some("nvidia.com/gpu".to_string()),
-                },
-            ],
-        },
-        InstanceFamily {
-            provider: CloudProvider::Gcp,
-            family: "g2-standard".to_string(),
-            description: "NVIDIA L4 — cost-effective inference on GCP".to_string(),
-            accelerator_type: AcceleratorType::Gpu,
-            instance_types: vec![
-                InstanceType {
-                    name: "g2-standard-4".to_string(),
-                    vcpus: 4,
-                    memory_gb: 16,
-                    accelerator: Some(AcceleratorDetail {
-                        accelerator_type: AcceleratorType::Gpu,
-                        device_name: "NVIDIA L4 24GB".to_string(),
-                        count: 1,
-                        memory_gb: Some(24),
-                        compute_capability: Some("8.9".to_string()),
-                        supports_mig: false,
-                        mig_profiles: vec![],
-                    }),
-                    network_gbps: Some(10.0),
-                    price_per_hour_usd: Some(0.84),
-                    k8s_resource_name: Some("nvidia.com/gpu".to_string()),
-                },
-                InstanceType {
-                    name: "g2-standard-48".to_string(),
-                    vcpus: 48,
-                    memory_gb: 192,
-                    accelerator: Some(AcceleratorDetail {
-                        accelerator_type: AcceleratorType::Gpu,
-                        device_name: "NVIDIA L4 24GB".to_string(),
-                        count: 4,
-                        memory_gb: Some(24),
-                        compute_capability: Some("8.9".to_string()),
-                        supports_mig: false,
-                        mig_profiles: vec![],
-                    }),
-                    network_gbps: Some(32.0),
-                    price_per_hour_usd: Some(5.21),
-                    k8s_resource_name: Some("nvidia.com/gpu".to_string()),
-                },
-            ],
-        },
-        InstanceFamily {
-            provider: CloudProvider::Gcp,
-            family: "TPU v4".to_string(),
-            description: "Google TPU v4 — high-performance ML training/inference".to_string(),
-            accelerator_type: AcceleratorType::Tpu,
-            instance_types: vec![InstanceType {
-                name: "ct4p-hightpu-4t".to_string(),
-                vcpus: 240,
-                memory_gb: 407,
-                accelerator: Some(AcceleratorDetail {
-                    accelerator_type: AcceleratorType::Tpu,
-                    device_name: "TPU v4".to_string(),
-                    count: 4,
-                    memory_gb: Some(32),
-                    compute_capability: None,
-                    supports_mig: false,
-                    mig_profiles: vec![],
-                }),
-                network_gbps: Some(200.0),
-                price_per_hour_usd: Some(12.88),
-                k8s_resource_name: Some("google.com/tpu".to_string()),
-            }],
-        },
-        InstanceFamily {
-            provider: CloudProvider::Gcp,
-            family: "TPU v5e".to_string(),
-            description: "Google TPU v5e — cost-effective ML inference".to_string(),
-            accelerator_type: AcceleratorType::Tpu,
-            instance_types: vec![
-                InstanceType {
-                    name: "ct5lp-hightpu-1t".to_string(),
-                    vcpus: 24,
-                    memory_gb: 48,
-                    accelerator: Some(AcceleratorDetail {
-                        accelerator_type: AcceleratorType::Tpu,
-                        device_name: "TPU v5e".to_string(),
-                        count: 1,
-                        memory_gb: Some(16),
-                        compute_capability: None,
-                        supports_mig: false,
-                        mig_profiles: vec![],
-                    }),
-                    network_gbps: Some(100.0),
-                    price_per_hour_usd: Some(1.20),
-                    k8s_resource_name: Some("google.com/tpu".to_string()),
-                },
-                InstanceType {
-                    name: "ct5lp-hightpu-4t".to_string(),
-                    vcpus: 120,
-                    memory_gb: 192,
-                    accelerator: Some(AcceleratorDetail {
-                        accelerator_type: AcceleratorType::Tpu,
-                        device_name: "TPU v5e".to_string(),
-                        count: 4,
-                        memory_gb: Some(16),
-                        compute_capability: None,
-                        supports_mig: false,
-                        mig_profiles: vec![],
-                    }),
-                    network_gbps: Some(200.0),
-                    price_per_hour_usd: Some(4.80),
-                    k8s_resource_name: Some("google.com/tpu".to_string()),
-                },
-                InstanceType {
-                    name: "ct5lp-hightpu-8t".to_string(),
-                    vcpus: 224,
-                    memory_gb: 384,
-                    accelerator: Some(AcceleratorDetail {
-                        accelerator_type: AcceleratorType::Tpu,
-                        device_name: "TPU v5e".to_string(),
-                        count: 8,
-                        memory_gb: Some(16),
-                        compute_capability: None,
-                        supports_mig: false,
-                        mig_profiles: vec![],
-                    }),
-                    network_gbps: Some(400.0),
-                    price_per_hour_usd: Some(9.60),
-                    k8s_resource_name: Some("google.com/tpu".to_string()),
-                },
-            ],
-        },
-        InstanceFamily {
-            provider: CloudProvider::Gcp,
-            family: "TPU v5p".to_string(),
-            description: "Google TPU v5p — high-performance ML training".to_string(),
-            accelerator_type: AcceleratorType::Tpu,
-            instance_types: vec![InstanceType {
-                name: "ct5p-hightpu-4t".to_string(),
-                vcpus: 208,
-                memory_gb: 448,
-                accelerator: Some(AcceleratorDetail {
-                    accelerator_type: AcceleratorType::Tpu,
-                    device_name: "TPU v5p".to_string(),
-                    count: 4,
-                    memory_gb: Some(95),
-                    compute_capability: None,
-                    supports_mig: false,
-                    mig_profiles: vec![],
-                }),
-                network_gbps: Some(400.0),
-                price_per_hour_usd: Some(16.80),
-                k8s_resource_name: Some("google.com/tpu".to_string()),
-            }],
-        },
-        InstanceFamily {
-            provider: CloudProvider::Gcp,
-            family: "TPU v6e".to_string(),
-            description: "Google TPU v6e (Trillium) — latest gen, 67% more efficient".to_string(),
-            accelerator_type: AcceleratorType::Tpu,
-            instance_types: vec![
-                InstanceType {
-                    name: "ct6e-standard-1t".to_string(),
-                    vcpus: 44,
-                    memory_gb: 176,
-                    accelerator: Some(AcceleratorDetail {
-                        accelerator_type: AcceleratorType::Tpu,
-                        device_name: "TPU v6e (Trillium)".to_string(),
-                        count: 1,
-                        memory_gb: Some(32),
-                        compute_capability: None,
-                        supports_mig: false,
-                        mig_profiles: vec![],
-                    }),
-                    network_gbps: Some(100.0),
-                    price_per_hour_usd: Some(2.00),
-                    k8s_resource_name: Some("google.com/tpu".to_string()),
-                },
-                InstanceType {
-                    name: "ct6e-standard-4t".to_string(),
-                    vcpus: 176,
-                    memory_gb: 704,
-                    accelerator: Some(AcceleratorDetail {
-                        accelerator_type: AcceleratorType::Tpu,
-                        device_name: "TPU v6e (Trillium)".to_string(),
-                        count: 4,
-                        memory_gb: Some(32),
-                        compute_capability: None,
-                        supports_mig: false,
-                        mig_profiles: vec![],
-                    }),
-                    network_gbps: Some(200.0),
-                    price_per_hour_usd: Some(8.00),
-                    k8s_resource_name: Some("google.com/tpu".to_string()),
-                },
-                InstanceType {
-                    name: "ct6e-standard-8t".to_string(),
-                    vcpus: 360,
-                    memory_gb: 1408,
-                    accelerator: Some(AcceleratorDetail {
-                        accelerator_type: AcceleratorType::Tpu,
-                        device_name: "TPU v6e (Trillium)".to_string(),
-                        count: 8,
-                        memory_gb: Some(32),
-                        compute_capability: None,
-                        supports_mig: false,
-                        mig_profiles: vec![],
-                    }),
-                    network_gbps: Some(400.0),
-                    price_per_hour_usd: Some(16.00),
-                    k8s_resource_name: Some("google.com/tpu".to_string()),
-                },
-            ],
-        },
-    ]
+// ─── Instance Catalog Files ─────────────────────────────────────────────────
+
+/// Catalog file format understood by [`InstanceCatalog::from_json`].
+pub const CATALOG_SCHEMA_VERSION: u32 = 1;
+
+/// A provider's instance catalog, as stored in a catalog file. The bundled
+/// files live in `catalog/`; the server can override them per provider.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceCatalog {
+    pub schema_version: u32,
+    pub provider: CloudProvider,
+    /// Free-form version of the catalog contents, e.g. "2026.10".
+    pub catalog_version: String,
+    /// Date the prices were last checked, as YYYY-MM-DD.
+    pub last_updated: String,
+    pub families: Vec<InstanceFamily>,
 }
 
-fn azure_catalog() -> Vec<InstanceFamily> {
-    vec![
-        InstanceFamily {
-            provider: CloudProvider::Azure,
-            family: "ND H100 v5".to_string(),
-            description: "NVIDIA H100 — Azure's highest GPU compute".to_string(),
-            accelerator_type: AcceleratorType::Gpu,
-            instance_types: vec![InstanceType {
-                name: "Standard_ND96isr_H100_v5".to_string(),
-                vcpus: 96,
-                memory_gb: 1900,
-                accelerator: Some(AcceleratorDetail {
-                    accelerator_type: AcceleratorType::Gpu,
-                    device_name: "NVIDIA H100 80GB".to_string(),
-                    count: 8,
-                    memory_gb: Some(80),
-                    compute_capability: Some("9.0".to_string()),
-                    supports_mig: true,
-                    mig_profiles: vec![
-                        "1g.10gb".to_string(),
-                        "2g.20gb".to_string(),
-                        "3g.40gb".to_string(),
-                        "7g.80gb".to_string(),
-                    ],
-                }),
-                network_gbps: Some(3200.0),
-                price_per_hour_usd: Some(98.32),
-                k8s_resource_name: Some("nvidia.com/gpu".to_string()),
-            }],
-        },
-        InstanceFamily {
-            provider: CloudProvider::Azure,
-            family: "NC A100 v4".to_string(),
-            description: "NVIDIA A100 — general GPU compute on Azure".to_string(),
-            accelerator_type: AcceleratorType::Gpu,
-            instance_types: vec![
-                InstanceType {
-                    name: "Standard_NC24ads_A100_v4".to_string(),
-                    vcpus: 24,
-                    memory_gb: 220,
-                    accelerator: Some(AcceleratorDetail {
-                        accelerator_type: AcceleratorType::Gpu,
-                        device_name: "NVIDIA A100 80GB".to_string(),
-                        count: 1,
-                        memory_gb: Some(80),
-                        compute_capability: Some("8.0".to_string()),
-                        supports_mig: true,
-                        mig_profiles: vec![
-                            "1g.10gb".to_string(),
-                            "2g.20gb".to_string(),
-                            "3g.40gb".to_string(),
-                        ],
-                    }),
-                    network_gbps: Some(12.5),
-                    price_per_hour_usd: Some(3.67),
-                    k8s_resource_name: Some("nvidia.com/gpu".to_string()),
-                },
-                InstanceType {
-                    name: "Standard_NC96ads_A100_v4".to_string(),
-                    vcpus: 96,
-                    memory_gb: 880,
-                    accelerator: Some(AcceleratorDetail {
-                        accelerator_type: AcceleratorType::Gpu,
-                        device_name: "NVIDIA A100 80GB".to_string(),
-                        count: 4,
-                        memory_gb: Some(80),
-                        compute_capability: Some("8.0".to_string()),
-                        supports_mig: true,
-                        mig_profiles: vec![
-                            "1g.10gb".to_string(),
-                            "2g.20gb".to_string(),
-                            "3g.40gb".to_string(),
-                        ],
-                    }),
-                    network_gbps: Some(40.0),
-                    price_per_hour_usd: Some(14.69),
-                    k8s_resource_name: Some("nvidia.com/gpu".to_string()),
-                },
-            ],
-        },
-        InstanceFamily {
-            provider: CloudProvider::Azure,
-            family: "NC T4 v3".to_string(),
-            description: "NVIDIA T4 — budget GPU for inference".to_string(),
-            accelerator_type: AcceleratorType::Gpu,
-            instance_types: vec![InstanceType {
-                name: "Standard_NC4as_T4_v3".to_string(),
-                vcpus: 4,
-                memory_gb: 28,
-                accelerator: Some(AcceleratorDetail {
-                    accelerator_type: AcceleratorType::Gpu,
-                    device_name: "NVIDIA T4 16GB".to_string(),
-                    count: 1,
-                    memory_gb: Some(16),
-                    compute_capability: Some("7.5".to_string()),
-                    supports_mig: false,
-                    mig_profiles: vec![],
-                }),
-                network_gbps: Some(8.0),
-                price_per_hour_usd: Some(0.526),
-                k8s_resource_name: Some("nvidia.com/gpu".to_string()),
-            }],
-        },
-        InstanceFamily {
-            provider: CloudProvider::Azure,
-            family: "NP v2".to_string(),
-            description: "Xilinx Alveo U250 FPGA — custom acceleration".to_string(),
-            accelerator_type: AcceleratorType::Fpga,
-            instance_types: vec![InstanceType {
-                name: "Standard_NP40s_v2".to_string(),
-                vcpus: 40,
-                memory_gb: 672,
-                accelerator: Some(AcceleratorDetail {
-                    accelerator_type: AcceleratorType::Fpga,
-                    device_name: "Xilinx Alveo U250".to_string(),
-                    count: 2,
-                    memory_gb: Some(64),
-                    compute_capability: None,
-                    supports_mig: false,
-                    mig_profiles: vec![],
-                }),
-                network_gbps: Some(20.0),
-                price_per_hour_usd: Some(11.22),
-                k8s_resource_name: Some("xilinx.com/fpga-u250".to_string()),
-            }],
-        },
-    ]
+/// Why a catalog file was rejected.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CatalogError {
+    /// Not JSON, or not shaped like a catalog.
+    Parse(String),
+    UnsupportedSchema(u32),
+    /// Well-formed, but inconsistent.
+    Invalid(String),
 }
 
-fn on_prem_catalog() -> Vec<InstanceFamily> {
-    vec![InstanceFamily {
-        provider: CloudProvider::OnPrem,
-        family: "Custom".to_string(),
-        description: "On-premises nodes with manually configured accelerators".to_string(),
-        accelerator_type: AcceleratorType::None,
-        instance_types: vec![InstanceType {
-            name: "custom-node".to_string(),
-            vcpus: 0,
-            memory_gb: 0,
-            accelerator: None,
-            network_gbps: None,
-            price_per_hour_usd: None,
-            k8s_resource_name: None,
-        }],
-    }]
+impl std::fmt::Display for CatalogError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse(e) => write!(f, "Invalid catalog file: {e}"),
+            Self::UnsupportedSchema(v) => write!(
+                f,
+                "Unsupported catalog schema_version {v} (expected {CATALOG_SCHEMA_VERSION})"
+            ),
+            Self::Invalid(e) => write!(f, "Invalid catalog: {e}"),
+        }
+    }
 }
 
-// ─── CRD YAML Generation ────────────────────────────────────────────────────
+impl std::error::Error for CatalogError {}
 
-impl DeploymentConfig {
-    /// Generate a MegaDB CRD YAML snippet from this deployment configuration.
-    pub fn to_crd_yaml(&self) -> String {
-        let mut yaml = format!(
-            r#"apiVersion: megadb.io/v1alpha1
-kind: MegaDB
-metadata:
-  name: {}
-spec:
-  replicas: {}
-  version: "latest"
-  storage:
-    storage_type: {}
-"#,
-            self.cluster_name,
-            self.cpu_worker_pool.replicas,
-            match self.storage_backend {
-                StorageBackend::S3 => "s3",
-                StorageBackend::Gcs => "gcs",
-                StorageBackend::AzureBlob => "azure",
-                StorageBackend::Local => "local",
-            }
-        );
+impl InstanceCatalog {
+    /// Parse and validate a catalog file.
+    pub fn from_json(json: &str) -> Result<Self, CatalogError> {
+        let catalog: Self =
+            serde_json::from_str(json).map_err(|e| CatalogError::Parse(e.to_string()))?;
+        catalog.check()?;
+        Ok(catalog)
+    }
 
-        if let Some(ref gpu_pool) = self.gpu_worker_pool {
-            yaml.push_str("  gpu_workers:\n");
-            yaml.push_str(&format!("    replicas: {}\n", gpu_pool.replicas));
-            if let Some(ref accel) = gpu_pool.accelerator {
-                yaml.push_str("    accelerators:\n");
-                yaml.push_str("      gpu:\n");
-                yaml.push_str(&format!("        count: {}\n", accel.count_per_pod));
-                yaml.push_str(&format!("        product: \"{}\"\n", accel.device_name));
-                if let Some(ref mig) = accel.mig_profile {
-                    yaml.push_str(&format!("        mig_profile: \"{mig}\"\n"));
-                }
+    /// The catalog shipped with the dashboard.
+    pub fn bundled(provider: CloudProvider) -> Self {
+        let json = match provider {
+            CloudProvider::Aws => include_str!("../catalog/aws.json"),
+            CloudProvider::Gcp => include_str!("../catalog/gcp.json"),
+            CloudProvider::Azure => include_str!("../catalog/azure.json"),
+            CloudProvider::OnPrem => include_str!("../catalog/on_prem.json"),
+        };
+        Self::from_json(json).expect("bundled catalog is valid")
+    }
+
+    /// Check the rules the configurator relies on: instance names are
+    /// unique, and accelerator details agree with their family.
+    pub fn check(&self) -> Result<(), CatalogError> {
+        if self.schema_version != CATALOG_SCHEMA_VERSION {
+            return Err(CatalogError::UnsupportedSchema(self.schema_version));
+        }
+        let invalid = |msg: String| Err(CatalogError::Invalid(msg));
+        if self.catalog_version.trim().is_empty() {
+            return invalid("catalog_version is empty".into());
+        }
+        if chrono::NaiveDate::parse_from_str(&self.last_updated, "%Y-%m-%d").is_err() {
+            return invalid(format!(
+                "last_updated \"{}\" is not a YYYY-MM-DD date",
+                self.last_updated
+            ));
+        }
+
+        if self.families.is_empty() {
+            return invalid("catalog has no families".into());
+        }
+        let negative = |v: Option<f64>| v.is_some_and(|v| !(v >= 0.0 && v.is_finite()));
+        let mut names = std::collections::HashSet::new();
+        for family in &self.families {
+            let name = &family.family;
+            if name.trim().is_empty() {
+                return invalid("a family has an empty name".into());
             }
-            if !gpu_pool.node_selector.is_empty() {
-                yaml.push_str("    node_selector:\n");
-                for (k, v) in &gpu_pool.node_selector {
-                    yaml.push_str(&format!("      {k}: \"{v}\"\n"));
-                }
+            if family.provider != self.provider {
+                return invalid(format!(
+                    "family {name} is for {}, not {}",
+                    family.provider.label(),
+                    self.provider.label()
+                ));
+            }
+            if family.instance_types.is_empty() {
+                return invalid(format!("family {name} has no instance types"));
             }
-            if !gpu_pool.tolerations.is_empty() {
-                yaml.push_str("    tolerations:\n");
-                for t in &gpu_pool.tolerations {
-                    yaml.push_str(&format!("      - key: \"{}\"\n", t.key));
-                    yaml.push_str(&format!("        operator: \"{}\"\n", t.operator));
-                    if let Some(ref v) = t.value {
-                        yaml.push_str(&format!("        value: \"{v}\"\n"));
+            for instance in &family.instance_types {
+                let it = &instance.name;
+                if it.trim().is_empty() {
+                    return invalid(format!("family {name} has an instance with an empty name"));
+                }
+                if !names.insert(it.as_str()) {
+                    return invalid(format!("instance type {it} is listed twice"));
+                }
+                if negative(instance.price_per_hour_usd) || negative(instance.network_gbps) {
+                    return invalid(format!("{it}: price and network must be non-negative"));
+                }
+                match (&instance.accelerator, family.accelerator_type) {
+                    (None, AcceleratorType::None) => {}
+                    (None, expected) => {
+                        return invalid(format!(
+                            "{it}: {} family without accelerator",
+                            expected.label()
+                        ));
+                    }
+                    (Some(_), AcceleratorType::None) => {
+                        return invalid(format!("{it}: accelerator in a CPU-only family"));
+                    }
+                    (Some(acc), expected) => {
+                        if acc.accelerator_type != expected {
+                            return invalid(format!(
+                                "{it}: {} accelerator in a {} family",
+                                acc.accelerator_type.label(),
+                                expected.label()
+                            ));
+                        }
+                        if acc.count == 0 {
+                            return invalid(format!("{it}: accelerator count is 0"));
+                        }
+                        if instance.k8s_resource_name.is_none() {
+                            return invalid(format!("{it}: accelerator without k8s_resource_name"));
+                        }
+                        if !acc.supports_mig && !acc.mig_profiles.is_empty() {
+                            return invalid(format!("{it}: mig_profiles without supports_mig"));
+                        }
                     }
-                    yaml.push_str(&format!("        effect: \"{}\"\n", t.effect));
                 }
             }
         }
+        Ok(())
+    }
+}
 
-        if let Some(ref fpga_pool) = self.fpga_worker_pool {
-            if let Some(ref accel) = fpga_pool.accelerator {
-                yaml.push_str("  accelerators:\n");
-                yaml.push_str("    fpga:\n");
-                yaml.push_str(&format!(
-                    "      resource_name: \"{}\"\n",
-                    accel.k8s_resource_name
-                ));
-                yaml.push_str(&format!("      count: {}\n", accel.count_per_pod));
+/// Returns the bundled instance catalog for a cloud provider.
+pub fn instance_catalog(provider: CloudProvider) -> Vec<InstanceFamily> {
+    InstanceCatalog::bundled(provider).families
+}
+
+// ─── Running Cluster Cost ───────────────────────────────────────────────────
+
+/// Hours in an average month (365 × 24 / 12), for monthly costs.
+pub const HOURS_PER_MONTH: f64 = 730.0;
+
+/// A detected node resolved against the instance catalog.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeCatalogMatch {
+    pub node_name: String,
+    /// The node's instance type label, if it has one.
+    pub instance_type: Option<String>,
+    /// Catalog family of the instance type, e.g. "g6".
+    pub family: Option<String>,
+    /// `None` when the instance type isn't in the catalog.
+    pub instance: Option<InstanceType>,
+}
+
+impl NodeCatalogMatch {
+    /// On-demand hourly price, when the instance type is known and priced.
+    pub fn price_per_hour_usd(&self) -> Option<f64> {
+        self.instance.as_ref()?.price_per_hour_usd
+    }
+}
+
+/// What the detected cluster is running and what it costs at on-demand prices.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ClusterCostSummary {
+    pub nodes: Vec<NodeCatalogMatch>,
+    /// Sum over priced nodes only.
+    pub hourly_cost_usd: f64,
+    pub monthly_cost_usd: f64,
+    /// Nodes whose instance type isn't in the catalog.
+    pub unknown_nodes: usize,
+    /// Nodes found in the catalog without a price (e.g. on-premises).
+    pub unpriced_nodes: usize,
+}
+
+/// An instance type by name, with its family.
+pub fn find_instance<'a>(
+    catalog: &'a [InstanceFamily],
+    name: &str,
+) -> Option<(&'a InstanceFamily, &'a InstanceType)> {
+    catalog.iter().find_map(|family| {
+        let instance = family.instance_types.iter().find(|t| t.name == name)?;
+        Some((family, instance))
+    })
+}
+
+/// Resolve each node to its catalog instance type by name and add up the
+/// prices of those that have one.
+pub fn match_nodes(nodes: &[NodeHardware], catalog: &[InstanceFamily]) -> ClusterCostSummary {
+    let mut summary = ClusterCostSummary::default();
+    for node in nodes {
+        let found = node
+            .provider_instance_type
+            .as_deref()
+            .and_then(|name| find_instance(catalog, name))
+            .map(|(family, instance)| (family.family.clone(), instance.clone()));
+        let (family, instance) = found.unzip();
+        let matched = NodeCatalogMatch {
+            node_name: node.node_name.clone(),
+            instance_type: node.provider_instance_type.clone(),
+            family,
+            instance,
+        };
+        match (&matched.instance, matched.price_per_hour_usd()) {
+            (None, _) => summary.unknown_nodes += 1,
+            (Some(_), None) => summary.unpriced_nodes += 1,
+            (Some(_), Some(price)) => summary.hourly_cost_usd += price,
+        }
+        summary.nodes.push(matched);
+    }
+    summary.monthly_cost_usd = summary.hourly_cost_usd * HOURS_PER_MONTH;
+    summary
+}
+
+impl ClusterHardwareStatus {
+    /// Price the detected nodes against `catalog`, if it is the detected
+    /// provider's.
+    pub fn price_nodes(&mut self, catalog: &InstanceCatalog) {
+        if self.detected_provider == Some(catalog.provider) {
+            self.cost = Some(match_nodes(&self.nodes, &catalog.families));
+        }
+    }
+}
+
+// ─── Deployment Cost Estimate ───────────────────────────────────────────────
+
+/// How instances are paid for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PricingModel {
+    #[default]
+    OnDemand,
+    Spot,
+    Reserved1Yr,
+    Reserved3Yr,
+}
+
+impl PricingModel {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::OnDemand => "On-demand",
+            Self::Spot => "Spot / preemptible",
+            Self::Reserved1Yr => "Reserved (1 year)",
+            Self::Reserved3Yr => "Reserved (3 years)",
+        }
+    }
+
+    pub fn all() -> &'static [PricingModel] {
+        &[
+            Self::OnDemand,
+            Self::Spot,
+            Self::Reserved1Yr,
+            Self::Reserved3Yr,
+        ]
+    }
+
+    /// Fraction of the on-demand price paid. Typical discounts across the
+    /// three clouds; actual rates vary by region and instance type.
+    pub fn price_factor(&self) -> f64 {
+        match self {
+            Self::OnDemand => 1.0,
+            Self::Spot => 0.35,
+            Self::Reserved1Yr => 0.62,
+            Self::Reserved3Yr => 0.40,
+        }
+    }
+}
+
+/// Inputs to [`DeploymentConfig::estimate_cost`] that the config itself
+/// doesn't carry.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CostOptions {
+    pub pricing: PricingModel,
+    /// Data kept in the storage backend.
+    pub storage_gb: u64,
+}
+
+/// Monthly cost of one worker pool.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoolCost {
+    pub pool: String,
+    pub instance_type: String,
+    /// Hourly price per instance under the pricing model; `None` when the
+    /// instance type isn't in the catalog or has no price.
+    pub price_per_hour_usd: Option<f64>,
+    pub replicas: i32,
+    /// Replica range the pool may scale within (KEDA), else `replicas`.
+    pub min_replicas: i32,
+    pub max_replicas: i32,
+    pub monthly_usd: f64,
+    pub min_monthly_usd: f64,
+    pub max_monthly_usd: f64,
+}
+
+/// Monthly cost of a deployment: at the configured replicas, and the best
+/// and worst case of the KEDA scaling range.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CostEstimate {
+    pub pricing: PricingModel,
+    pub pools: Vec<PoolCost>,
+    pub storage_monthly_usd: f64,
+    pub monthly_usd: f64,
+    pub min_monthly_usd: f64,
+    pub max_monthly_usd: f64,
+}
+
+impl CostEstimate {
+    /// Pools left out of the totals for lack of a catalog price.
+    pub fn unpriced_pools(&self) -> impl Iterator<Item = &PoolCost> {
+        self.pools.iter().filter(|p| p.price_per_hour_usd.is_none())
+    }
+}
+
+impl DeploymentConfig {
+    /// Estimate the monthly cost from the catalog prices of each pool's
+    /// instance type (one pod per node) plus storage. KEDA scales the CPU
+    /// workers only, so the other pools cost the same in every case.
+    pub fn estimate_cost(&self, catalog: &[InstanceFamily], options: &CostOptions) -> CostEstimate {
+        let factor = options.pricing.price_factor();
+        let storage_monthly_usd =
+            options.storage_gb as f64 * self.storage_backend.price_per_gb_month_usd();
+        let mut estimate = CostEstimate {
+            pricing: options.pricing,
+            pools: Vec::new(),
+            storage_monthly_usd,
+            monthly_usd: storage_monthly_usd,
+            min_monthly_usd: storage_monthly_usd,
+            max_monthly_usd: storage_monthly_usd,
+        };
+
+        for (index, (label, pool)) in self.pools().into_iter().enumerate() {
+            let price = find_instance(catalog, &pool.instance_type)
+                .and_then(|(_, instance)| instance.price_per_hour_usd)
+                .map(|price| price * factor);
+            let replicas = pool.replicas.max(0);
+            let (min_replicas, max_replicas) = if self.keda_enabled && index == 0 {
+                (pool.min_replicas.max(0), pool.max_replicas.max(0))
+            } else {
+                (replicas, replicas)
+            };
+            let monthly = |n: i32| price.unwrap_or(0.0) * n as f64 * HOURS_PER_MONTH;
+            let cost = PoolCost {
+                pool: label.to_string(),
+                instance_type: pool.instance_type.clone(),
+                price_per_hour_usd: price,
+                replicas,
+                min_replicas,
+                max_replicas,
+                monthly_usd: monthly(replicas),
+                min_monthly_usd: monthly(min_replicas),
+                max_monthly_usd: monthly(max_replicas),
+            };
+            estimate.monthly_usd += cost.monthly_usd;
+            estimate.min_monthly_usd += cost.min_monthly_usd;
+            estimate.max_monthly_usd += cost.max_monthly_usd;
+            estimate.pools.push(cost);
+        }
+        estimate
+    }
+}
+
+// ─── Validation ─────────────────────────────────────────────────────────────
+
+/// How serious a [`ValidationIssue`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum IssueSeverity {
+    /// Worth a look, but the config can still be generated.
+    Warning,
+    /// The generated resources would be rejected or never schedule.
+    Error,
+}
+
+/// A problem found by [`DeploymentConfig::validate`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidationIssue {
+    pub severity: IssueSeverity,
+    /// Path of the offending field, e.g. `gpu_worker_pool.replicas`.
+    pub field: String,
+    pub message: String,
+}
+
+impl ValidationIssue {
+    fn error(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: IssueSeverity::Error,
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+
+    fn warning(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: IssueSeverity::Warning,
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == IssueSeverity::Error
+    }
+}
+
+/// Whether `name` is a DNS-1123 label: at most 63 lowercase alphanumerics
+/// or '-', starting and ending with an alphanumeric.
+pub fn is_dns1123_label(name: &str) -> bool {
+    let valid_char = |c: char| c.is_ascii_lowercase() || c.is_ascii_digit();
+    !name.is_empty()
+        && name.len() <= 63
+        && name.chars().all(|c| valid_char(c) || c == '-')
+        && name.starts_with(valid_char)
+        && name.ends_with(valid_char)
+}
+
+impl DeploymentConfig {
+    /// Check the config against the instance catalog and, when given, the
+    /// detected cluster's free accelerators. Errors first.
+    pub fn validate(
+        &self,
+        catalog: &[InstanceFamily],
+        cluster: Option<&ClusterHardwareStatus>,
+    ) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        if !is_dns1123_label(&self.cluster_name) {
+            issues.push(ValidationIssue::error(
+                "cluster_name",
+                format!(
+                    "Cluster name \"{}\" must be a DNS-1123 label: lowercase letters, digits and '-', starting and ending with a letter or digit",
+                    self.cluster_name
+                ),
+            ));
+        } else if self.cluster_name.len() + "-worker".len() > 63 {
+            issues.push(ValidationIssue::error(
+                "cluster_name",
+                "Cluster name is too long for the \"<name>-worker\" StatefulSet (56 characters at most)",
+            ));
+        }
+
+        let pools = [
+            (
+                "cpu_worker_pool",
+                AcceleratorType::None,
+                Some(&self.cpu_worker_pool),
+            ),
+            (
+                "gpu_worker_pool",
+                AcceleratorType::Gpu,
+                self.gpu_worker_pool.as_ref(),
+            ),
+            (
+                "fpga_worker_pool",
+                AcceleratorType::Fpga,
+                self.fpga_worker_pool.as_ref(),
+            ),
+            (
+                "npu_worker_pool",
+                AcceleratorType::Npu,
+                self.npu_worker_pool.as_ref(),
+            ),
+        ];
+        for (field, expected, pool) in pools {
+            if let Some(pool) = pool {
+                validate_pool(field, expected, pool, catalog, cluster, &mut issues);
             }
         }
 
-        if self.keda_enabled {
-            yaml.push_str("  # KEDA autoscaling (configure ScaledObject separately)\n");
-            yaml.push_str(&format!(
-                "  # min_replicas: {}\n",
-                self.cpu_worker_pool.min_replicas
+        issues.sort_by_key(|i| std::cmp::Reverse(i.severity));
+        issues
+    }
+
+    /// Whether [`validate`](Self::validate) found any errors.
+    pub fn has_errors(
+        &self,
+        catalog: &[InstanceFamily],
+        cluster: Option<&ClusterHardwareStatus>,
+    ) -> bool {
+        self.validate(catalog, cluster)
+            .iter()
+            .any(ValidationIssue::is_error)
+    }
+}
+
+fn validate_pool(
+    field: &str,
+    expected: AcceleratorType,
+    pool: &WorkerPoolConfig,
+    catalog: &[InstanceFamily],
+    cluster: Option<&ClusterHardwareStatus>,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    if pool.min_replicas < 0 || pool.min_replicas > pool.max_replicas {
+        issues.push(ValidationIssue::error(
+            format!("{field}.min_replicas"),
+            format!(
+                "Need 0 <= min <= max replicas, got {}..{}",
+                pool.min_replicas, pool.max_replicas
+            ),
+        ));
+    } else if pool.replicas < pool.min_replicas || pool.replicas > pool.max_replicas {
+        issues.push(ValidationIssue::error(
+            format!("{field}.replicas"),
+            format!(
+                "{} replicas is outside the {}..{} range",
+                pool.replicas, pool.min_replicas, pool.max_replicas
+            ),
+        ));
+    }
+
+    let instance = find_instance(catalog, &pool.instance_type).map(|(_, t)| t);
+    if instance.is_none() {
+        issues.push(ValidationIssue::warning(
+            format!("{field}.instance_type"),
+            format!(
+                "Instance type \"{}\" isn't in the catalog; its accelerators and price can't be checked",
+                pool.instance_type
+            ),
+        ));
+    }
+    let detail = instance.and_then(|t| t.accelerator.as_ref());
+    if let Some(detail) = detail {
+        if detail.accelerator_type != expected {
+            issues.push(ValidationIssue::error(
+                format!("{field}.instance_type"),
+                format!(
+                    "{} has {} accelerators, not {}",
+                    pool.instance_type,
+                    detail.accelerator_type.label(),
+                    expected.label()
+                ),
+            ));
+        }
+    }
+
+    let Some(accel) = &pool.accelerator else {
+        if expected != AcceleratorType::None {
+            issues.push(ValidationIssue::error(
+                format!("{field}.accelerator"),
+                format!("A {} pool needs an accelerator", expected.label()),
             ));
-            yaml.push_str(&format!(
-                "  # max_replicas: {}\n",
-                self.cpu_worker_pool.max_replicas
+        }
+        return;
+    };
+    let accel_field = format!("{field}.accelerator");
+    if accel.count_per_pod == 0 {
+        issues.push(ValidationIssue::error(
+            format!("{accel_field}.count_per_pod"),
+            "Each pod needs at least one accelerator",
+        ));
+    }
+    if let Some(detail) = detail {
+        if accel.count_per_pod > detail.count {
+            issues.push(ValidationIssue::error(
+                format!("{accel_field}.count_per_pod"),
+                format!(
+                    "{} has {} {}s, but each pod asks for {}",
+                    pool.instance_type,
+                    detail.count,
+                    detail.accelerator_type.label(),
+                    accel.count_per_pod
+                ),
             ));
         }
+    }
+    if let Some(mig) = &accel.mig_profile {
+        match detail {
+            Some(detail) if !detail.supports_mig => issues.push(ValidationIssue::error(
+                format!("{accel_field}.mig_profile"),
+                format!("{} doesn't support MIG", detail.device_name),
+            )),
+            Some(detail) if !detail.mig_profiles.contains(mig) => {
+                issues.push(ValidationIssue::error(
+                    format!("{accel_field}.mig_profile"),
+                    format!(
+                        "MIG profile {mig} isn't offered by {} (available: {})",
+                        detail.device_name,
+                        detail.mig_profiles.join(", ")
+                    ),
+                ))
+            }
+            _ => {}
+        }
+    }
+
+    // Capacity only means something for a connected cluster
+    let Some(cluster) = cluster.filter(|c| c.k8s_connected) else {
+        return;
+    };
+    let (total, available) = match accel.accelerator_type {
+        AcceleratorType::Gpu => (cluster.total_gpus, cluster.gpu_available),
+        AcceleratorType::Fpga => (cluster.total_fpgas, cluster.fpga_available),
+        AcceleratorType::Npu => (cluster.total_npus, cluster.npu_available),
+        AcceleratorType::Tpu => (cluster.total_tpus, cluster.tpu_available),
+        AcceleratorType::None => return,
+    };
+    let needed = pool.replicas.max(0) as u32 * accel.count_per_pod;
+    if needed > available {
+        let label = accel.accelerator_type.label();
+        issues.push(ValidationIssue::warning(
+            format!("{field}.replicas"),
+            format!(
+                "Needs {needed} {label}s but the cluster has {available} free ({total} total); new nodes must be added"
+            ),
+        ));
+    }
+}
+
+// ─── Config Diff ────────────────────────────────────────────────────────────
+
+/// A setting that differs between two deployment configs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigChange {
+    /// Dotted path, e.g. "gpu_worker_pool.replicas".
+    pub field: String,
+    /// `None` when the setting is absent.
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+impl DeploymentConfig {
+    /// What applying this config would change relative to `base`, e.g. the
+    /// resource it was imported from. The cost estimate is not a setting
+    /// and is left out.
+    pub fn changes_from(&self, base: &DeploymentConfig) -> Vec<ConfigChange> {
+        let to_value = |config: &DeploymentConfig| {
+            let mut value = serde_json::to_value(config).unwrap_or_default();
+            if let Some(object) = value.as_object_mut() {
+                object.remove("estimated_monthly_cost_usd");
+            }
+            value
+        };
+        let mut changes = Vec::new();
+        diff_values(
+            String::new(),
+            &to_value(base),
+            &to_value(self),
+            &mut changes,
+        );
+        changes
+    }
+}
+
+fn diff_values(
+    path: String,
+    old: &serde_json::Value,
+    new: &serde_json::Value,
+    changes: &mut Vec<ConfigChange>,
+) {
+    use serde_json::Value;
+
+    let child = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{path}.{key}")
+        }
+    };
+    // An added or removed pool shows up field by field
+    let (empty_object, empty_array) = (Value::Object(Default::default()), Value::Array(vec![]));
+    let (old, new) = match (old, new) {
+        (Value::Null, Value::Object(_)) => (&empty_object, new),
+        (Value::Object(_), Value::Null) => (old, &empty_object),
+        (Value::Null, Value::Array(_)) => (&empty_array, new),
+        (Value::Array(_), Value::Null) => (old, &empty_array),
+        _ => (old, new),
+    };
+    match (old, new) {
+        (Value::Object(a), Value::Object(b)) => {
+            let mut keys: Vec<&String> = a.keys().chain(b.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let (old, new) = (
+                    a.get(key).unwrap_or(&Value::Null),
+                    b.get(key).unwrap_or(&Value::Null),
+                );
+                diff_values(child(key), old, new, changes);
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            for i in 0..a.len().max(b.len()) {
+                let (old, new) = (
+                    a.get(i).unwrap_or(&Value::Null),
+                    b.get(i).unwrap_or(&Value::Null),
+                );
+                diff_values(format!("{path}[{i}]"), old, new, changes);
+            }
+        }
+        (old, new) if old != new => {
+            let show = |v: &Value| match v {
+                Value::Null => None,
+                Value::String(s) => Some(s.clone()),
+                other => Some(other.to_string()),
+            };
+            changes.push(ConfigChange {
+                field: path,
+                old: show(old),
+                new: show(new),
+            });
+        }
+        _ => {}
+    }
+}
+
+// ─── Instance Recommendation ────────────────────────────────────────────────
+
+/// CPU time a query spends per GB of data with CPU/SIMD kernels. It only
+/// sets the scale of the sizing; candidates are ranked against each other.
+pub const VCPU_SECONDS_PER_GB: f64 = 0.05;
+
+/// Share of the data the workers keep in memory.
+pub const WORKING_SET_FRACTION: f64 = 0.25;
+
+/// Largest pool [`suggest_configs`] proposes.
+pub const MAX_SUGGESTED_REPLICAS: i32 = 64;
+
+/// What a deployment has to handle, for [`suggest_configs`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkloadProfile {
+    pub provider: CloudProvider,
+    pub data_size_gb: u64,
+    /// Sustained queries per second to provision for.
+    pub target_qps: f64,
+    /// Share of CPU query time spent in each operator, 0–1. The rest of the
+    /// query runs on the CPU whatever the hardware; shares adding up to
+    /// more than 1 are scaled down.
+    pub op_mix: Vec<(AccelerableOp, f64)>,
+    /// Monthly cost cap, storage included.
+    pub budget_monthly_usd: Option<f64>,
+}
+
+impl WorkloadProfile {
+    /// Whether the target QPS is something to size for: finite and above 0.
+    pub fn has_valid_target(&self) -> bool {
+        self.target_qps.is_finite() && self.target_qps > 0.0
+    }
 
-        yaml
+    /// Query speedup of a node with `backend` over a CPU-only node, by
+    /// Amdahl's law: operators `backend` has no kernel for run at CPU speed.
+    pub fn speedup(&self, backend: AcceleratorBackend) -> f64 {
+        let shares = || self.op_mix.iter().map(|(op, share)| (op, share.max(0.0)));
+        let total: f64 = shares().map(|(_, share)| share).sum();
+        let scale = if total > 1.0 { 1.0 / total } else { 1.0 };
+        let time = shares().fold(1.0 - total * scale, |time, (op, share)| {
+            time + share * scale / op.speedup(backend).unwrap_or(1.0)
+        });
+        1.0 / time
+    }
+
+    /// Queries per second one worker on `instance` sustains.
+    pub fn node_qps(&self, instance: &InstanceType) -> f64 {
+        let backend = instance
+            .accelerator
+            .as_ref()
+            .and_then(|a| a.accelerator_type.backend())
+            .unwrap_or(AcceleratorBackend::Cpu);
+        let vcpu_seconds = VCPU_SECONDS_PER_GB * self.data_size_gb.max(1) as f64;
+        instance.vcpus as f64 * self.speedup(backend) / vcpu_seconds
+    }
+
+    /// Workers of `instance` needed for `qps` with `memory_gb` of working set.
+    fn replicas(&self, instance: &InstanceType, qps: f64, memory_gb: f64) -> i32 {
+        let for_qps = qps / self.node_qps(instance);
+        let for_memory = memory_gb / instance.memory_gb.max(1) as f64;
+        for_qps.max(for_memory).ceil().clamp(1.0, i32::MAX as f64) as i32
+    }
+}
+
+/// A deployment proposed by [`suggest_configs`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigSuggestion {
+    pub config: DeploymentConfig,
+    /// Queries per second the pools sustain together.
+    pub throughput_qps: f64,
+    /// On-demand monthly cost, storage included.
+    pub monthly_usd: f64,
+    /// Speedup of the accelerator pool for the op mix; 1.0 without one.
+    pub speedup: f64,
+}
+
+impl ConfigSuggestion {
+    /// Throughput bought per dollar a month, which suggestions are ranked by.
+    pub fn qps_per_dollar(&self) -> f64 {
+        self.throughput_qps / self.monthly_usd
     }
 }
 
+/// Candidate deployments for `workload` from the provider's `catalog`, best
+/// throughput per dollar first. A candidate is either CPU workers alone, or
+/// one CPU worker next to a single GPU, FPGA or NPU pool, sized for the
+/// target QPS and to hold the working set in memory. Accelerators nothing in
+/// the op mix runs faster on, unpriced instance types, and candidates over
+/// budget or past [`MAX_SUGGESTED_REPLICAS`] are left out. A workload
+/// without a valid target QPS gets no suggestions.
+pub fn suggest_configs(
+    workload: &WorkloadProfile,
+    catalog: &[InstanceFamily],
+) -> Vec<ConfigSuggestion> {
+    if !workload.has_valid_target() {
+        return vec![];
+    }
+    let priced = |accelerator_type: AcceleratorType| {
+        catalog
+            .iter()
+            .filter(move |f| f.accelerator_type == accelerator_type)
+            .flat_map(|f| &f.instance_types)
+            .filter(|t| t.price_per_hour_usd.is_some_and(|p| p > 0.0))
+    };
+    let qps_per_usd =
+        |t: &InstanceType| workload.node_qps(t) / t.price_per_hour_usd.unwrap_or(f64::INFINITY);
+    let target_qps = workload.target_qps;
+    let working_set_gb = workload.data_size_gb as f64 * WORKING_SET_FRACTION;
+    let base = DeploymentConfig::default_for(workload.provider, catalog);
+    // Room for KEDA to double the CPU workers under bursts
+    let cpu_pool = |instance: &InstanceType, replicas: i32| WorkerPoolConfig {
+        max_replicas: replicas * 2,
+        ..WorkerPoolConfig::for_instance(instance, replicas)
+    };
+
+    let mut candidates = vec![];
+    for cpu in priced(AcceleratorType::None) {
+        let replicas = workload.replicas(cpu, target_qps, working_set_gb);
+        if replicas > MAX_SUGGESTED_REPLICAS {
+            continue;
+        }
+        let config = DeploymentConfig {
+            cpu_worker_pool: cpu_pool(cpu, replicas),
+            ..base.clone()
+        };
+        candidates.push((config, 1.0));
+    }
+
+    // Accelerated candidates keep a single CPU worker, of the type that
+    // does the most per dollar on its own
+    let best_cpu =
+        priced(AcceleratorType::None).max_by(|a, b| qps_per_usd(a).total_cmp(&qps_per_usd(b)));
+    for accelerator_type in [
+        AcceleratorType::Gpu,
+        AcceleratorType::Fpga,
+        AcceleratorType::Npu,
+    ] {
+        let (Some(cpu), Some(backend)) = (best_cpu, accelerator_type.backend()) else {
+            continue;
+        };
+        let speedup = workload.speedup(backend);
+        if speedup <= 1.0 {
+            continue;
+        }
+        for instance in priced(accelerator_type) {
+            let replicas = workload.replicas(
+                instance,
+                target_qps - workload.node_qps(cpu),
+                working_set_gb - cpu.memory_gb as f64,
+            );
+            if replicas > MAX_SUGGESTED_REPLICAS {
+                continue;
+            }
+            let pool = Some(WorkerPoolConfig::for_instance(instance, replicas));
+            let mut config = DeploymentConfig {
+                cpu_worker_pool: cpu_pool(cpu, 1),
+                ..base.clone()
+            };
+            match accelerator_type {
+                AcceleratorType::Gpu => config.gpu_worker_pool = pool,
+                AcceleratorType::Fpga => config.fpga_worker_pool = pool,
+                _ => config.npu_worker_pool = pool,
+            }
+            candidates.push((config, speedup));
+        }
+    }
+
+    let options = CostOptions {
+        pricing: PricingModel::OnDemand,
+        storage_gb: workload.data_size_gb,
+    };
+    let mut suggestions: Vec<ConfigSuggestion> = candidates
+        .into_iter()
+        .map(|(mut config, speedup)| {
+            let throughput_qps = config
+                .pools()
+                .iter()
+                .filter_map(|(_, pool)| {
+                    let (_, instance) = find_instance(catalog, &pool.instance_type)?;
+                    Some(workload.node_qps(instance) * pool.replicas as f64)
+                })
+                .sum();
+            let monthly_usd = config.estimate_cost(catalog, &options).monthly_usd;
+            config.estimated_monthly_cost_usd = monthly_usd;
+            ConfigSuggestion {
+                config,
+                throughput_qps,
+                monthly_usd,
+                speedup,
+            }
+        })
+        .filter(|s| {
+            workload
+                .budget_monthly_usd
+                .is_none_or(|budget| s.monthly_usd <= budget)
+        })
+        .collect();
+    suggestions.sort_by(|a, b| b.qps_per_dollar().total_cmp(&a.qps_per_dollar()));
+    suggestions
+}
+
 // ─── Tests ──────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -1210,56 +1392,402 @@ mod tests {
     }
 
     #[test]
-    fn crd_yaml_generation() {
+    fn bundled_catalogs_are_valid() {
+        for &provider in CloudProvider::all() {
+            let catalog = InstanceCatalog::bundled(provider);
+            assert_eq!(catalog.provider, provider);
+            assert_eq!(catalog.schema_version, CATALOG_SCHEMA_VERSION);
+            assert!(!catalog.families.is_empty());
+        }
+    }
+
+    #[test]
+    fn custom_catalog_file() {
+        let json = r#"{
+            "schema_version": 1,
+            "provider": "OnPrem",
+            "catalog_version": "rack-b",
+            "last_updated": "2026-09-30",
+            "families": [{
+                "provider": "OnPrem",
+                "family": "dgx",
+                "description": "DGX nodes in rack B",
+                "accelerator_type": "Gpu",
+                "instance_types": [{
+                    "name": "dgx-a100",
+                    "vcpus": 128,
+                    "memory_gb": 1024,
+                    "accelerator": {
+                        "accelerator_type": "Gpu",
+                        "device_name": "NVIDIA A100 80GB",
+                        "count": 8
+                    },
+                    "price_per_hour_usd": 12.5,
+                    "k8s_resource_name": "nvidia.com/gpu"
+                }]
+            }]
+        }"#;
+        let catalog = InstanceCatalog::from_json(json).unwrap();
+        let (family, instance) = find_instance(&catalog.families, "dgx-a100").unwrap();
+        assert_eq!(family.family, "dgx");
+        let accelerator = instance.accelerator.as_ref().unwrap();
+        assert_eq!(accelerator.count, 8);
+        assert!(!accelerator.supports_mig);
+        assert_eq!(instance.network_gbps, None);
+
+        assert!(matches!(
+            InstanceCatalog::from_json(&json.replacen('{', "[", 1)),
+            Err(CatalogError::Parse(_))
+        ));
+        assert_eq!(
+            InstanceCatalog::from_json(
+                &json.replace("\"schema_version\": 1", "\"schema_version\": 2")
+            )
+            .unwrap_err(),
+            CatalogError::UnsupportedSchema(2)
+        );
+
+        let edits: [fn(&mut InstanceCatalog); 8] = [
+            |c| c.last_updated = "last week".into(),
+            |c| c.catalog_version = " ".into(),
+            |c| c.families[0].provider = CloudProvider::Aws,
+            |c| c.families[0].accelerator_type = AcceleratorType::Fpga,
+            |c| {
+                c.families[0].instance_types[0]
+                    .accelerator
+                    .as_mut()
+                    .unwrap()
+                    .count = 0
+            },
+            |c| c.families[0].instance_types[0].price_per_hour_usd = Some(-1.0),
+            |c| c.families[0].instance_types[0].k8s_resource_name = None,
+            |c| {
+                let mut copy = c.families[0].clone();
+                copy.family = "dgx-2".into();
+                c.families.push(copy);
+            },
+        ];
+        for (i, edit) in edits.iter().enumerate() {
+            let mut invalid = catalog.clone();
+            edit(&mut invalid);
+            assert!(
+                matches!(invalid.check(), Err(CatalogError::Invalid(_))),
+                "edit {i}"
+            );
+        }
+    }
+
+    #[test]
+    fn match_nodes_against_catalog() {
+        let node = |name: &str, instance_type: Option<&str>| NodeHardware {
+            node_name: name.to_string(),
+            provider_instance_type: instance_type.map(str::to_string),
+            cpu_allocatable: "48".to_string(),
+            memory_allocatable_bytes: 0,
+            accelerators: vec![],
+            labels: vec![],
+        };
+        let nodes = [
+            node("gpu-0", Some("g6.12xlarge")),
+            node("gpu-1", Some("g6.12xlarge")),
+            node("cpu-0", Some("m6i.4xlarge")),
+            node("mem-0", Some("x2idn.16xlarge")),
+            node("bare", None),
+        ];
+        let catalog = instance_catalog(CloudProvider::Aws);
+        let price = |name: &str| {
+            catalog
+                .iter()
+                .flat_map(|f| &f.instance_types)
+                .find(|t| t.name == name)
+                .and_then(|t| t.price_per_hour_usd)
+                .unwrap()
+        };
+        let hourly = 2.0 * price("g6.12xlarge") + price("m6i.4xlarge");
+
+        let summary = match_nodes(&nodes, &catalog);
+        assert_eq!(summary.nodes[0].family.as_deref(), Some("g6"));
+        assert_eq!(
+            summary.nodes[0].price_per_hour_usd(),
+            Some(price("g6.12xlarge"))
+        );
+        assert!(summary.nodes[3].instance.is_none());
+        assert_eq!((summary.unknown_nodes, summary.unpriced_nodes), (2, 0));
+        assert!((summary.hourly_cost_usd - hourly).abs() < 1e-9);
+        assert!((summary.monthly_cost_usd - hourly * HOURS_PER_MONTH).abs() < 1e-6);
+
+        let on_prem = match_nodes(
+            &[node("rack-1", Some("custom-node"))],
+            &instance_catalog(CloudProvider::OnPrem),
+        );
+        assert_eq!((on_prem.unknown_nodes, on_prem.unpriced_nodes), (0, 1));
+        assert_eq!(on_prem.monthly_cost_usd, 0.0);
+    }
+
+    #[test]
+    fn estimate_deployment_cost() {
+        let catalog = instance_catalog(CloudProvider::Aws);
+        let price = |name: &str| {
+            find_instance(&catalog, name)
+                .unwrap()
+                .1
+                .price_per_hour_usd
+                .unwrap()
+        };
+        let mut config = DeploymentConfig::default_for(CloudProvider::Aws, &catalog);
+        assert_eq!(config.cpu_worker_pool.instance_type, "m6i.2xlarge");
+        let g6 = find_instance(&catalog, "g6.12xlarge").unwrap().1;
+        let gpu_pool = WorkerPoolConfig::for_instance(g6, 2);
+        assert_eq!(gpu_pool.accelerator.as_ref().unwrap().count_per_pod, 4);
+        assert_eq!(gpu_pool.tolerations[0].key, "nvidia.com/gpu");
+        assert_eq!(gpu_pool.node_selector[0].1, "gpu-pool");
+        config.gpu_worker_pool = Some(gpu_pool);
+        let options = CostOptions {
+            pricing: PricingModel::OnDemand,
+            storage_gb: 1000,
+        };
+
+        let estimate = config.estimate_cost(&catalog, &options);
+        let cpu = price("m6i.2xlarge") * HOURS_PER_MONTH;
+        let gpu = 2.0 * price("g6.12xlarge") * HOURS_PER_MONTH;
+        let storage = 1000.0 * 0.023;
+        assert!((estimate.storage_monthly_usd - storage).abs() < 1e-9);
+        assert!((estimate.monthly_usd - (3.0 * cpu + gpu + storage)).abs() < 1e-6);
+        // KEDA ranges the CPU workers over 2..=10; the GPU pool stays at 2
+        assert!((estimate.min_monthly_usd - (2.0 * cpu + gpu + storage)).abs() < 1e-6);
+        assert!((estimate.max_monthly_usd - (10.0 * cpu + gpu + storage)).abs() < 1e-6);
+        assert_eq!(estimate.pools[1].min_replicas, 2);
+
+        config.keda_enabled = false;
+        let spot = config.estimate_cost(
+            &catalog,
+            &CostOptions {
+                pricing: PricingModel::Spot,
+                storage_gb: 0,
+            },
+        );
+        assert!((spot.monthly_usd - 0.35 * (3.0 * cpu + gpu)).abs() < 1e-6);
+        assert_eq!(spot.min_monthly_usd, spot.max_monthly_usd);
+
+        config.cpu_worker_pool.instance_type = "unknown.large".to_string();
+        let estimate = config.estimate_cost(&catalog, &CostOptions::default());
+        let unpriced: Vec<&str> = estimate.unpriced_pools().map(|p| p.pool.as_str()).collect();
+        assert_eq!(unpriced, ["CPU workers"]);
+        assert!((estimate.monthly_usd - gpu).abs() < 1e-6);
+    }
+
+    #[test]
+    fn validate_deployment_config() {
+        let catalog = instance_catalog(CloudProvider::Aws);
+        let mut config = DeploymentConfig::default_for(CloudProvider::Aws, &catalog);
+        assert!(config.validate(&catalog, None).is_empty());
+
+        let p4d = find_instance(&catalog, "p4d.24xlarge").unwrap().1;
+        let mut gpu_pool = WorkerPoolConfig::for_instance(p4d, 2);
+        let accel = gpu_pool.accelerator.as_mut().unwrap();
+        accel.count_per_pod = 16;
+        accel.mig_profile = Some("7g.80gb".to_string());
+        config.gpu_worker_pool = Some(gpu_pool);
+        config.fpga_worker_pool = Some(WorkerPoolConfig {
+            accelerator: None,
+            ..WorkerPoolConfig::for_instance(find_instance(&catalog, "f2.6xlarge").unwrap().1, 1)
+        });
+        config.cluster_name = "MegaDB_Prod".to_string();
+        config.cpu_worker_pool.replicas = 20;
+
+        let issues = config.validate(&catalog, None);
+        let fields: Vec<&str> = issues.iter().map(|i| i.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            [
+                "cluster_name",
+                "cpu_worker_pool.replicas",
+                "gpu_worker_pool.accelerator.count_per_pod",
+                "gpu_worker_pool.accelerator.mig_profile",
+                "fpga_worker_pool.accelerator",
+            ]
+        );
+        assert!(issues.iter().all(ValidationIssue::is_error));
+        assert!(config.has_errors(&catalog, None));
+
+        // A MIG profile on a GPU without MIG
+        let g6 = find_instance(&catalog, "g6.12xlarge").unwrap().1;
+        let mut gpu_pool = WorkerPoolConfig::for_instance(g6, 2);
+        gpu_pool.accelerator.as_mut().unwrap().mig_profile = Some("1g.10gb".to_string());
         let config = DeploymentConfig {
-            cluster_name: "megadb-prod".to_string(),
+            gpu_worker_pool: Some(gpu_pool),
+            ..DeploymentConfig::default_for(CloudProvider::Aws, &catalog)
+        };
+        let issues = config.validate(&catalog, None);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].message.contains("doesn't support MIG"));
+    }
+
+    #[test]
+    fn validate_against_cluster_capacity() {
+        let catalog = instance_catalog(CloudProvider::Aws);
+        let g6 = find_instance(&catalog, "g6.12xlarge").unwrap().1;
+        let mut config = DeploymentConfig::default_for(CloudProvider::Aws, &catalog);
+        config.gpu_worker_pool = Some(WorkerPoolConfig::for_instance(g6, 2));
+        config.cpu_worker_pool.instance_type = "x2idn.16xlarge".to_string();
+        let cluster = ClusterHardwareStatus {
+            total_gpus: 8,
+            gpu_available: 6,
+            k8s_connected: true,
+            ..Default::default()
+        };
+
+        let issues = config.validate(&catalog, Some(&cluster));
+        assert_eq!(issues.len(), 2, "{issues:?}");
+        assert!(issues.iter().all(|i| i.severity == IssueSeverity::Warning));
+        assert_eq!(issues[0].field, "cpu_worker_pool.instance_type");
+        assert!(issues[1].message.contains("Needs 8 GPUs"));
+        assert!(!config.has_errors(&catalog, Some(&cluster)));
+    }
+
+    #[test]
+    fn dns1123_labels() {
+        assert!(is_dns1123_label("megadb-prod-1"));
+        for name in [
+            "",
+            "-megadb",
+            "megadb-",
+            "MegaDB",
+            "mega_db",
+            &"a".repeat(64),
+        ] {
+            assert!(!is_dns1123_label(name), "{name:?}");
+        }
+    }
+
+    #[test]
+    fn config_changes() {
+        let catalog = instance_catalog(CloudProvider::Aws);
+        let base = DeploymentConfig::default_for(CloudProvider::Aws, &catalog);
+        let mut edited = base.clone();
+        assert!(edited.changes_from(&base).is_empty());
+
+        edited.cpu_worker_pool.replicas = 5;
+        edited.keda_enabled = false;
+        edited.estimated_monthly_cost_usd = 1234.0;
+        let g6 = find_instance(&catalog, "g6.12xlarge").unwrap().1;
+        edited.gpu_worker_pool = Some(WorkerPoolConfig::for_instance(g6, 2));
+
+        let changes = edited.changes_from(&base);
+        let change = |field: &str| changes.iter().find(|c| c.field == field).unwrap();
+        assert_eq!(
+            change("cpu_worker_pool.replicas"),
+            &ConfigChange {
+                field: "cpu_worker_pool.replicas".to_string(),
+                old: Some("3".to_string()),
+                new: Some("5".to_string()),
+            }
+        );
+        assert_eq!(change("keda_enabled").new.as_deref(), Some("false"));
+        let instance = change("gpu_worker_pool.instance_type");
+        assert_eq!(instance.old, None);
+        assert_eq!(instance.new.as_deref(), Some("g6.12xlarge"));
+        assert!(changes
+            .iter()
+            .any(|c| c.field == "gpu_worker_pool.tolerations[0].key"));
+        assert!(!changes.iter().any(|c| c.field.starts_with("estimated")));
+
+        // Removing the pool again reports it the other way round
+        let reverse = base.changes_from(&edited);
+        let instance = reverse
+            .iter()
+            .find(|c| c.field == "gpu_worker_pool.instance_type")
+            .unwrap();
+        assert_eq!(
+            (instance.old.as_deref(), instance.new.as_deref()),
+            (Some("g6.12xlarge"), None)
+        );
+    }
+
+    #[test]
+    fn suggest_configs_by_throughput_per_dollar() {
+        let catalog = instance_catalog(CloudProvider::Aws);
+        let workload = WorkloadProfile {
             provider: CloudProvider::Aws,
-            cpu_worker_pool: WorkerPoolConfig {
-                instance_type: "m6i.4xlarge".to_string(),
-                replicas: 3,
-                min_replicas: 2,
-                max_replicas: 10,
-                accelerator: None,
-                node_selector: vec![],
-                tolerations: vec![],
+            data_size_gb: 1000,
+            target_qps: 5.0,
+            op_mix: vec![(AccelerableOp::Filter, 0.5)],
+            budget_monthly_usd: None,
+        };
+        assert_eq!(workload.speedup(AcceleratorBackend::Gpu), 1.0);
+
+        // Nothing in the mix is accelerated: CPU workers only
+        let cpu_only = suggest_configs(&workload, &catalog);
+        assert!(!cpu_only.is_empty());
+        for suggestion in &cpu_only {
+            assert_eq!(suggestion.config.pools().len(), 1);
+            assert!(suggestion.throughput_qps >= workload.target_qps);
+            assert!(!suggestion.config.has_errors(&catalog, None));
+        }
+
+        // Vector search dominates: a GPU pool wins, and the list is ranked
+        let vector = WorkloadProfile {
+            op_mix: vec![
+                (AccelerableOp::VectorDistance, 0.6),
+                (AccelerableOp::HashAggregate, 0.2),
+            ],
+            ..workload.clone()
+        };
+        let speedup = 1.0 / (0.2 + 0.6 / 50.0 + 0.2 / 9.3);
+        assert!((vector.speedup(AcceleratorBackend::Gpu) - speedup).abs() < 1e-9);
+        let suggestions = suggest_configs(&vector, &catalog);
+        let top = &suggestions[0];
+        assert!(top.config.gpu_worker_pool.is_some());
+        assert_eq!(top.config.cpu_worker_pool.replicas, 1);
+        assert!(top.throughput_qps >= vector.target_qps);
+        assert_eq!(top.monthly_usd, top.config.estimated_monthly_cost_usd,);
+        assert!(suggestions
+            .windows(2)
+            .all(|w| w[0].qps_per_dollar() >= w[1].qps_per_dollar()));
+        assert!(suggestions
+            .iter()
+            .all(|s| !s.config.has_errors(&catalog, None)));
+
+        // A budget drops what it can't pay for
+        let budget = top.monthly_usd;
+        let capped = suggest_configs(
+            &WorkloadProfile {
+                budget_monthly_usd: Some(budget),
+                ..vector.clone()
             },
-            gpu_worker_pool: Some(WorkerPoolConfig {
-                instance_type: "g6.12xlarge".to_string(),
-                replicas: 2,
-                min_replicas: 1,
-                max_replicas: 8,
-                accelerator: Some(PoolAccelerator {
-                    accelerator_type: AcceleratorType::Gpu,
-                    k8s_resource_name: "nvidia.com/gpu".to_string(),
-                    count_per_pod: 4,
-                    device_name: "NVIDIA L4 24GB".to_string(),
-                    mig_profile: None,
-                }),
-                node_selector: vec![(
-                    "cloud.google.com/gke-accelerator".to_string(),
-                    "nvidia-l4".to_string(),
-                )],
-                tolerations: vec![TolerationConfig {
-                    key: "nvidia.com/gpu".to_string(),
-                    operator: "Exists".to_string(),
-                    value: None,
-                    effect: "NoSchedule".to_string(),
-                }],
-            }),
-            fpga_worker_pool: None,
-            npu_worker_pool: None,
-            storage_backend: StorageBackend::S3,
-            keda_enabled: true,
-            estimated_monthly_cost_usd: 15000.0,
+            &catalog,
+        );
+        assert!(!capped.is_empty() && capped.len() < suggestions.len());
+        assert!(capped.iter().all(|s| s.monthly_usd <= budget));
+        let broke = WorkloadProfile {
+            budget_monthly_usd: Some(1.0),
+            ..vector
         };
+        assert!(suggest_configs(&broke, &catalog).is_empty());
 
-        let yaml = config.to_crd_yaml();
-        assert!(yaml.contains("megadb-prod"));
-        assert!(yaml.contains("replicas: 3"));
-        assert!(yaml.contains("gpu_workers:"));
-        assert!(yaml.contains("NVIDIA L4 24GB"));
-        assert!(yaml.contains("nvidia.com/gpu"));
-        assert!(yaml.contains("storage_type: s3"));
+        // Targets that can't be sized for, or only past the replica cap
+        for target_qps in [0.0, -1.0, f64::NAN, f64::INFINITY, 1e300] {
+            let workload = WorkloadProfile {
+                target_qps,
+                ..workload.clone()
+            };
+            assert_eq!(workload.has_valid_target(), target_qps == 1e300);
+            assert!(suggest_configs(&workload, &catalog).is_empty());
+        }
+        let mut no_vcpus = catalog.clone();
+        for family in &mut no_vcpus {
+            for instance in &mut family.instance_types {
+                instance.vcpus = 0;
+            }
+        }
+        assert!(suggest_configs(&workload, &no_vcpus).is_empty());
+
+        // Without prices there is nothing to rank
+        let on_prem = WorkloadProfile {
+            provider: CloudProvider::OnPrem,
+            ..workload
+        };
+        assert!(suggest_configs(&on_prem, &instance_catalog(CloudProvider::OnPrem)).is_empty());
     }
 
     #[test]
@@ -1286,5 +1814,12 @@ mod tests {
         assert_eq!(decoded.node_name, "gpu-node-0");
         assert_eq!(decoded.accelerators.len(), 1);
         assert_eq!(decoded.accelerators[0].allocatable, 8);
+        assert_eq!(decoded.cpu_cores(), 96.0);
+
+        let node = NodeHardware {
+            cpu_allocatable: "47500m".to_string(),
+            ..decoded
+        };
+        assert_eq!(node.cpu_cores(), 47.5);
     }
 }
@@ -26,6 +26,13 @@ pub enum ConnectionStatus {
     Error(String),
 }
 
+impl ConnectionConfig {
+    /// Base URL of MegaDB's HTTP API for this connection (e.g. `http://localhost:8080`).
+    pub fn http_base_url(&self) -> String {
+        format!("http://{}:{}", self.host, self.http_port)
+    }
+}
+
 impl Default for ConnectionConfig {
     fn default() -> Self {
         Self {
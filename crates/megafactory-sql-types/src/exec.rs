@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+
+/// Entry in [`ExecPolicy::allowed_commands`] that allows any program.
+pub const ALLOW_ANY_COMMAND: &str = "*";
+
+/// Which commands the dashboard may run in cluster pods.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExecPolicy {
+    /// Exec is refused outright when false.
+    pub enabled: bool,
+    /// Programs (the command's first word) that may be run, or `"*"` for any.
+    pub allowed_commands: Vec<String>,
+}
+
+impl ExecPolicy {
+    /// Check a command line against the policy.
+    pub fn check(&self, command: &[String]) -> Result<(), String> {
+        if !self.enabled {
+            return Err("Exec into pods is disabled on this server".to_string());
+        }
+        let Some(program) = command.first().filter(|p| !p.is_empty()) else {
+            return Err("No command given".to_string());
+        };
+        let allowed = self
+            .allowed_commands
+            .iter()
+            .any(|c| c == ALLOW_ANY_COMMAND || c == program);
+        if !allowed {
+            return Err(format!(
+                "Command {program} is not allowed; allowed: {}",
+                self.allowed_commands.join(", ")
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Messages sent from the browser terminal to `/proxy/k8s/pods/{name}/exec`.
+/// The first message must be `Start`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "payload")]
+pub enum ExecClientMessage {
+    Start {
+        command: Vec<String>,
+        /// Allocate a TTY; stderr is then merged into stdout.
+        tty: bool,
+        cols: u16,
+        rows: u16,
+    },
+    Stdin {
+        data: String,
+    },
+    /// Only honoured for TTY sessions.
+    Resize {
+        cols: u16,
+        rows: u16,
+    },
+}
+
+/// Messages sent from the exec endpoint to the browser terminal.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "payload")]
+pub enum ExecServerMessage {
+    Started,
+    Stdout {
+        data: String,
+    },
+    Stderr {
+        data: String,
+    },
+    /// The process ended; the socket closes after this.
+    Exit {
+        code: Option<i32>,
+        message: String,
+    },
+    Error {
+        message: String,
+    },
+}
+
+/// Remove ANSI escape sequences (colours, cursor movement) and carriage
+/// returns from terminal output, for display as plain text.
+pub fn strip_ansi(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\u{1b}' => match chars.next() {
+                // CSI: parameters, then one final byte in @..~
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // OSC: terminated by BEL or ESC \
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\u{7}' || (c == '\u{1b}' && chars.next_if_eq(&'\\').is_some()) {
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            },
+            '\r' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(words: &[&str]) -> Vec<String> {
+        words.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn test_exec_policy() {
+        let policy = ExecPolicy {
+            enabled: true,
+            allowed_commands: vec!["megadb-admin".into()],
+        };
+        assert!(policy.check(&command(&["megadb-admin", "status"])).is_ok());
+        assert!(policy.check(&command(&["sh"])).is_err());
+        assert!(policy.check(&[]).is_err());
+
+        let disabled = ExecPolicy {
+            enabled: false,
+            ..policy
+        };
+        assert!(disabled.check(&command(&["megadb-admin"])).is_err());
+
+        let any = ExecPolicy {
+            enabled: true,
+            allowed_commands: vec![ALLOW_ANY_COMMAND.into()],
+        };
+        assert!(any.check(&command(&["sh", "-c", "ls"])).is_ok());
+    }
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(strip_ansi("\u{1b}[1;32mok\u{1b}[0m\r\n"), "ok\n");
+        assert_eq!(strip_ansi("\u{1b}]0;title\u{7}$ ls"), "$ ls");
+        assert_eq!(strip_ansi("plain"), "plain");
+    }
+}
@@ -0,0 +1,176 @@
+//! Configurator output formats and the zip archive they are downloaded as.
+
+use serde::{Deserialize, Serialize};
+
+/// How a deployment config is rendered for a GitOps repository.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExportFormat {
+    /// The MegaDB resource (and ScaledObject) as applied by the dashboard.
+    Crd,
+    /// A `values.yaml` for the MegaDB Helm chart.
+    Helm,
+    /// A kustomization with a scheduling patch per worker pool.
+    Kustomize,
+}
+
+impl ExportFormat {
+    pub fn all() -> &'static [ExportFormat] {
+        &[Self::Crd, Self::Helm, Self::Kustomize]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Crd => "MegaDB CRD",
+            Self::Helm => "Helm values",
+            Self::Kustomize => "Kustomize",
+        }
+    }
+
+    /// Short name used in download file names.
+    pub fn slug(&self) -> &'static str {
+        match self {
+            Self::Crd => "crd",
+            Self::Helm => "helm",
+            Self::Kustomize => "kustomize",
+        }
+    }
+}
+
+/// One rendered file, with its path relative to the export root.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportFile {
+    pub path: String,
+    pub content: String,
+}
+
+/// Pack `files` into a zip archive. Entries are stored uncompressed:
+/// manifests are small, and this keeps the archive buildable in the browser
+/// without a compression library.
+pub fn zip_files(files: &[ExportFile]) -> Vec<u8> {
+    let mut archive = Vec::new();
+    let mut central = Vec::new();
+    for file in files {
+        let (name, data) = (file.path.as_bytes(), file.content.as_bytes());
+        let crc = crc32(data);
+        let offset = archive.len() as u32;
+
+        // Local file header
+        put_u32(&mut archive, 0x0403_4b50);
+        put_entry_fields(&mut archive, crc, data.len() as u32, name.len() as u16);
+        archive.extend_from_slice(name);
+        archive.extend_from_slice(data);
+
+        // Central directory header
+        put_u32(&mut central, 0x0201_4b50);
+        put_u16(&mut central, 20); // version made by
+        put_entry_fields(&mut central, crc, data.len() as u32, name.len() as u16);
+        put_u16(&mut central, 0); // comment length
+        put_u16(&mut central, 0); // disk number
+        put_u16(&mut central, 0); // internal attributes
+        put_u32(&mut central, 0); // external attributes
+        put_u32(&mut central, offset);
+        central.extend_from_slice(name);
+    }
+
+    let central_offset = archive.len() as u32;
+    let central_size = central.len() as u32;
+    archive.extend_from_slice(&central);
+    // End of central directory
+    put_u32(&mut archive, 0x0605_4b50);
+    put_u16(&mut archive, 0); // this disk
+    put_u16(&mut archive, 0); // central directory disk
+    put_u16(&mut archive, files.len() as u16);
+    put_u16(&mut archive, files.len() as u16);
+    put_u32(&mut archive, central_size);
+    put_u32(&mut archive, central_offset);
+    put_u16(&mut archive, 0); // comment length
+    archive
+}
+
+/// Fields shared by local and central headers, from "version needed" up to
+/// "extra field length".
+fn put_entry_fields(out: &mut Vec<u8>, crc: u32, size: u32, name_len: u16) {
+    put_u16(out, 20); // version needed
+    put_u16(out, 0x0800); // flags: UTF-8 names
+    put_u16(out, 0); // method: stored
+    put_u16(out, 0); // modification time
+    put_u16(out, 0x21); // modification date: 1980-01-01
+    put_u32(out, crc);
+    put_u32(out, size); // compressed size
+    put_u32(out, size); // uncompressed size
+    put_u16(out, name_len);
+    put_u16(out, 0); // extra field length
+}
+
+fn put_u16(out: &mut Vec<u8>, value: u16) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+/// CRC-32 (IEEE), as zip requires.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn zip_layout() {
+        let files = vec![
+            ExportFile {
+                path: "kustomization.yaml".to_string(),
+                content: "resources:\n- megadb.yaml\n".to_string(),
+            },
+            ExportFile {
+                path: "patches/gpu-workers.yaml".to_string(),
+                content: "spec: {}\n".to_string(),
+            },
+        ];
+        let zip = zip_files(&files);
+        let u16_at = |i: usize| u16::from_le_bytes([zip[i], zip[i + 1]]);
+        let u32_at = |i: usize| u32::from_le_bytes(zip[i..i + 4].try_into().unwrap());
+
+        // First entry: header, name, then the stored data
+        assert_eq!(u32_at(0), 0x0403_4b50);
+        assert_eq!(u32_at(14), crc32(files[0].content.as_bytes()));
+        assert_eq!(&zip[30..48], b"kustomization.yaml");
+        assert_eq!(
+            &zip[48..48 + files[0].content.len()],
+            files[0].content.as_bytes()
+        );
+
+        // End record points at a central directory listing both entries
+        let end = zip.len() - 22;
+        assert_eq!(u32_at(end), 0x0605_4b50);
+        assert_eq!(u16_at(end + 10), 2);
+        let central = u32_at(end + 16) as usize;
+        assert_eq!(u32_at(central), 0x0201_4b50);
+        assert_eq!(central + u32_at(end + 12) as usize, end);
+        let second = central + 46 + "kustomization.yaml".len();
+        assert_eq!(u32_at(second), 0x0201_4b50);
+        let second_offset = u32_at(second + 42) as usize;
+        assert_eq!(u32_at(second_offset), 0x0403_4b50);
+    }
+}
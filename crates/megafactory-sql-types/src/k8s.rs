@@ -1,3 +1,6 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 /// Overall cluster status from MegaDB CRD.
@@ -10,6 +13,36 @@ pub struct ClusterStatus {
     pub message: String,
     pub pods: Vec<PodInfo>,
     pub volumes: Vec<VolumeInfo>,
+    /// Conditions from the MegaDB custom resource, when one exists.
+    #[serde(default)]
+    pub conditions: Vec<ClusterCondition>,
+}
+
+/// A status condition reported by the MegaDB operator on its custom resource.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClusterCondition {
+    #[serde(rename = "type")]
+    pub condition_type: String,
+    /// "True", "False" or "Unknown"
+    pub status: String,
+    #[serde(default)]
+    pub reason: String,
+    #[serde(default)]
+    pub message: String,
+    /// RFC 3339 timestamp
+    pub last_transition_time: Option<String>,
+}
+
+/// A MegaDB custom resource (megadb.io/v1alpha1) as listed in the UI.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MegaDbSummary {
+    pub name: String,
+    pub namespace: String,
+    pub version: String,
+    pub replicas: i32,
+    pub ready_replicas: i32,
+    pub phase: String,
+    pub conditions: Vec<ClusterCondition>,
 }
 
 /// Status of a single pod in the cluster.
@@ -37,6 +70,18 @@ pub struct VolumeInfo {
     pub capacity_bytes: u64,
     pub used_bytes: u64,
     pub bound_pod: String,
+    /// Storage class the claim was provisioned from; empty when it has none.
+    #[serde(default)]
+    pub storage_class: String,
+    /// Whether the storage class has `allowVolumeExpansion`.
+    #[serde(default)]
+    pub expandable: bool,
+}
+
+/// Request to grow a PVC to a new size.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeResizeRequest {
+    pub size_gib: u64,
 }
 
 /// Request to scale the cluster.
@@ -45,6 +90,56 @@ pub struct ScaleRequest {
     pub replicas: i32,
 }
 
+/// Outcome of a scale request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScaleResponse {
+    /// Desired worker replicas before the request.
+    pub previous_replicas: i32,
+    /// Desired worker replicas now set on the StatefulSet.
+    pub replicas: i32,
+    /// Worker pods currently running; converges to `replicas`.
+    pub current_replicas: i32,
+}
+
+/// A pod lifecycle operation started from the dashboard.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum PodAction {
+    /// Restart every worker, one at a time, by bumping the StatefulSet's
+    /// pod template annotation.
+    RollingRestart,
+    /// Delete one pod; its controller recreates it.
+    DeletePod { pod: String },
+    /// Ask MegaDB to move a worker's work elsewhere, then delete the pod.
+    DrainWorker { pod: String },
+}
+
+impl PodAction {
+    /// Title of the confirmation dialog.
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::RollingRestart => "Rolling Restart",
+            Self::DeletePod { .. } => "Delete Pod",
+            Self::DrainWorker { .. } => "Drain Worker",
+        }
+    }
+
+    /// What the action will do, for the confirmation dialog.
+    pub fn description(&self) -> String {
+        match self {
+            Self::RollingRestart => {
+                "Restart all workers one at a time? Queries on a restarting worker fail.".into()
+            }
+            Self::DeletePod { pod } => {
+                format!("Delete {pod}? Its StatefulSet recreates it; running queries on it fail.")
+            }
+            Self::DrainWorker { pod } => format!(
+                "Drain {pod}? MegaDB stops scheduling work on it, then the pod is deleted and recreated."
+            ),
+        }
+    }
+}
+
 /// KEDA autoscaling configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KedaConfig {
@@ -53,6 +148,123 @@ pub struct KedaConfig {
     pub max_replicas: i32,
 }
 
+impl Default for KedaConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_replicas: 1,
+            max_replicas: 32,
+        }
+    }
+}
+
+impl KedaConfig {
+    /// Check a requested replica count against the configured bounds.
+    pub fn validate_replicas(&self, replicas: i32) -> Result<(), String> {
+        if replicas < self.min_replicas || replicas > self.max_replicas {
+            return Err(format!(
+                "Replicas must be between {} and {}, got {replicas}",
+                self.min_replicas, self.max_replicas
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Prometheus server KEDA queries for the generated worker triggers.
+pub const KEDA_PROMETHEUS_ADDRESS: &str = "http://prometheus:9090";
+
+/// A Prometheus trigger of the worker ScaledObject. KEDA adds a worker for
+/// every `threshold` of the query's value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScalingTrigger {
+    pub name: String,
+    pub query: String,
+    pub threshold: f64,
+}
+
+impl ScalingTrigger {
+    /// Triggers generated for new clusters: query rate and queue depth.
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self {
+                name: "qps".into(),
+                query: "sum(rate(megadb_queries_total[1m]))".into(),
+                threshold: 100.0,
+            },
+            Self {
+                name: "queue-depth".into(),
+                query: "sum(megadb_query_queue_depth)".into(),
+                threshold: 10.0,
+            },
+        ]
+    }
+}
+
+/// A trigger of the live ScaledObject with the value KEDA last saw.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TriggerStatus {
+    #[serde(flatten)]
+    pub trigger: ScalingTrigger,
+    /// Per-replica value from the HPA; `None` until KEDA has reported one.
+    pub current_value: Option<f64>,
+}
+
+/// A replica change made by the autoscaler, or KEDA (de)activating it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScalingEvent {
+    pub time: Option<DateTime<Utc>>,
+    /// New replica count, for rescales.
+    pub replicas: Option<i32>,
+    pub reason: String,
+    pub message: String,
+}
+
+/// The worker ScaledObject as shown in the scaling panel.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AutoscalerStatus {
+    pub name: String,
+    pub min_replicas: i32,
+    pub max_replicas: i32,
+    /// Replicas the HPA last saw and wants.
+    pub current_replicas: i32,
+    pub desired_replicas: i32,
+    /// KEDA's `Active` condition: some trigger is above its activation value.
+    pub active: bool,
+    pub triggers: Vec<TriggerStatus>,
+    /// Newest first.
+    pub history: Vec<ScalingEvent>,
+}
+
+/// Changes to the live ScaledObject from the scaling panel.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AutoscalerUpdate {
+    pub min_replicas: i32,
+    pub max_replicas: i32,
+    /// New threshold per trigger name; triggers not listed are unchanged.
+    #[serde(default)]
+    pub thresholds: BTreeMap<String, f64>,
+}
+
+impl AutoscalerUpdate {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.min_replicas < 0 || self.min_replicas > self.max_replicas {
+            return Err(format!(
+                "Need 0 <= min <= max replicas, got {}..{}",
+                self.min_replicas, self.max_replicas
+            ));
+        }
+        if let Some((name, _)) = self
+            .thresholds
+            .iter()
+            .find(|(_, t)| t.is_nan() || **t <= 0.0)
+        {
+            return Err(format!("Threshold of trigger {name} must be positive"));
+        }
+        Ok(())
+    }
+}
+
 impl PodInfo {
     pub fn is_ready(&self) -> bool {
         self.status == "Running"
@@ -80,3 +292,102 @@ impl PodInfo {
         }
     }
 }
+
+const GIB: u64 = 1 << 30;
+
+impl VolumeInfo {
+    pub fn usage_percent(&self) -> f64 {
+        if self.capacity_bytes > 0 {
+            self.used_bytes as f64 / self.capacity_bytes as f64 * 100.0
+        } else {
+            0.0
+        }
+    }
+
+    /// Size to offer when the volume runs full: half as large again,
+    /// rounded up to a whole GiB.
+    pub fn suggested_size_gib(&self) -> u64 {
+        (self.capacity_bytes + self.capacity_bytes / 2).div_ceil(GIB)
+    }
+
+    /// Kubernetes can only grow a volume, and only if its class allows it.
+    pub fn validate_resize(&self, size_gib: u64) -> Result<(), String> {
+        if !self.expandable {
+            return Err(format!(
+                "Storage class {:?} of {} does not allow volume expansion",
+                self.storage_class, self.name
+            ));
+        }
+        if size_gib.saturating_mul(GIB) <= self.capacity_bytes {
+            return Err(format!(
+                "{} is already {} GiB; volumes can only grow",
+                self.name,
+                self.capacity_bytes / GIB
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_replicas() {
+        let keda = KedaConfig {
+            enabled: true,
+            min_replicas: 2,
+            max_replicas: 8,
+        };
+        assert!(keda.validate_replicas(2).is_ok());
+        assert!(keda.validate_replicas(8).is_ok());
+        assert!(keda.validate_replicas(1).is_err());
+        assert!(keda.validate_replicas(9).is_err());
+    }
+
+    #[test]
+    fn test_volume_resize() {
+        let cache = VolumeInfo {
+            name: "cache-0".into(),
+            access_mode: "ReadWriteOnce".into(),
+            capacity_bytes: 100 * GIB,
+            used_bytes: 72 * GIB,
+            bound_pod: "megadb-worker-0".into(),
+            storage_class: "gp3".into(),
+            expandable: true,
+        };
+        assert_eq!(cache.usage_percent(), 72.0);
+        assert_eq!(cache.suggested_size_gib(), 150);
+        assert!(cache.validate_resize(150).is_ok());
+        assert!(cache.validate_resize(100).is_err());
+
+        let fixed = VolumeInfo {
+            expandable: false,
+            ..cache
+        };
+        assert!(fixed.validate_resize(150).is_err());
+    }
+
+    #[test]
+    fn test_validate_autoscaler_update() {
+        let update = AutoscalerUpdate {
+            min_replicas: 1,
+            max_replicas: 8,
+            thresholds: BTreeMap::from([("qps".to_string(), 150.0)]),
+        };
+        assert!(update.validate().is_ok());
+
+        let inverted = AutoscalerUpdate {
+            min_replicas: 9,
+            ..update.clone()
+        };
+        assert!(inverted.validate().is_err());
+
+        let zero = AutoscalerUpdate {
+            thresholds: BTreeMap::from([("qps".to_string(), 0.0)]),
+            ..update
+        };
+        assert!(zero.validate().is_err());
+    }
+}
@@ -1,8 +1,11 @@
 pub mod advisor;
 pub mod configurator;
 pub mod connection;
+pub mod exec;
 pub mod explain;
+pub mod export;
 pub mod k8s;
+pub mod logs;
 pub mod metrics;
 pub mod query;
 pub mod schema;
@@ -0,0 +1,155 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Which logs to stream from a pod (`GET /proxy/k8s/pods/{name}/logs`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogQuery {
+    /// Container to read; the pod's only (or default) container when unset.
+    pub container: Option<String>,
+    /// Keep the stream open and send new lines as they are written.
+    pub follow: bool,
+    /// Start with only the last N lines.
+    pub tail_lines: Option<i64>,
+    /// Read the previous, terminated container instead, e.g. after a restart.
+    pub previous: bool,
+    /// Only lines newer than this many seconds.
+    pub since_seconds: Option<i64>,
+    /// Only lines written at or after this time. Ignored when `since_seconds` is set.
+    pub since_time: Option<DateTime<Utc>>,
+}
+
+impl LogQuery {
+    /// URL query string (without the leading `?`) for the set options.
+    pub fn to_query_string(&self) -> String {
+        let mut params = Vec::new();
+        if let Some(container) = &self.container {
+            params.push(format!("container={}", encode_component(container)));
+        }
+        if self.follow {
+            params.push("follow=true".to_string());
+        }
+        if let Some(n) = self.tail_lines {
+            params.push(format!("tail_lines={n}"));
+        }
+        if self.previous {
+            params.push("previous=true".to_string());
+        }
+        if let Some(s) = self.since_seconds {
+            params.push(format!("since_seconds={s}"));
+        }
+        if let Some(t) = self.since_time {
+            params.push(format!(
+                "since_time={}",
+                encode_component(&t.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+            ));
+        }
+        params.join("&")
+    }
+}
+
+/// Percent-encode everything except RFC 3986 unreserved characters.
+fn encode_component(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+/// One NDJSON line of a log stream.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LogChunk {
+    Line {
+        text: String,
+    },
+    /// The stream failed after it started; no more lines follow.
+    Error {
+        message: String,
+    },
+}
+
+/// Severity of a log line, guessed from its text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    /// Level named by the first level-like word of `line`, matching both
+    /// plain (`WARN ...`) and structured (`level=warn`, `"level":"warn"`) logs.
+    pub fn detect(line: &str) -> Option<Self> {
+        line.split(|c: char| !c.is_ascii_alphabetic())
+            .filter(|word| !word.is_empty())
+            .take(8)
+            .find_map(|word| match word.to_ascii_uppercase().as_str() {
+                "ERROR" | "ERR" | "FATAL" | "PANIC" => Some(Self::Error),
+                "WARN" | "WARNING" => Some(Self::Warn),
+                "INFO" => Some(Self::Info),
+                "DEBUG" => Some(Self::Debug),
+                "TRACE" => Some(Self::Trace),
+                _ => None,
+            })
+    }
+
+    /// CSS modifier for highlighting.
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            Self::Error => "log-line--error",
+            Self::Warn => "log-line--warn",
+            Self::Info => "log-line--info",
+            Self::Debug => "log-line--debug",
+            Self::Trace => "log-line--trace",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_level() {
+        assert_eq!(
+            LogLevel::detect("2026-01-01T00:00:00Z ERROR worker lost"),
+            Some(LogLevel::Error)
+        );
+        assert_eq!(
+            LogLevel::detect(r#"{"ts":1,"level":"warn","msg":"slow"}"#),
+            Some(LogLevel::Warn)
+        );
+        assert_eq!(
+            LogLevel::detect("ts=1 level=debug msg=tick"),
+            Some(LogLevel::Debug)
+        );
+        // Only whole words count
+        assert_eq!(LogLevel::detect("errors=0 informational"), None);
+        assert_eq!(LogLevel::detect(""), None);
+    }
+
+    #[test]
+    fn test_log_query_string() {
+        assert_eq!(LogQuery::default().to_query_string(), "");
+        let query = LogQuery {
+            container: Some("megadb".into()),
+            follow: true,
+            tail_lines: Some(500),
+            previous: true,
+            since_seconds: None,
+            since_time: "2026-01-01T00:00:00Z".parse().ok(),
+        };
+        assert_eq!(
+            query.to_query_string(),
+            "container=megadb&follow=true&tail_lines=500&previous=true\
+             &since_time=2026-01-01T00%3A00%3A00Z"
+        );
+    }
+}
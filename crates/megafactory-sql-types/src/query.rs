@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::connection::ConnectionConfig;
+
 /// Request to execute a SQL query against MegaDB.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QueryRequest {
@@ -7,6 +9,9 @@ pub struct QueryRequest {
     pub database: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<u64>,
+    /// Client-assigned id for this execution, used to cancel it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query_id: Option<uuid::Uuid>,
 }
 
 /// Result of a SQL query execution.
@@ -21,13 +26,47 @@ pub struct QueryResult {
 }
 
 /// Column metadata in a query result.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct QueryColumn {
     pub name: String,
     pub data_type: String,
     pub nullable: bool,
 }
 
+/// One NDJSON line of a streamed query result.
+///
+/// A stream is `Columns`, then zero or more `Rows` batches, then `Done` or `Error`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum QueryResultChunk {
+    Columns {
+        columns: Vec<QueryColumn>,
+    },
+    Rows {
+        rows: Vec<Vec<serde_json::Value>>,
+    },
+    Done {
+        row_count: u64,
+        execution_time_ms: u64,
+        /// The stream was cut off at the request's row limit.
+        #[serde(default)]
+        truncated: bool,
+    },
+    Error {
+        message: String,
+    },
+}
+
+/// Body of `POST /proxy/megadb/query/stream`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamQueryRequest {
+    #[serde(flatten)]
+    pub request: QueryRequest,
+    /// Connection to run against; the server's default MegaDB when `None`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connection: Option<ConnectionConfig>,
+}
+
 /// A saved query with name and metadata.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SavedQuery {
@@ -67,4 +106,96 @@ impl QueryResult {
     pub fn is_ok(&self) -> bool {
         self.error.is_none()
     }
+
+    /// Fold a streamed chunk into this result, keeping at most `max_rows` rows.
+    ///
+    /// Rows beyond `max_rows` are counted but not retained.
+    pub fn apply_chunk(&mut self, chunk: QueryResultChunk, max_rows: usize) {
+        match chunk {
+            QueryResultChunk::Columns { columns } => self.columns = columns,
+            QueryResultChunk::Rows { rows } => {
+                self.row_count += rows.len() as u64;
+                let room = max_rows.saturating_sub(self.rows.len());
+                self.rows.extend(rows.into_iter().take(room));
+            }
+            QueryResultChunk::Done {
+                row_count,
+                execution_time_ms,
+                ..
+            } => {
+                self.row_count = self.row_count.max(row_count);
+                self.execution_time_ms = execution_time_ms;
+            }
+            QueryResultChunk::Error { message } => self.error = Some(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(n: usize) -> QueryResultChunk {
+        QueryResultChunk::Rows {
+            rows: (0..n).map(|i| vec![serde_json::json!(i)]).collect(),
+        }
+    }
+
+    #[test]
+    fn test_chunk_ndjson_round_trip() {
+        let chunk = QueryResultChunk::Done {
+            row_count: 3,
+            execution_time_ms: 12,
+            truncated: true,
+        };
+        let line = serde_json::to_string(&chunk).unwrap();
+        assert!(line.starts_with(r#"{"type":"done""#));
+        assert!(!line.contains('\n'));
+        let parsed: QueryResultChunk = serde_json::from_str(&line).unwrap();
+        assert_eq!(parsed, chunk);
+    }
+
+    #[test]
+    fn test_apply_chunks_bounds_retained_rows() {
+        let mut result = QueryResult::empty();
+        result.apply_chunk(
+            QueryResultChunk::Columns {
+                columns: vec![QueryColumn {
+                    name: "id".into(),
+                    data_type: "Int64".into(),
+                    nullable: false,
+                }],
+            },
+            5,
+        );
+        result.apply_chunk(rows(3), 5);
+        result.apply_chunk(rows(4), 5);
+        assert_eq!(result.rows.len(), 5);
+        assert_eq!(result.row_count, 7);
+
+        result.apply_chunk(
+            QueryResultChunk::Done {
+                row_count: 7,
+                execution_time_ms: 40,
+                truncated: false,
+            },
+            5,
+        );
+        assert_eq!(result.execution_time_ms, 40);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_apply_error_chunk() {
+        let mut result = QueryResult::empty();
+        result.apply_chunk(rows(2), 10);
+        result.apply_chunk(
+            QueryResultChunk::Error {
+                message: "boom".into(),
+            },
+            10,
+        );
+        assert_eq!(result.error.as_deref(), Some("boom"));
+        assert_eq!(result.rows.len(), 2);
+    }
 }
@@ -1,5 +1,18 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+/// Topic carrying `MetricsUpdate`.
+pub const METRICS_TOPIC: &str = "metrics";
+/// Topic carrying `K8sEvent`.
+pub const K8S_TOPIC: &str = "k8s";
+/// Keyed by query id: `query:<query_id>` carries that query's progress.
+pub const QUERY_TOPIC: &str = "query";
+
+/// Topic name for progress of a single query.
+pub fn query_topic(query_id: impl std::fmt::Display) -> String {
+    format!("{QUERY_TOPIC}:{query_id}")
+}
+
 /// Messages sent from the client to the WebSocket server.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", content = "payload")]
@@ -20,12 +33,103 @@ pub enum WsServerMessage {
     Error { message: String },
 }
 
-/// A Kubernetes cluster event.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A Kubernetes cluster event: a core/v1 Event, a pod or MegaDB change seen
+/// by the server's watchers, or a change made through the dashboard.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct K8sEvent {
+    /// Position in the server's event log, increasing; 0 until recorded.
+    #[serde(default)]
+    pub id: u64,
+    /// "Normal"/"Warning" for core/v1 Events, otherwise what happened
+    /// ("MODIFIED", "SCALED", ...).
     pub event_type: String,
+    /// Name of the involved object, usually a pod.
     pub pod_name: String,
     pub message: String,
+    #[serde(default)]
+    pub reason: String,
+    /// Kind of the involved object ("Pod", "MegaDB", "StatefulSet", ...).
+    #[serde(default)]
+    pub object_kind: String,
+    /// How many times Kubernetes has seen this event.
+    #[serde(default = "default_count")]
+    pub count: i32,
+    pub first_timestamp: Option<DateTime<Utc>>,
+    pub last_timestamp: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub severity: EventSeverity,
+    /// UID of the core/v1 Event; a recorded update (e.g. a count bump)
+    /// replaces earlier copies with the same UID.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uid: Option<String>,
+}
+
+fn default_count() -> i32 {
+    1
+}
+
+impl K8sEvent {
+    /// A one-off informational event happening now.
+    pub fn new(
+        event_type: impl Into<String>,
+        object_kind: impl Into<String>,
+        object: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            id: 0,
+            event_type: event_type.into(),
+            pod_name: object.into(),
+            message: message.into(),
+            reason: String::new(),
+            object_kind: object_kind.into(),
+            count: 1,
+            first_timestamp: Some(now),
+            last_timestamp: Some(now),
+            severity: EventSeverity::Info,
+            uid: None,
+        }
+    }
+}
+
+/// How much attention an event needs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum EventSeverity {
+    #[default]
+    Info,
+    Warning,
+    Error,
+}
+
+impl EventSeverity {
+    /// Severity of a core/v1 Event: Kubernetes only has Normal and Warning,
+    /// so warnings whose reason means something failed are raised to Error.
+    pub fn classify(event_type: &str, reason: &str) -> Self {
+        const FAILURE_REASONS: [&str; 8] = [
+            "Failed",
+            "BackOff",
+            "FailedScheduling",
+            "FailedMount",
+            "FailedAttachVolume",
+            "FailedCreate",
+            "OOMKilling",
+            "Evicted",
+        ];
+        match event_type {
+            "Warning" if FAILURE_REASONS.contains(&reason) => Self::Error,
+            "Warning" => Self::Warning,
+            _ => Self::Info,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Info => "Info",
+            Self::Warning => "Warning",
+            Self::Error => "Error",
+        }
+    }
 }
 
 /// Progress update for a running query.
@@ -36,3 +140,37 @@ pub struct QueryProgressEvent {
     pub rows_processed: u64,
     pub stage: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_severity() {
+        assert_eq!(
+            EventSeverity::classify("Normal", "Scheduled"),
+            EventSeverity::Info
+        );
+        assert_eq!(
+            EventSeverity::classify("Warning", "Unhealthy"),
+            EventSeverity::Warning
+        );
+        assert_eq!(
+            EventSeverity::classify("Warning", "BackOff"),
+            EventSeverity::Error
+        );
+        assert!(EventSeverity::Error > EventSeverity::Warning);
+    }
+
+    #[test]
+    fn test_k8s_event_defaults() {
+        // Events from older servers carry only the original three fields
+        let event: K8sEvent = serde_json::from_str(
+            r#"{"event_type":"MODIFIED","pod_name":"megadb-worker-0","message":"ok"}"#,
+        )
+        .unwrap();
+        assert_eq!((event.id, event.count), (0, 1));
+        assert_eq!(event.severity, EventSeverity::Info);
+        assert!(event.last_timestamp.is_none());
+    }
+}
@@ -29,6 +29,7 @@ docker buildx build -f docker/Dockerfile -t megafactory-sql:latest --load .
 
 # 4. Deploy to K8s
 echo "Deploying to K8s..."
+kubectl apply -f k8s/rbac.yaml
 kubectl apply -f k8s/deployment.yaml
 kubectl apply -f k8s/service.yaml
 